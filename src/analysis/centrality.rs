@@ -0,0 +1,336 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
+};
+
+use crate::{
+    graph::{Attributes, Edge, Graph, GraphNode},
+    render::sizes::{NodeSizing, SizeBy},
+};
+
+/// Attribute betweenness centrality is written to.
+const BETWEENNESS_ATTRIBUTE: &str = "betweenness";
+/// Attribute PageRank is written to.
+const PAGERANK_ATTRIBUTE: &str = "pagerank";
+
+/// Computes the betweenness centrality of every node with Brandes' algorithm
+/// on a background thread, counting hops and following edge direction in a
+/// directed graph. Once done, each node's share of the shortest paths
+/// between all other pairs, from 0 to 1, goes to its `betweenness`
+/// attribute, ready for [`ColorMapping`](crate::ColorMapping) or
+/// [`SizeBy::Attribute`](crate::SizeBy::Attribute). Asking again while a
+/// run is going starts it over.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct ComputeBetweenness;
+
+/// Fraction of a betweenness run that is done, there only while one is
+/// going.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct BetweennessProgress(pub f32);
+
+/// Settings for [`ComputePageRank`].
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PageRank {
+    /// Chance of following an edge rather than jumping to a random node.
+    pub damping: f32,
+    /// Stop once no score moves by more than this in an iteration.
+    pub tolerance: f32,
+    pub max_iterations: usize,
+}
+
+impl Default for PageRank {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        }
+    }
+}
+
+/// Ranks nodes with PageRank by power iteration, following edges in
+/// proportion to their [`Edge::weight`], both ways in an undirected graph.
+/// Scores sum to 1 and go to each node's `pagerank` attribute, which nodes
+/// are then sized by.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct ComputePageRank;
+
+/// The betweenness run in flight.
+#[derive(Resource)]
+struct Running {
+    task: Task<HashMap<Entity, f32>>,
+    /// Source nodes finished so far, out of `total`.
+    done: Arc<AtomicUsize>,
+    total: usize,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<PageRank>()
+        .add_event::<ComputeBetweenness>()
+        .add_event::<ComputePageRank>()
+        .add_systems(
+            Update,
+            (
+                (
+                    start_betweenness.run_if(on_event::<ComputeBetweenness>),
+                    finish_betweenness.run_if(resource_exists::<Running>),
+                )
+                    .chain(),
+                compute_pagerank.run_if(on_event::<ComputePageRank>),
+            ),
+        );
+}
+
+fn start_betweenness(
+    mut events: EventReader<ComputeBetweenness>,
+    mut cmd: Commands,
+    graph: Res<Graph>,
+) {
+    events.clear();
+    let mut nodes: Vec<Entity> = graph.nodes().collect();
+    nodes.sort();
+    let index: HashMap<Entity, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let adjacency: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|&node| {
+            let next: Vec<_> = if graph.is_directed() {
+                graph.successors(node).collect()
+            } else {
+                graph.neighbors(node).collect()
+            };
+            // Parallel edges and self-loops add no shortest paths.
+            let mut next: Vec<usize> = next
+                .into_iter()
+                .filter(|&(n, _)| n != node)
+                .filter_map(|(n, _)| index.get(&n).copied())
+                .collect();
+            next.sort_unstable();
+            next.dedup();
+            next
+        })
+        .collect();
+    let done = Arc::new(AtomicUsize::new(0));
+    let counter = done.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let scores = betweenness(&adjacency, &counter);
+        nodes.into_iter().zip(scores).collect()
+    });
+    cmd.insert_resource(Running {
+        task,
+        done,
+        total: graph.node_count(),
+    });
+    cmd.insert_resource(BetweennessProgress(0.));
+}
+
+fn finish_betweenness(
+    mut cmd: Commands,
+    mut running: ResMut<Running>,
+    mut progress: ResMut<BetweennessProgress>,
+    mut nodes: Query<&mut Attributes, With<GraphNode>>,
+) {
+    let Some(scores) = block_on(future::poll_once(&mut running.task)) else {
+        let done = running.done.load(Ordering::Relaxed);
+        progress.0 = done as f32 / running.total.max(1) as f32;
+        return;
+    };
+    // Nodes removed in the meantime are simply gone.
+    for (node, score) in scores {
+        if let Ok(mut attributes) = nodes.get_mut(node) {
+            attributes
+                .0
+                .insert(BETWEENNESS_ATTRIBUTE.into(), format!("{score:.6}"));
+        }
+    }
+    cmd.remove_resource::<Running>();
+    cmd.remove_resource::<BetweennessProgress>();
+}
+
+/// Brandes' algorithm over `adjacency`: a breadth-first search from every
+/// node, then the dependencies accumulated back along the shortest-path
+/// DAG. Scores are normalised by the number of ordered pairs each node could
+/// lie between, which also halves undirected scores, whose paths are found
+/// from both ends. Bumps `done` after every source.
+fn betweenness(adjacency: &[Vec<usize>], done: &AtomicUsize) -> Vec<f32> {
+    let n = adjacency.len();
+    let mut centrality = vec![0f64; n];
+    let mut order = Vec::with_capacity(n);
+    let mut queue = VecDeque::new();
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut paths = vec![0f64; n];
+    let mut distance = vec![usize::MAX; n];
+    let mut dependency = vec![0f64; n];
+    for source in 0..n {
+        order.clear();
+        for p in &mut predecessors {
+            p.clear();
+        }
+        paths.fill(0.);
+        distance.fill(usize::MAX);
+        dependency.fill(0.);
+        paths[source] = 1.;
+        distance[source] = 0;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &w in &adjacency[v] {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        for &w in order.iter().rev() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1. + dependency[w]);
+            }
+            if w != source {
+                centrality[w] += dependency[w];
+            }
+        }
+        done.fetch_add(1, Ordering::Relaxed);
+    }
+    let pairs = (n.saturating_sub(1) * n.saturating_sub(2)) as f64;
+    let scale = if pairs > 0. { 1. / pairs } else { 0. };
+    centrality.into_iter().map(|c| (c * scale) as f32).collect()
+}
+
+fn compute_pagerank(
+    mut events: EventReader<ComputePageRank>,
+    graph: Res<Graph>,
+    edges: Query<&Edge>,
+    mut nodes: Query<&mut Attributes, With<GraphNode>>,
+    settings: Res<PageRank>,
+    sizing: Option<ResMut<NodeSizing>>,
+) {
+    events.clear();
+    let mut order: Vec<Entity> = graph.nodes().collect();
+    order.sort();
+    let index: HashMap<Entity, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut links: Vec<Vec<(usize, f64)>> = vec![Vec::new(); order.len()];
+    for (edge, source, target) in graph.edges() {
+        let weight = edges.get(edge).map_or(1., |e| e.weight).max(0.) as f64;
+        let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) else {
+            continue;
+        };
+        links[a].push((b, weight));
+        if !graph.is_directed() && a != b {
+            links[b].push((a, weight));
+        }
+    }
+    let ranks = pagerank(&links, &settings);
+    for (node, rank) in order.into_iter().zip(ranks) {
+        if let Ok(mut attributes) = nodes.get_mut(node) {
+            attributes
+                .0
+                .insert(PAGERANK_ATTRIBUTE.into(), format!("{rank:.6}"));
+        }
+    }
+    if let Some(mut sizing) = sizing {
+        sizing.by = Some(SizeBy::Attribute(PAGERANK_ATTRIBUTE.into()));
+    }
+}
+
+/// Power iteration over weighted outgoing `links`. Nodes without any spread
+/// their rank over every node, as the random jump does.
+fn pagerank(links: &[Vec<(usize, f64)>], settings: &PageRank) -> Vec<f32> {
+    let n = links.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let damping = settings.damping.clamp(0., 1.) as f64;
+    let out: Vec<f64> = links
+        .iter()
+        .map(|l| l.iter().map(|&(_, w)| w).sum())
+        .collect();
+    let mut rank = vec![1. / n as f64; n];
+    let mut next = vec![0.; n];
+    for _ in 0..settings.max_iterations {
+        let dangling: f64 = (0..n).filter(|&i| out[i] <= 0.).map(|i| rank[i]).sum();
+        next.fill((1. - damping + damping * dangling) / n as f64);
+        for (i, l) in links.iter().enumerate() {
+            if out[i] > 0. {
+                for &(j, w) in l {
+                    next[j] += damping * rank[i] * w / out[i];
+                }
+            }
+        }
+        let delta = rank
+            .iter()
+            .zip(&next)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0., f64::max);
+        std::mem::swap(&mut rank, &mut next);
+        if delta < settings.tolerance as f64 {
+            break;
+        }
+    }
+    rank.into_iter().map(|r| r as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adjacency of an undirected graph with `n` nodes.
+    fn undirected(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); n];
+        for &(a, b) in edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+        adjacency
+    }
+
+    fn assert_scores(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn path() {
+        let adjacency = undirected(4, &[(0, 1), (1, 2), (2, 3)]);
+        let done = AtomicUsize::new(0);
+        // The inner nodes each lie between 2 of the 3 other pairs.
+        assert_scores(&betweenness(&adjacency, &done), &[0., 2. / 3., 2. / 3., 0.]);
+        assert_eq!(done.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn star() {
+        let adjacency = undirected(5, &[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        let scores = betweenness(&adjacency, &AtomicUsize::new(0));
+        assert_scores(&scores, &[1., 0., 0., 0., 0.]);
+    }
+
+    #[test]
+    fn directed_path() {
+        let adjacency = vec![vec![1], vec![2], vec![]];
+        let scores = betweenness(&adjacency, &AtomicUsize::new(0));
+        // Only 0 -> 2 of the 2 ordered pairs around node 1 passes through it.
+        assert_scores(&scores, &[0., 0.5, 0.]);
+    }
+
+    #[test]
+    fn splits_credit_between_equal_paths() {
+        // A square: 0 and 2 are joined through 1 and through 3.
+        let adjacency = undirected(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let scores = betweenness(&adjacency, &AtomicUsize::new(0));
+        assert_scores(&scores, &[1. / 6.; 4]);
+    }
+}
@@ -0,0 +1,283 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    graph::{Attributes, Edge, Graph, GraphNode},
+    render::colors::ColorMapping,
+    stats::GraphStats,
+};
+
+/// Attribute the community of each node is written to.
+const COMMUNITY_ATTRIBUTE: &str = "community";
+
+/// Community a node was put in by [`DetectCommunities`], numbered from the
+/// largest community down.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct Community(pub usize);
+
+/// Splits the graph into communities with the Louvain method, treating it as
+/// undirected and weighing edges by [`Edge::weight`]. Every node gets a
+/// [`Community`] and a `community` attribute, nodes are colored by it, and
+/// the modularity of the split goes to [`GraphStats::modularity`].
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct DetectCommunities;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<Community>()
+        .add_event::<DetectCommunities>()
+        .add_systems(
+            Update,
+            detect_communities.run_if(on_event::<DetectCommunities>),
+        );
+}
+
+fn detect_communities(
+    mut events: EventReader<DetectCommunities>,
+    mut cmd: Commands,
+    graph: Res<Graph>,
+    edges: Query<&Edge>,
+    mut nodes: Query<&mut Attributes, With<GraphNode>>,
+    mut stats: ResMut<GraphStats>,
+    mapping: Option<ResMut<ColorMapping>>,
+) {
+    events.clear();
+    let (communities, modularity) = louvain(&graph, |e| edges.get(e).map_or(1., |e| e.weight));
+    let count = communities.values().max().map_or(0, |&c| c + 1);
+    for (&node, &community) in &communities {
+        cmd.entity(node).insert(Community(community));
+        if let Ok(mut attributes) = nodes.get_mut(node) {
+            attributes
+                .0
+                .insert(COMMUNITY_ATTRIBUTE.into(), community.to_string());
+        }
+    }
+    stats.modularity = Some(modularity);
+    if let Some(mut mapping) = mapping {
+        mapping.attribute = Some(COMMUNITY_ATTRIBUTE.into());
+        mapping.categorical = true;
+    }
+    info!("found {count} communities, modularity {modularity:.3}");
+}
+
+/// Louvain community detection: nodes move greedily to the neighbouring
+/// community that raises modularity most, then each community collapses
+/// into a single node and the process repeats on the smaller graph until
+/// nothing moves. Returns each node's community, numbered by size, and the
+/// modularity reached.
+pub(crate) fn louvain(
+    graph: &Graph,
+    weight: impl Fn(Entity) -> f32,
+) -> (HashMap<Entity, usize>, f32) {
+    // Entity order keeps the result from depending on hashing.
+    let mut nodes: Vec<Entity> = graph.nodes().collect();
+    nodes.sort();
+    let index: HashMap<Entity, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut level = Level::new(nodes.len());
+    for (edge, source, target) in graph.edges() {
+        if let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) {
+            level.add_edge(a, b, weight(edge).max(0.) as f64);
+        }
+    }
+
+    // Community of every original node, refined level by level.
+    let mut membership: Vec<usize> = (0..nodes.len()).collect();
+    loop {
+        let (moved, communities) = level.move_nodes();
+        for m in &mut membership {
+            *m = communities[*m];
+        }
+        if !moved {
+            break;
+        }
+        level = level.aggregate(&communities);
+    }
+
+    let modularity = level.modularity();
+    let mut sizes: HashMap<usize, usize> = HashMap::default();
+    for &m in &membership {
+        *sizes.entry(m).or_default() += 1;
+    }
+    // Largest first, ties broken by first member.
+    let mut order: Vec<usize> = sizes.keys().copied().collect();
+    order.sort_by_key(|c| {
+        (
+            std::cmp::Reverse(sizes[c]),
+            membership.iter().position(|m| m == c),
+        )
+    });
+    let rank: HashMap<usize, usize> = order.into_iter().enumerate().map(|(i, c)| (c, i)).collect();
+    let communities = nodes
+        .into_iter()
+        .zip(membership)
+        .map(|(node, m)| (node, rank[&m]))
+        .collect();
+    (communities, modularity as f32)
+}
+
+/// One level of the Louvain hierarchy: a weighted graph whose nodes are the
+/// communities of the level below.
+struct Level {
+    /// Outgoing arcs per node; every undirected edge is stored both ways and
+    /// a self-loop once, so a node's degree is the sum of its arcs.
+    arcs: Vec<Vec<(usize, f64)>>,
+}
+
+impl Level {
+    fn new(n: usize) -> Self {
+        Self {
+            arcs: vec![Vec::new(); n],
+        }
+    }
+
+    fn add_edge(&mut self, a: usize, b: usize, w: f64) {
+        self.arcs[a].push((b, w));
+        if a != b {
+            self.arcs[b].push((a, w));
+        }
+    }
+
+    fn degree(&self, node: usize) -> f64 {
+        self.arcs[node].iter().map(|&(_, w)| w).sum()
+    }
+
+    /// Moves nodes between communities until no move helps, returning
+    /// whether any node moved and each node's community, renumbered from 0.
+    fn move_nodes(&self) -> (bool, Vec<usize>) {
+        let n = self.arcs.len();
+        let degrees: Vec<f64> = (0..n).map(|i| self.degree(i)).collect();
+        let total: f64 = degrees.iter().sum();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut totals = degrees.clone();
+        let mut moved = false;
+        if total > 0. {
+            let mut links: HashMap<usize, f64> = HashMap::default();
+            loop {
+                let mut improved = false;
+                for i in 0..n {
+                    links.clear();
+                    for &(j, w) in &self.arcs[i] {
+                        if j != i {
+                            *links.entry(community[j]).or_default() += w;
+                        }
+                    }
+                    let own = community[i];
+                    totals[own] -= degrees[i];
+                    let gain = |c: usize, w: f64| w - totals[c] * degrees[i] / total;
+                    let mut best = (own, gain(own, links.get(&own).copied().unwrap_or(0.)));
+                    let mut candidates: Vec<_> = links.iter().map(|(&c, &w)| (c, w)).collect();
+                    candidates.sort_by_key(|&(c, _)| c);
+                    for (c, w) in candidates {
+                        let g = gain(c, w);
+                        if g > best.1 + 1e-12 {
+                            best = (c, g);
+                        }
+                    }
+                    totals[best.0] += degrees[i];
+                    if best.0 != own {
+                        community[i] = best.0;
+                        improved = true;
+                        moved = true;
+                    }
+                }
+                if !improved {
+                    break;
+                }
+            }
+        }
+        let mut renumber: HashMap<usize, usize> = HashMap::default();
+        for c in &mut community {
+            let next = renumber.len();
+            *c = *renumber.entry(*c).or_insert(next);
+        }
+        (moved, community)
+    }
+
+    /// The graph of communities, with the edges inside each one folded into
+    /// a self-loop.
+    fn aggregate(&self, community: &[usize]) -> Self {
+        let n = community.iter().max().map_or(0, |&c| c + 1);
+        let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::default(); n];
+        for (i, arcs) in self.arcs.iter().enumerate() {
+            for &(j, w) in arcs {
+                *weights[community[i]].entry(community[j]).or_default() += w;
+            }
+        }
+        let arcs = weights
+            .into_iter()
+            .map(|w| {
+                let mut arcs: Vec<_> = w.into_iter().collect();
+                arcs.sort_by_key(|&(c, _)| c);
+                arcs
+            })
+            .collect();
+        Self { arcs }
+    }
+
+    /// Modularity of splitting the graph below into this level's nodes.
+    fn modularity(&self) -> f64 {
+        let total: f64 = (0..self.arcs.len()).map(|i| self.degree(i)).sum();
+        if total <= 0. {
+            return 0.;
+        }
+        (0..self.arcs.len())
+            .map(|i| {
+                let inside: f64 = self.arcs[i]
+                    .iter()
+                    .filter(|&&(j, _)| j == i)
+                    .map(|&(_, w)| w)
+                    .sum();
+                inside / total - (self.degree(i) / total).powi(2)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    /// Two 4-cliques joined by a single edge.
+    fn two_cliques() -> (App, Vec<Entity>) {
+        let mut app = App::new();
+        app.add_plugins(graph::plugin);
+        let world = app.world_mut();
+        let nodes: Vec<Entity> = (0..8).map(|_| world.spawn(GraphNode).id()).collect();
+        for clique in [&nodes[..4], &nodes[4..]] {
+            for (i, &a) in clique.iter().enumerate() {
+                for &b in &clique[i + 1..] {
+                    world.spawn(Edge::new(a, b));
+                }
+            }
+        }
+        world.spawn(Edge::new(nodes[3], nodes[4]));
+        (app, nodes)
+    }
+
+    #[test]
+    fn splits_two_cliques() {
+        let (app, nodes) = two_cliques();
+        let (communities, modularity) = louvain(app.world().resource::<Graph>(), |_| 1.);
+        let left = communities[&nodes[0]];
+        let right = communities[&nodes[4]];
+        assert_ne!(left, right);
+        assert!(nodes[..4].iter().all(|n| communities[n] == left));
+        assert!(nodes[4..].iter().all(|n| communities[n] == right));
+        // Each side holds 6 of the 13 edges and half the degree.
+        let expected = 2. * (6. / 13. - 0.25);
+        assert!((modularity - expected).abs() < 1e-5, "{modularity}");
+    }
+
+    #[test]
+    fn isolated_nodes_are_their_own_community() {
+        let mut app = App::new();
+        app.add_plugins(graph::plugin);
+        let world = app.world_mut();
+        let nodes: Vec<Entity> = (0..3).map(|_| world.spawn(GraphNode).id()).collect();
+        let (communities, modularity) = louvain(app.world().resource::<Graph>(), |_| 1.);
+        let mut seen: Vec<usize> = nodes.iter().map(|n| communities[n]).collect();
+        seen.sort_unstable();
+        assert_eq!(seen, [0, 1, 2]);
+        assert_eq!(modularity, 0.);
+    }
+}
@@ -0,0 +1,182 @@
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    config::Config,
+    graph::{Attributes, Graph, GraphNode},
+    layout::registry::drive_layout,
+    render::{colors::ColorMapping, transitions::MoveTo},
+};
+
+/// Attribute the component of each node is written to while
+/// [`ComponentSettings::color`] is on.
+const COMPONENT_ATTRIBUTE: &str = "component";
+
+/// Connected component a node is in, ignoring edge direction, numbered from
+/// the largest component down. Kept up to date as the graph changes.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct ConnectedComponent(pub usize);
+
+/// What to do with [`ConnectedComponent`]s besides tracking them.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct ComponentSettings {
+    /// Write each node's component to its `component` attribute and color
+    /// nodes by it.
+    pub color: bool,
+    /// Keep every component in its own cell of a grid, largest first, so
+    /// that components never overlap whatever the layout does inside them.
+    /// Components holding a pinned or dragged node stay where they are.
+    pub separate: bool,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<ConnectedComponent>()
+        .init_resource::<ComponentSettings>()
+        .add_systems(
+            Update,
+            (
+                (
+                    find_components.run_if(resource_changed::<Graph>),
+                    label_components.run_if(
+                        resource_changed::<ComponentSettings>
+                            .or(any_match_filter::<Changed<ConnectedComponent>>),
+                    ),
+                )
+                    .chain(),
+                separate_components.after(drive_layout),
+            ),
+        );
+}
+
+fn find_components(
+    mut cmd: Commands,
+    graph: Res<Graph>,
+    nodes: Query<(Entity, Option<&ConnectedComponent>), With<GraphNode>>,
+) {
+    let mut components = graph.components();
+    for component in &mut components {
+        component.sort();
+    }
+    components.sort_by_key(|c| (std::cmp::Reverse(c.len()), c[0]));
+    let index: HashMap<Entity, usize> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(i, c)| c.iter().map(move |&node| (node, i)))
+        .collect();
+    for (id, current) in &nodes {
+        if let Some(&i) = index.get(&id)
+            && current != Some(&ConnectedComponent(i))
+        {
+            cmd.entity(id).insert(ConnectedComponent(i));
+        }
+    }
+}
+
+/// Keeps the `component` attribute in step while [`ComponentSettings::color`]
+/// is on and points the color mapping at it, then clears both once it's
+/// turned off.
+fn label_components(
+    mut nodes: Query<(&ConnectedComponent, &mut Attributes)>,
+    settings: Res<ComponentSettings>,
+    mapping: Option<ResMut<ColorMapping>>,
+    mut labelled: Local<bool>,
+) {
+    if !settings.color && !*labelled {
+        return;
+    }
+    for (component, mut attributes) in &mut nodes {
+        let label = component.0.to_string();
+        if !settings.color {
+            attributes.0.remove(COMPONENT_ATTRIBUTE);
+        } else if attributes.get(COMPONENT_ATTRIBUTE) != Some(&label) {
+            attributes.0.insert(COMPONENT_ATTRIBUTE.into(), label);
+        }
+    }
+    if let Some(mut mapping) = mapping {
+        if settings.color {
+            mapping.attribute = Some(COMPONENT_ATTRIBUTE.into());
+            mapping.categorical = true;
+        } else if mapping.attribute.as_deref() == Some(COMPONENT_ATTRIBUTE) {
+            mapping.attribute = None;
+            mapping.categorical = false;
+        }
+    }
+    *labelled = settings.color;
+}
+
+/// A node as [`separate_components`] sees it.
+type Separated = (
+    &'static ConnectedComponent,
+    &'static RigidBody,
+    &'static mut Transform,
+    Option<&'static mut MoveTo>,
+);
+
+/// Shifts every component into its grid cell, centred on the origin. Cells
+/// are as wide as the widest component in their column and as tall as the
+/// tallest in their row, with [`Config::ideal_length`] between them.
+fn separate_components(
+    mut nodes: Query<Separated, With<GraphNode>>,
+    settings: Res<ComponentSettings>,
+    config: Res<Config>,
+) {
+    if !settings.separate {
+        return;
+    }
+    // Bounds of each component, and whether it's held in place.
+    let mut bounds: Vec<Option<(Rect, bool)>> = Vec::new();
+    for (component, body, transform, _) in &nodes {
+        if bounds.len() <= component.0 {
+            bounds.resize(component.0 + 1, None);
+        }
+        let pos = transform.translation.xy();
+        let (rect, anchored) =
+            bounds[component.0].get_or_insert((Rect::from_center_size(pos, Vec2::ZERO), false));
+        *rect = rect.union_point(pos);
+        *anchored |= !body.is_dynamic();
+    }
+    let loose: Vec<(usize, Rect)> = bounds
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| b.filter(|&(_, anchored)| !anchored).map(|(r, _)| (i, r)))
+        .collect();
+    if loose.len() < 2 {
+        return;
+    }
+
+    let columns = (loose.len() as f32).sqrt().ceil() as usize;
+    let rows = loose.len().div_ceil(columns);
+    let gap = config.ideal_length;
+    let mut widths = vec![0f32; columns];
+    let mut heights = vec![0f32; rows];
+    for (k, (_, rect)) in loose.iter().enumerate() {
+        let (row, column) = (k / columns, k % columns);
+        widths[column] = widths[column].max(rect.width() + gap);
+        heights[row] = heights[row].max(rect.height() + gap);
+    }
+    let total = Vec2::new(widths.iter().sum(), heights.iter().sum());
+    let mut shifts = vec![Vec2::ZERO; bounds.len()];
+    for (k, &(i, rect)) in loose.iter().enumerate() {
+        let (row, column) = (k / columns, k % columns);
+        // Rows go down the screen, so the first one sits at the top.
+        let corner = Vec2::new(
+            widths[..column].iter().sum::<f32>(),
+            -heights[..row].iter().sum::<f32>(),
+        );
+        let cell = Vec2::new(widths[column], -heights[row]);
+        let center = corner + cell / 2. - Vec2::new(total.x, -total.y) / 2.;
+        shifts[i] = center - rect.center();
+    }
+
+    for (component, _, mut transform, tween) in &mut nodes {
+        let shift = shifts[component.0];
+        if shift.length_squared() < 1e-6 {
+            continue;
+        }
+        transform.translation += shift.extend(0.);
+        if let Some(mut tween) = tween {
+            tween.shift(shift);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use super::spanning::find;
+use crate::graph::{Edge, Graph};
+
+/// Edges that lie on a cycle, for tracking down dependency loops. In a
+/// directed graph those are the edges inside a strongly connected component,
+/// found with Tarjan's algorithm, plus self-loops; in an undirected one
+/// every edge that isn't a bridge. With `highlight` on they are kept up to
+/// date and [`Highlighted`](crate::Highlighted), and everything off them is
+/// [`Dimmed`](crate::Dimmed).
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Cycles {
+    pub highlight: bool,
+    edges: HashSet<Entity>,
+    groups: Vec<Vec<Entity>>,
+}
+
+impl Cycles {
+    pub fn edges(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.edges.iter().copied()
+    }
+
+    pub fn contains(&self, edge: Entity) -> bool {
+        self.edges.contains(&edge)
+    }
+
+    /// Nodes joined by cycle edges, largest group first; in a directed
+    /// graph, the strongly connected components that have a cycle.
+    pub fn groups(&self) -> &[Vec<Entity>] {
+        &self.groups
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Cycles>().add_systems(
+        Update,
+        find_cycles.run_if(resource_changed::<Cycles>.or(resource_changed::<Graph>)),
+    );
+}
+
+pub(super) fn find_cycles(mut cycles: ResMut<Cycles>, graph: Res<Graph>, edges: Query<&Edge>) {
+    let found = if cycles.highlight {
+        cycle_edges(&graph, &edges)
+    } else {
+        HashSet::new()
+    };
+    if found == cycles.edges {
+        return;
+    }
+    let groups = group(&found, &edges);
+    // Only flagged when the cycles changed, or this would rerun every frame.
+    let c = cycles.bypass_change_detection();
+    c.edges = found;
+    c.groups = groups;
+    cycles.set_changed();
+}
+
+/// Edges on a cycle among the enabled ones.
+fn cycle_edges(graph: &Graph, edges: &Query<&Edge>) -> HashSet<Entity> {
+    let mut nodes: Vec<Entity> = graph.nodes().collect();
+    nodes.sort();
+    let index: HashMap<Entity, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut adjacency: Vec<Vec<(usize, Entity)>> = vec![Vec::new(); nodes.len()];
+    let mut links = Vec::new();
+    for (id, source, target) in graph.edges() {
+        if !edges.contains(id) {
+            continue;
+        }
+        let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) else {
+            continue;
+        };
+        adjacency[a].push((b, id));
+        if !graph.is_directed() {
+            adjacency[b].push((a, id));
+        }
+        links.push((id, a, b));
+    }
+    if graph.is_directed() {
+        let component = strong_components(&adjacency);
+        let mut sizes = vec![0; nodes.len()];
+        for &c in &component {
+            sizes[c] += 1;
+        }
+        links
+            .into_iter()
+            .filter(|&(_, a, b)| {
+                component[a] == component[b] && (a == b || sizes[component[a]] > 1)
+            })
+            .map(|(id, ..)| id)
+            .collect()
+    } else {
+        let bridges = bridges(&adjacency);
+        links
+            .into_iter()
+            .map(|(id, ..)| id)
+            .filter(|id| !bridges.contains(id))
+            .collect()
+    }
+}
+
+/// Tarjan's algorithm, unrolled so deep graphs can't overflow the stack.
+/// Returns the component of every node.
+fn strong_components(adjacency: &[Vec<(usize, Entity)>]) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut component = vec![usize::MAX; n];
+    let mut stack = Vec::new();
+    let mut next_order = 0;
+    let mut next_component = 0;
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        // Each frame is a node and how many of its arcs have been followed.
+        let mut calls = vec![(root, 0)];
+        order[root] = next_order;
+        low[root] = next_order;
+        next_order += 1;
+        stack.push(root);
+        while let Some(&mut (v, ref mut arc)) = calls.last_mut() {
+            if let Some(&(w, _)) = adjacency[v].get(*arc) {
+                *arc += 1;
+                if order[w] == usize::MAX {
+                    order[w] = next_order;
+                    low[w] = next_order;
+                    next_order += 1;
+                    stack.push(w);
+                    calls.push((w, 0));
+                } else if component[w] == usize::MAX {
+                    low[v] = low[v].min(order[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(u, _)) = calls.last() {
+                low[u] = low[u].min(low[v]);
+            }
+            if low[v] == order[v] {
+                while let Some(w) = stack.pop() {
+                    component[w] = next_component;
+                    if w == v {
+                        break;
+                    }
+                }
+                next_component += 1;
+            }
+        }
+    }
+    component
+}
+
+/// Edges whose removal would disconnect their ends, by the lowest discovery
+/// time each subtree of a depth-first search reaches back to.
+fn bridges(adjacency: &[Vec<(usize, Entity)>]) -> HashSet<Entity> {
+    let n = adjacency.len();
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut bridges = HashSet::new();
+    let mut next_order = 0;
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        // A node, the edge it was reached by and how many arcs it followed.
+        let mut calls: Vec<(usize, Option<Entity>, usize)> = vec![(root, None, 0)];
+        order[root] = next_order;
+        low[root] = next_order;
+        next_order += 1;
+        while let Some(&mut (v, via, ref mut arc)) = calls.last_mut() {
+            if let Some(&(w, edge)) = adjacency[v].get(*arc) {
+                *arc += 1;
+                // Parallel edges back to the parent still count.
+                if Some(edge) == via {
+                    continue;
+                }
+                if order[w] == usize::MAX {
+                    order[w] = next_order;
+                    low[w] = next_order;
+                    next_order += 1;
+                    calls.push((w, Some(edge), 0));
+                } else {
+                    low[v] = low[v].min(order[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let (Some(&(u, ..)), Some(edge)) = (calls.last(), via) {
+                low[u] = low[u].min(low[v]);
+                if low[v] > order[u] {
+                    bridges.insert(edge);
+                }
+            }
+        }
+    }
+    bridges
+}
+
+/// Nodes joined by the cycle edges, largest group first.
+fn group(cycle_edges: &HashSet<Entity>, edges: &Query<&Edge>) -> Vec<Vec<Entity>> {
+    let mut parent: HashMap<Entity, Entity> = HashMap::default();
+    for edge in cycle_edges.iter().filter_map(|&id| edges.get(id).ok()) {
+        parent.entry(edge.source).or_insert(edge.source);
+        parent.entry(edge.target).or_insert(edge.target);
+        let (a, b) = (
+            find(&mut parent, edge.source),
+            find(&mut parent, edge.target),
+        );
+        parent.insert(a, b);
+    }
+    let nodes: Vec<Entity> = parent.keys().copied().collect();
+    let mut groups: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    for node in nodes {
+        let root = find(&mut parent, node);
+        groups.entry(root).or_default().push(node);
+    }
+    let mut groups: Vec<Vec<Entity>> = groups.into_values().collect();
+    for nodes in &mut groups {
+        nodes.sort();
+    }
+    groups.sort_by_key(|g| (std::cmp::Reverse(g.len()), g[0]));
+    groups
+}
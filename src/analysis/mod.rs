@@ -0,0 +1,143 @@
+use bevy::{ecs::system::SystemParam, platform::collections::HashSet, prelude::*};
+
+use crate::{
+    filter::{FilteredOut, NodeFilter},
+    graph::{Edge, Graph, GraphNode},
+    render::emphasis::{Dimmed, Highlighted},
+};
+
+pub mod centrality;
+pub mod community;
+pub mod components;
+pub mod cycles;
+pub mod path;
+pub mod spanning;
+pub mod traversal;
+
+use cycles::Cycles;
+use path::ShortestPath;
+use spanning::{SpanningTree, SpanningTreeMode};
+use traversal::Traversal;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins((
+        centrality::plugin,
+        community::plugin,
+        components::plugin,
+        cycles::plugin,
+        path::plugin,
+        spanning::plugin,
+        traversal::plugin,
+    ))
+    .add_systems(
+        Update,
+        emphasize
+            .after(path::find_path)
+            .after(traversal::step_traversal)
+            .after(spanning::find_tree)
+            .after(cycles::find_cycles)
+            .after(crate::timeline::apply_timeline)
+            .run_if(
+                resource_changed::<ShortestPath>
+                    .or(resource_changed::<Traversal>)
+                    .or(resource_changed::<Cycles>)
+                    .or(resource_changed::<SpanningTree>)
+                    .or(resource_changed::<Graph>)
+                    .or(resource_exists_and_changed::<NodeFilter>)
+                    .or(any_match_filter::<Added<FilteredOut>>)
+                    .or(any_component_removed::<FilteredOut>),
+            ),
+    );
+}
+
+/// Everything [`emphasize`] picks from.
+#[derive(SystemParam)]
+struct Highlights<'w> {
+    path: Res<'w, ShortestPath>,
+    traversal: ResMut<'w, Traversal>,
+    cycles: Res<'w, Cycles>,
+    tree: Res<'w, SpanningTree>,
+    filter: Option<Res<'w, NodeFilter>>,
+}
+
+/// Singles out the [`Traversal`] tree, or else the [`ShortestPath`], or else
+/// highlighted [`Cycles`], or else a highlighted [`SpanningTree`] and all
+/// nodes: their edges are [`Highlighted`] and every other node and edge
+/// [`Dimmed`]. A path that was
+/// just set takes over from the walk. Ghosts the [`NodeFilter`] keeps are
+/// dimmed along with their edges either way.
+fn emphasize(
+    mut cmd: Commands,
+    highlights: Highlights,
+    nodes: Query<(Entity, Has<Dimmed>, Has<FilteredOut>), With<GraphNode>>,
+    edges: Query<(Entity, &Edge, Has<Dimmed>, Has<Highlighted>)>,
+    mut shown: Local<bool>,
+) {
+    let Highlights {
+        path,
+        mut traversal,
+        cycles,
+        tree,
+        filter,
+    } = highlights;
+    if path.is_changed() && path.is_set() && traversal.is_active() {
+        traversal.stop();
+    }
+    let focus = if traversal.is_active() {
+        Some(traversal.tree())
+    } else if path.is_set() {
+        let nodes = path.nodes().iter().copied().collect();
+        Some((nodes, path.edges().iter().copied().collect()))
+    } else if cycles.highlight {
+        let nodes = cycles.groups().iter().flatten().copied().collect();
+        Some((nodes, cycles.edges().collect()))
+    } else if tree.mode == SpanningTreeMode::Highlight {
+        Some((
+            nodes.iter().map(|(id, ..)| id).collect(),
+            tree.edges().collect(),
+        ))
+    } else {
+        None
+    };
+    let ghosts: HashSet<Entity> = if filter.as_ref().is_some_and(|f| f.ghosts) {
+        nodes
+            .iter()
+            .filter(|&(.., filtered)| filtered)
+            .map(|(id, ..)| id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    // Leaves emphasis set by others alone until there is something to show,
+    // but clears ghosts the filter just let back in.
+    let refilter = filter.is_some_and(|f| f.is_changed());
+    if focus.is_none() && ghosts.is_empty() && !*shown && !refilter {
+        return;
+    }
+    *shown = focus.is_some() || !ghosts.is_empty();
+    let focused = focus.is_some();
+    let (focus_nodes, focus_edges): (HashSet<_>, HashSet<_>) = focus.unwrap_or_default();
+    for (id, dimmed, _) in &nodes {
+        let dim = (focused && !focus_nodes.contains(&id)) || ghosts.contains(&id);
+        if dim && !dimmed {
+            cmd.entity(id).insert(Dimmed);
+        } else if !dim && dimmed {
+            cmd.entity(id).remove::<Dimmed>();
+        }
+    }
+    for (id, edge, dimmed, highlighted) in &edges {
+        let ghost = ghosts.contains(&edge.source) || ghosts.contains(&edge.target);
+        let highlight = focus_edges.contains(&id) && !ghost;
+        let dim = (focused && !highlight) || ghost;
+        if dim && !dimmed {
+            cmd.entity(id).insert(Dimmed);
+        } else if !dim && dimmed {
+            cmd.entity(id).remove::<Dimmed>();
+        }
+        if highlight && !highlighted {
+            cmd.entity(id).insert(Highlighted);
+        } else if !highlight && highlighted {
+            cmd.entity(id).remove::<Highlighted>();
+        }
+    }
+}
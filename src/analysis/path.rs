@@ -0,0 +1,163 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::graph::{Edge, Graph, GraphNode};
+
+/// Shortest path from `source` to `target`, with [`Edge::weight`] as the
+/// length of each edge, following edge direction in a directed graph. While
+/// both ends are set, the path's edges are
+/// [`Highlighted`](crate::Highlighted) and every node and edge off it is
+/// [`Dimmed`](crate::Dimmed). The path is found again whenever the ends,
+/// the graph or a weight changes.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ShortestPath {
+    pub source: Option<Entity>,
+    pub target: Option<Entity>,
+    nodes: Vec<Entity>,
+    edges: Vec<Entity>,
+    length: f32,
+}
+
+impl ShortestPath {
+    /// Nodes along the path from `source` to `target`, empty if there is
+    /// none.
+    pub fn nodes(&self) -> &[Entity] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[Entity] {
+        &self.edges
+    }
+
+    /// Whether both ends are set, connected or not.
+    pub fn is_set(&self) -> bool {
+        self.source.is_some() && self.target.is_some()
+    }
+
+    /// Whether both ends are set and connected.
+    pub fn is_found(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    pub fn hops(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Sum of the weights along the path.
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ShortestPath>().add_systems(
+        Update,
+        find_path.run_if(
+            resource_changed::<ShortestPath>
+                .or(resource_changed::<Graph>)
+                .or(any_match_filter::<Changed<Edge>>),
+        ),
+    );
+}
+
+pub(super) fn find_path(
+    mut path: ResMut<ShortestPath>,
+    graph: Res<Graph>,
+    edges: Query<&Edge>,
+    nodes: Query<(), With<GraphNode>>,
+) {
+    let (nodes, edges, length) = match (path.source, path.target) {
+        (Some(source), Some(target)) => dijkstra(&graph, source, target, |edge| {
+            // Disabled edges and nodes are out of the picture.
+            let edge = edges.get(edge).ok()?;
+            (nodes.contains(edge.source) && nodes.contains(edge.target))
+                .then_some(edge.weight.max(0.))
+        })
+        .unwrap_or_default(),
+        _ => Default::default(),
+    };
+    if nodes == path.nodes && edges == path.edges && length == path.length {
+        return;
+    }
+    // Only flagged when the path moved, or this would rerun every frame.
+    let p = path.bypass_change_detection();
+    p.nodes = nodes;
+    p.edges = edges;
+    p.length = length;
+    path.set_changed();
+}
+
+/// A node waiting in [`dijkstra`]'s queue, nearest first.
+#[derive(PartialEq)]
+struct Visit(f32, Entity);
+
+impl Eq for Visit {}
+
+impl Ord for Visit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Dijkstra's algorithm from `source` to `target` over the edges `length`
+/// gives a length for. Returns the nodes and edges along the way, and the
+/// total length.
+fn dijkstra(
+    graph: &Graph,
+    source: Entity,
+    target: Entity,
+    length: impl Fn(Entity) -> Option<f32>,
+) -> Option<(Vec<Entity>, Vec<Entity>, f32)> {
+    if !graph.contains_node(source) || !graph.contains_node(target) {
+        return None;
+    }
+    let mut distance: HashMap<Entity, f32> = HashMap::default();
+    let mut came_from: HashMap<Entity, (Entity, Entity)> = HashMap::default();
+    let mut queue = BinaryHeap::new();
+    distance.insert(source, 0.);
+    queue.push(Visit(0., source));
+    while let Some(Visit(d, node)) = queue.pop() {
+        if node == target {
+            break;
+        }
+        if distance.get(&node).is_some_and(|&best| d > best) {
+            continue;
+        }
+        let next: Vec<_> = if graph.is_directed() {
+            graph.successors(node).collect()
+        } else {
+            graph.neighbors(node).collect()
+        };
+        for (neighbor, edge) in next {
+            let Some(l) = length(edge) else {
+                continue;
+            };
+            let d = d + l;
+            if distance.get(&neighbor).is_none_or(|&best| d < best) {
+                distance.insert(neighbor, d);
+                came_from.insert(neighbor, (node, edge));
+                queue.push(Visit(d, neighbor));
+            }
+        }
+    }
+    let total = *distance.get(&target)?;
+    let (mut nodes, mut edges) = (vec![target], Vec::new());
+    while let Some(&(previous, edge)) = came_from.get(nodes.last()?) {
+        nodes.push(previous);
+        edges.push(edge);
+    }
+    nodes.reverse();
+    edges.reverse();
+    Some((nodes, edges, total))
+}
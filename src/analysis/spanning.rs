@@ -0,0 +1,137 @@
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::graph::{Edge, Graph};
+
+/// How the [`SpanningTree`] is shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpanningTreeMode {
+    /// Not computed at all.
+    #[default]
+    Off,
+    /// Tree edges are [`Highlighted`](crate::Highlighted) and the others
+    /// [`Dimmed`](crate::Dimmed).
+    Highlight,
+    /// Edges off the tree are hidden.
+    Only,
+}
+
+/// Minimum spanning forest of the graph by Kruskal's algorithm, with
+/// [`Edge::weight`] as the cost of each edge and direction ignored. Kept up
+/// to date while `mode` isn't [`SpanningTreeMode::Off`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SpanningTree {
+    pub mode: SpanningTreeMode,
+    edges: HashSet<Entity>,
+    weight: f32,
+}
+
+impl SpanningTree {
+    pub fn edges(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.edges.iter().copied()
+    }
+
+    pub fn contains(&self, edge: Entity) -> bool {
+        self.edges.contains(&edge)
+    }
+
+    /// Sum of the tree's edge weights.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+}
+
+/// Marks an edge [`SpanningTreeMode::Only`] hides.
+#[derive(Component)]
+pub(crate) struct OffTree;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SpanningTree>().add_systems(
+        Update,
+        (
+            find_tree.run_if(
+                resource_changed::<SpanningTree>
+                    .or(resource_changed::<Graph>)
+                    .or(any_match_filter::<Changed<Edge>>),
+            ),
+            hide_off_tree.run_if(resource_changed::<SpanningTree>.or(resource_changed::<Graph>)),
+        )
+            .chain(),
+    );
+}
+
+pub(super) fn find_tree(
+    mut tree: ResMut<SpanningTree>,
+    graph: Res<Graph>,
+    edges: Query<(Entity, &Edge)>,
+) {
+    let (found, weight) = if tree.mode == SpanningTreeMode::Off {
+        (HashSet::new(), 0.)
+    } else {
+        kruskal(&graph, &edges)
+    };
+    if found == tree.edges && weight == tree.weight {
+        return;
+    }
+    // Only flagged when the tree changed, or this would rerun every frame.
+    let t = tree.bypass_change_detection();
+    t.edges = found;
+    t.weight = weight;
+    tree.set_changed();
+}
+
+/// Grows the forest from the cheapest edge up, skipping any that would
+/// close a cycle. Disabled edges are left out.
+fn kruskal(graph: &Graph, edges: &Query<(Entity, &Edge)>) -> (HashSet<Entity>, f32) {
+    let mut sorted: Vec<(Entity, &Edge)> = edges.iter().collect();
+    // Entity order breaks ties so the tree doesn't flicker between equals.
+    sorted.sort_by(|a, b| a.1.weight.total_cmp(&b.1.weight).then(a.0.cmp(&b.0)));
+    let mut parent: HashMap<Entity, Entity> = graph.nodes().map(|n| (n, n)).collect();
+    let mut tree = HashSet::new();
+    let mut weight = 0.;
+    for (id, edge) in sorted {
+        if !parent.contains_key(&edge.source) || !parent.contains_key(&edge.target) {
+            continue;
+        }
+        let (a, b) = (
+            find(&mut parent, edge.source),
+            find(&mut parent, edge.target),
+        );
+        if a != b {
+            parent.insert(a, b);
+            tree.insert(id);
+            weight += edge.weight;
+        }
+    }
+    (tree, weight)
+}
+
+/// Union-find root of `node`, halving the path on the way up.
+pub(super) fn find(parent: &mut HashMap<Entity, Entity>, mut node: Entity) -> Entity {
+    while parent[&node] != node {
+        let grandparent = parent[&parent[&node]];
+        parent.insert(node, grandparent);
+        node = grandparent;
+    }
+    node
+}
+
+/// Marks the edges off the tree while only the tree is shown, leaving
+/// whether they're drawn to [`Hidden`](crate::Hidden)'s visibility sync.
+fn hide_off_tree(
+    mut cmd: Commands,
+    tree: Res<SpanningTree>,
+    edges: Query<(Entity, Has<OffTree>), With<Edge>>,
+) {
+    let only = tree.mode == SpanningTreeMode::Only;
+    for (id, hidden) in &edges {
+        let hide = only && !tree.contains(id);
+        if hide && !hidden {
+            cmd.entity(id).insert(OffTree);
+        } else if !hide && hidden {
+            cmd.entity(id).remove::<OffTree>();
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use super::path::ShortestPath;
+use crate::{
+    graph::{Graph, GraphNode, NodeRadius},
+    render::theme::Theme,
+};
+
+/// Order a [`Traversal`] visits nodes in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Level by level, nearest nodes first.
+    #[default]
+    BreadthFirst,
+    /// As deep as possible before backtracking.
+    DepthFirst,
+}
+
+/// Animated walk through the graph from the root of the last
+/// [`StartTraversal`]: every `step_delay` seconds the next node is visited
+/// and lights up along with the tree edge it was reached by, while the rest
+/// of the graph stays [`Dimmed`](crate::Dimmed). The finished tree stays on
+/// show until [`Traversal::stop`].
+#[derive(Resource, Clone, Debug)]
+pub struct Traversal {
+    pub step_delay: f32,
+    /// Every node the walk reaches, with the edge it was reached by.
+    steps: Vec<(Entity, Option<Entity>)>,
+    /// Steps visited so far.
+    visited: usize,
+    elapsed: f32,
+}
+
+impl Default for Traversal {
+    fn default() -> Self {
+        Self {
+            step_delay: 0.3,
+            steps: Vec::new(),
+            visited: 0,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl Traversal {
+    /// Nodes visited so far, in order.
+    pub fn visited(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.steps[..self.visited].iter().map(|&(node, _)| node)
+    }
+
+    /// Whether a walk is on show, finished or not.
+    pub fn is_active(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.visited == self.steps.len()
+    }
+
+    /// Nodes reached so far and the tree edges they were reached by.
+    pub(super) fn tree(&self) -> (HashSet<Entity>, HashSet<Entity>) {
+        let steps = &self.steps[..self.visited];
+        let nodes = steps.iter().map(|&(node, _)| node).collect();
+        let edges = steps.iter().filter_map(|&(_, edge)| edge).collect();
+        (nodes, edges)
+    }
+
+    /// Ends the walk and brings the graph back to normal.
+    pub fn stop(&mut self) {
+        self.steps.clear();
+        self.visited = 0;
+    }
+}
+
+/// Starts a [`Traversal`] from `root`, replacing any walk or
+/// [`ShortestPath`] on show; setting a path ends the walk in turn.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct StartTraversal {
+    pub root: Entity,
+    pub order: TraversalOrder,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Traversal>()
+        .add_event::<StartTraversal>()
+        .add_systems(
+            Update,
+            (
+                start_traversal.run_if(on_event::<StartTraversal>),
+                step_traversal,
+                ring_current.run_if(resource_exists::<GizmoConfigStore>),
+            )
+                .chain(),
+        );
+}
+
+fn start_traversal(
+    mut events: EventReader<StartTraversal>,
+    mut traversal: ResMut<Traversal>,
+    mut path: ResMut<ShortestPath>,
+    graph: Res<Graph>,
+    nodes: Query<(), With<GraphNode>>,
+) {
+    let Some(&StartTraversal { root, order }) = events.read().last() else {
+        return;
+    };
+    if !nodes.contains(root) {
+        return;
+    }
+    path.clear();
+    // Neighbours in entity order, leaving out disabled ones.
+    let next = |node: Entity| {
+        let mut next: Vec<_> = if graph.is_directed() {
+            graph.successors(node).collect()
+        } else {
+            graph.neighbors(node).collect()
+        };
+        next.retain(|&(n, _)| nodes.contains(n));
+        next.sort();
+        next
+    };
+    let mut seen = HashSet::new();
+    let mut steps = Vec::new();
+    match order {
+        TraversalOrder::BreadthFirst => {
+            seen.insert(root);
+            let mut queue = VecDeque::from([(root, None)]);
+            while let Some((node, edge)) = queue.pop_front() {
+                steps.push((node, edge));
+                for (neighbor, edge) in next(node) {
+                    if seen.insert(neighbor) {
+                        queue.push_back((neighbor, Some(edge)));
+                    }
+                }
+            }
+        }
+        TraversalOrder::DepthFirst => {
+            let mut stack = vec![(root, None)];
+            while let Some((node, edge)) = stack.pop() {
+                if !seen.insert(node) {
+                    continue;
+                }
+                steps.push((node, edge));
+                // Reversed so the first neighbour comes off the stack first.
+                for (neighbor, edge) in next(node).into_iter().rev() {
+                    if !seen.contains(&neighbor) {
+                        stack.push((neighbor, Some(edge)));
+                    }
+                }
+            }
+        }
+    }
+    traversal.steps = steps;
+    traversal.visited = 1;
+    traversal.elapsed = 0.;
+}
+
+pub(super) fn step_traversal(mut traversal: ResMut<Traversal>, time: Res<Time>) {
+    if !traversal.is_active() || traversal.is_finished() {
+        return;
+    }
+    let t = traversal.bypass_change_detection();
+    t.elapsed += time.delta_secs();
+    if t.elapsed < t.step_delay {
+        return;
+    }
+    t.elapsed = 0.;
+    t.visited += 1;
+    traversal.set_changed();
+}
+
+/// Rings the node visited last while the walk is under way.
+fn ring_current(
+    mut gizmos: Gizmos,
+    traversal: Res<Traversal>,
+    nodes: Query<(&Transform, &NodeRadius), With<GraphNode>>,
+    theme: Res<Theme>,
+) {
+    if traversal.is_finished() {
+        return;
+    }
+    let Some(current) = traversal.visited().last() else {
+        return;
+    };
+    if let Ok((transform, radius)) = nodes.get(current) {
+        gizmos.circle_2d(transform.translation.xy(), radius.0 + 3., theme.selection);
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::Config,
+    graph::{GraphNode, NodeRadius},
+    selection::Selected,
+};
+
+/// Screen-space margin left around framed nodes, in pixels.
+pub(crate) const FRAME_PADDING: f32 = 40.;
+
+/// How long the camera takes to fly to a new framing.
+const FLIGHT_SECS: f32 = 0.4;
+
+/// Centres the 2D camera on a node and zooms to show its surroundings, a few
+/// ideal edge lengths across.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct FocusNode(pub Entity);
+
+/// Flies the 2D camera to frame a set of nodes. Bound to `Home` for
+/// [`All`](Self::All) and `F` for [`Selection`](Self::Selection).
+#[derive(Event, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FitView {
+    /// Every node.
+    #[default]
+    All,
+    /// The [`Selected`] nodes, or every node if none are selected.
+    Selection,
+}
+
+/// Camera easing from one framing to another.
+#[derive(Component)]
+struct Flight {
+    from: (Vec2, f32),
+    to: (Vec2, f32),
+    timer: Timer,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_event::<FocusNode>()
+        .add_event::<FitView>()
+        .add_systems(Update, (fit_hotkeys, focus_node, fit_view, fly).chain());
+}
+
+fn fit_hotkeys(keys: Res<ButtonInput<KeyCode>>, mut ev_w: EventWriter<FitView>) {
+    if keys.just_pressed(KeyCode::Home) {
+        ev_w.write(FitView::All);
+    }
+    if keys.just_pressed(KeyCode::KeyF) {
+        ev_w.write(FitView::Selection);
+    }
+}
+
+type FlyingCamera<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Camera,
+        &'static Transform,
+        &'static Projection,
+    ),
+    With<Camera2d>,
+>;
+
+/// Starts a flight of the single 2D camera to frame `bounds`.
+fn fly_to(cmd: &mut Commands, cameras: &FlyingCamera, bounds: Rect, padding: f32) {
+    let Ok((id, camera, transform, Projection::Orthographic(ortho))) = cameras.single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    cmd.entity(id).insert(Flight {
+        from: (transform.translation.xy(), ortho.scale),
+        to: framing(bounds, viewport, padding),
+        timer: Timer::from_seconds(FLIGHT_SECS, TimerMode::Once),
+    });
+}
+
+fn focus_node(
+    mut cmd: Commands,
+    mut events: EventReader<FocusNode>,
+    cameras: FlyingCamera,
+    nodes: NodeBoundsQuery,
+    config: Res<Config>,
+) {
+    let Some(&FocusNode(id)) = events.read().last() else {
+        return;
+    };
+    if let Some(bounds) = nodes.get(id).ok().and_then(|node| node_bounds([node])) {
+        fly_to(
+            &mut cmd,
+            &cameras,
+            bounds.inflate(2. * config.ideal_length),
+            0.,
+        );
+    }
+}
+
+fn fit_view(
+    mut cmd: Commands,
+    mut events: EventReader<FitView>,
+    cameras: FlyingCamera,
+    nodes: NodeBoundsQuery,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let Some(&fit) = events.read().last() else {
+        return;
+    };
+    let bounds = match fit {
+        FitView::All => node_bounds(nodes.iter()),
+        FitView::Selection => {
+            node_bounds(nodes.iter_many(&selected)).or_else(|| node_bounds(nodes.iter()))
+        }
+    };
+    if let Some(bounds) = bounds {
+        fly_to(&mut cmd, &cameras, bounds, FRAME_PADDING);
+    }
+}
+
+/// Eases the camera along its flight, zooming geometrically so the zoom
+/// speed looks even.
+fn fly(
+    mut cmd: Commands,
+    mut cameras: Query<(Entity, &mut Flight, &mut Transform, &mut Projection)>,
+    time: Res<Time>,
+) {
+    for (id, mut flight, mut transform, mut projection) in &mut cameras {
+        flight.timer.tick(time.delta());
+        let t = flight.timer.fraction();
+        let t = t * t * (3. - 2. * t);
+        let (from, to) = (flight.from, flight.to);
+        transform.translation = from.0.lerp(to.0, t).extend(transform.translation.z);
+        if let Projection::Orthographic(ortho) = &mut *projection {
+            ortho.scale = from.1 * (to.1 / from.1).powf(t);
+        }
+        if flight.timer.finished() {
+            cmd.entity(id).remove::<Flight>();
+        }
+    }
+}
+
+/// World-space box around every node, including its radius.
+pub(crate) fn node_bounds<'a>(
+    nodes: impl IntoIterator<Item = (&'a Transform, &'a NodeRadius)>,
+) -> Option<Rect> {
+    nodes.into_iter().fold(None, |acc, (t, r)| {
+        let rect = Rect::from_center_half_size(t.translation.xy(), Vec2::splat(r.0));
+        Some(acc.map_or(rect, |a: Rect| a.union(rect)))
+    })
+}
+
+/// Camera translation and orthographic scale that frame `bounds` in a
+/// viewport of `viewport` pixels, leaving `padding` pixels on every side.
+pub(crate) fn framing(bounds: Rect, viewport: Vec2, padding: f32) -> (Vec2, f32) {
+    let usable = (viewport - 2. * padding).max(Vec2::ONE);
+    let scale = (bounds.size() / usable).max_element().max(f32::EPSILON);
+    (bounds.center(), scale)
+}
+
+pub(crate) type NodeBoundsQuery<'w, 's> =
+    Query<'w, 's, (&'static Transform, &'static NodeRadius), (With<GraphNode>, Without<Camera>)>;
@@ -0,0 +1,175 @@
+use avian2d::prelude::*;
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    config::Config,
+    graph::{Charge, Edge, EdgeDirection, GraphNode, IncludeDisabled},
+    render::transitions::{MoveTo, Transitions},
+    setup::GraphSpawner,
+    spec::NodeSpec,
+};
+
+/// Replaces nodes with one meta-node at their centroid, as heavy and
+/// repulsive as all of them together and sized to match. Edges leaving the
+/// group are merged into one edge per outside node, weighing as much as the
+/// edges it stands for; the members and their edges are disabled meanwhile.
+#[derive(Event, Clone, Debug)]
+pub struct CollapseNodes(pub Vec<Entity>);
+
+/// Expands a meta-node back into its members, laid out around where it is
+/// now as they were when collapsed. Despawning the meta-node does the same.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ExpandNode(pub Entity);
+
+/// A node standing in for the collapsed nodes in `members`.
+#[derive(Component, Clone, Debug)]
+pub struct MetaNode {
+    pub members: Vec<Entity>,
+    /// Where each member was relative to the centroid.
+    offsets: Vec<Vec2>,
+}
+
+/// Marks a node collapsed into a [`MetaNode`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CollapsedInto(pub Entity);
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_event::<CollapseNodes>()
+        .add_event::<ExpandNode>()
+        .add_systems(
+            Update,
+            (collapse_nodes, expand_nodes)
+                .chain()
+                .before(crate::timeline::apply_timeline),
+        )
+        .add_observer(release_members);
+}
+
+fn collapse_nodes(
+    mut events: EventReader<CollapseNodes>,
+    mut spawner: GraphSpawner,
+    nodes: Query<&Transform, (With<GraphNode>, Without<CollapsedInto>)>,
+    edges: Query<&Edge, IncludeDisabled>,
+    config: Res<Config>,
+) {
+    // `Graph` only learns about new IDs once the commands are applied.
+    let mut taken = HashSet::new();
+    for CollapseNodes(group) in events.read() {
+        let mut members: Vec<Entity> = group
+            .iter()
+            .copied()
+            .filter(|&n| nodes.contains(n))
+            .collect();
+        members.sort();
+        members.dedup();
+        if members.len() < 2 {
+            continue;
+        }
+        let positions: Vec<Vec2> = nodes
+            .iter_many(&members)
+            .map(|t| t.translation.xy())
+            .collect();
+        let centroid = positions.iter().sum::<Vec2>() / positions.len() as f32;
+        let id = (1..)
+            .map(|n| format!("group {n}"))
+            .find(|id| !taken.contains(id) && spawner.graph().node_by_id(id).is_none())
+            .unwrap_or_default();
+        taken.insert(id.clone());
+        let n = members.len() as f32;
+        let spec = NodeSpec::new(id)
+            .with_attribute("label", format!("{} nodes", members.len()))
+            .with_attribute("size", (5. * n.sqrt()).to_string());
+        let meta = spawner.spawn_node_at(&spec, centroid);
+
+        // Weight of the merged edge to each outside node, per direction.
+        let inside: HashSet<Entity> = members.iter().copied().collect();
+        let directed = spawner.graph().is_directed();
+        let mut merged: HashMap<(Entity, Entity), f32> = HashMap::default();
+        let mut seen = HashSet::new();
+        for &member in &members {
+            for (_, id) in spawner.graph().neighbors(member) {
+                let Ok(edge) = edges.get(id) else {
+                    continue;
+                };
+                if !seen.insert(id) {
+                    continue;
+                }
+                let end = |node| if inside.contains(&node) { meta } else { node };
+                let (source, target) = (end(edge.source), end(edge.target));
+                if source == target {
+                    continue;
+                }
+                let key = if directed || source == meta {
+                    (source, target)
+                } else {
+                    (target, source)
+                };
+                *merged.entry(key).or_default() += edge.weight;
+            }
+        }
+        for ((source, target), weight) in merged {
+            let edge = spawner.spawn_edge(Edge::new(source, target).with_weight(weight));
+            if directed {
+                spawner.cmd.entity(edge).insert(EdgeDirection::Forward);
+            }
+        }
+
+        spawner.cmd.entity(meta).insert((
+            MetaNode {
+                offsets: positions.iter().map(|&p| p - centroid).collect(),
+                members: members.clone(),
+            },
+            Mass(config.node_mass * n),
+            Charge(n),
+        ));
+        for member in members {
+            spawner.cmd.entity(member).insert(CollapsedInto(meta));
+        }
+    }
+}
+
+fn expand_nodes(
+    mut cmd: Commands,
+    mut events: EventReader<ExpandNode>,
+    metas: Query<(), With<MetaNode>>,
+) {
+    for &ExpandNode(meta) in events.read() {
+        if metas.contains(meta) {
+            cmd.entity(meta).despawn();
+        }
+    }
+}
+
+/// Puts the members of a meta-node that's going away back around it, gliding
+/// out of it if [`Transitions`] are on.
+fn release_members(
+    trigger: Trigger<OnRemove, MetaNode>,
+    mut cmd: Commands,
+    metas: Query<&MetaNode>,
+    mut nodes: Query<(&mut Transform, &mut LinearVelocity), (With<GraphNode>, IncludeDisabled)>,
+    transitions: Option<Res<Transitions>>,
+) {
+    let meta = trigger.target();
+    let (Ok(group), Ok((transform, _))) = (metas.get(meta), nodes.get(meta)) else {
+        return;
+    };
+    let center = transform.translation.xy();
+    let animate = transitions.is_some_and(|t| t.is_enabled());
+    for (&member, &offset) in group.members.iter().zip(&group.offsets) {
+        let Ok((mut transform, mut velocity)) = nodes.get_mut(member) else {
+            continue;
+        };
+        velocity.0 = Vec2::ZERO;
+        if animate {
+            transform.translation = center.extend(transform.translation.z);
+            cmd.entity(member)
+                .insert(MoveTo::new(center, center + offset));
+        } else {
+            transform.translation = (center + offset).extend(transform.translation.z);
+        }
+        cmd.entity(member).remove::<CollapsedInto>();
+    }
+}
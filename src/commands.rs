@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::{
+    edit::set_attribute,
+    graph::{Attributes, Edge, EdgeDirection, EdgeLabel, GraphElement, GraphNode},
+    setup::GraphSpawner,
+    spec::NodeSpec,
+};
+
+/// Structural edits on [`Commands`] for other plugins to drive the graph
+/// with. They're queued like any other command, and `add_node` hands out the
+/// node's entity straight away so edges can be hung off it in the same
+/// system. Nodes and edges get their bodies and joints just like the ones of the
+/// [`GraphSpec`](crate::GraphSpec), and a removed node takes its edges and
+/// their joints with it. Unlike the edit events, such as
+/// [`AddNode`](crate::AddNode), these edits aren't recorded in the
+/// [`History`](crate::History).
+pub trait GraphCommands {
+    /// Spawns `node`, scattered at random unless it has a position. The
+    /// returned entity can be passed to [`add_edge`](Self::add_edge) straight
+    /// away; it's despawned again if the node's ID turns out to be taken.
+    fn add_node(&mut self, node: impl Into<NodeSpec>) -> Entity;
+
+    /// Spawns `edge` with its joint, pointing from `source` to `target` in a
+    /// directed graph.
+    fn add_edge(&mut self, edge: Edge) -> Entity;
+
+    /// Despawns a node along with its edges, or a single edge.
+    fn remove_node(&mut self, node: Entity);
+
+    /// Sets an attribute of a node or edge, or removes it if `value` is
+    /// `None`. Setting `label` relabels the element.
+    fn set_attribute(&mut self, element: Entity, key: impl Into<String>, value: Option<String>);
+}
+
+impl GraphCommands for Commands<'_, '_> {
+    fn add_node(&mut self, node: impl Into<NodeSpec>) -> Entity {
+        let node = node.into();
+        let id = self.spawn_empty().id();
+        self.queue(move |world: &mut World| {
+            if let Err(e) = world.run_system_cached_with(insert_node, (id, node)) {
+                warn!("failed to add node: {e}");
+            }
+        });
+        id
+    }
+
+    fn add_edge(&mut self, edge: Edge) -> Entity {
+        let id = self.spawn_empty().id();
+        self.queue(move |world: &mut World| {
+            if let Err(e) = world.run_system_cached_with(insert_edge, (id, edge)) {
+                warn!("failed to add edge: {e}");
+            }
+        });
+        id
+    }
+
+    fn remove_node(&mut self, node: Entity) {
+        self.queue(move |world: &mut World| {
+            let Ok(entity) = world.get_entity_mut(node) else {
+                return;
+            };
+            if entity.contains::<GraphNode>() || entity.contains::<Edge>() {
+                entity.despawn();
+            }
+        });
+    }
+
+    fn set_attribute(&mut self, element: Entity, key: impl Into<String>, value: Option<String>) {
+        let key = key.into();
+        self.queue(move |world: &mut World| {
+            if let Err(e) = world.run_system_cached_with(apply_attribute, (element, key, value)) {
+                warn!("failed to set attribute: {e}");
+            }
+        });
+    }
+}
+
+fn insert_node(In((id, node)): In<(Entity, NodeSpec)>, mut spawner: GraphSpawner) {
+    if spawner.graph().node_by_id(&node.id).is_some() {
+        warn!("not adding node {}: the ID is taken", node.id);
+        spawner.cmd.entity(id).despawn();
+        return;
+    }
+    spawner.insert_node(id, &node);
+}
+
+fn insert_edge(In((id, edge)): In<(Entity, Edge)>, mut spawner: GraphSpawner) {
+    let graph = spawner.graph();
+    if !graph.contains_node(edge.source) || !graph.contains_node(edge.target) {
+        warn!(
+            "not adding edge {} -> {}: unknown endpoint",
+            edge.source, edge.target
+        );
+        spawner.cmd.entity(id).despawn();
+        return;
+    }
+    let directed = graph.is_directed();
+    spawner.insert_edge(id, edge);
+    if directed {
+        spawner.cmd.entity(id).insert(EdgeDirection::Forward);
+    }
+}
+
+fn apply_attribute(
+    In((element, key, value)): In<(Entity, String, Option<String>)>,
+    mut cmd: Commands,
+    mut elements: Query<(Option<&mut Attributes>, Has<Edge>), GraphElement>,
+) {
+    let Ok((attributes, is_edge)) = elements.get_mut(element) else {
+        warn!("not setting {key} on {element}: not a node or edge");
+        return;
+    };
+    if !is_edge {
+        if let Some(mut attributes) = attributes {
+            set_attribute(&mut cmd, element, &mut attributes, &key, value);
+        }
+        return;
+    }
+    if key == "label" {
+        match &value {
+            Some(label) => cmd.entity(element).insert(EdgeLabel(label.clone())),
+            None => cmd.entity(element).remove::<EdgeLabel>(),
+        };
+    }
+    match (attributes, value) {
+        (Some(mut attributes), Some(value)) => {
+            attributes.0.insert(key, value);
+        }
+        (Some(mut attributes), None) => {
+            attributes.0.remove(&key);
+        }
+        (None, Some(value)) => {
+            cmd.entity(element)
+                .insert(Attributes([(key, value)].into_iter().collect()));
+        }
+        (None, None) => {}
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{forces::GravityCenter, layout::spectral::Placement};
+
+pub const IDEAL_LENGTH: f32 = 50.;
+pub const COOLING_FACTOR: f32 = 0.2;
+pub const NODE_TOTAL: usize = 50;
+pub const NODE_MASS: f32 = 5.;
+pub const COMPLIANCE: f32 = 0.001;
+pub const REPULSION_STRENGTH: f32 = 50.;
+pub const BARNES_HUT_THETA: f32 = 0.9;
+pub const EDGE_WIDTH: f32 = 1.5;
+pub const ARROW_SIZE: f32 = 8.;
+pub const EDGE_CURVATURE: f32 = 0.;
+pub const LABEL_FONT_SIZE: f32 = 12.;
+pub const LABEL_OFFSET: f32 = 4.;
+pub const WEIGHT_LENGTH_EXPONENT: f32 = 0.5;
+pub const WEIGHT_COMPLIANCE_EXPONENT: f32 = 1.;
+pub const CENTER_GRAVITY: f32 = 0.5;
+pub const CONVERGENCE_ENERGY: f32 = 1.;
+pub const SEED: u64 = 0;
+pub const EDGE_COLOR: Color = Color::srgba(1., 1., 1., 0.5);
+
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    pub placement: Placement,
+    pub ideal_length: f32,
+    pub cooling_factor: f32,
+    pub node_mass: f32,
+    pub compliance: f32,
+    /// How strongly edge weight shortens joints: a joint of weight `w` rests
+    /// at `ideal_length / w^weight_length_exponent`. `0` ignores weights.
+    pub weight_length_exponent: f32,
+    /// How strongly edge weight stiffens joints: a joint of weight `w` has
+    /// `compliance / w^weight_compliance_exponent`. `0` ignores weights.
+    pub weight_compliance_exponent: f32,
+    pub node_total: usize,
+    pub repulsion_strength: f32,
+    /// Barnes–Hut opening angle: larger is faster but coarser.
+    pub barnes_hut_theta: f32,
+    /// Pull towards `gravity_center` per unit of distance and mass, which
+    /// stops disconnected components drifting apart. `0` turns it off.
+    pub center_gravity: f32,
+    pub gravity_center: GravityCenter,
+    /// Average kinetic energy per node under which the physics layout counts
+    /// as converged and stops repelling nodes. `0` never stops.
+    pub convergence_energy: f32,
+    /// Put nodes to sleep once the active layout converges, so the joints
+    /// stop nudging them too.
+    pub sleep_when_converged: bool,
+    /// Thickness of rendered edges in world units.
+    pub edge_width: f32,
+    pub edge_color: Color,
+    /// Length of arrowheads on directed edges.
+    pub arrow_size: f32,
+    /// Sideways bend of every edge as a fraction of its length; `0` draws
+    /// straight lines. Parallel edges are fanned out around this.
+    pub edge_curvature: f32,
+    /// Label text height in screen pixels, whatever the zoom.
+    pub label_font_size: f32,
+    /// Gap between a node's rim and the bottom of its label.
+    pub label_offset: f32,
+    pub show_edge_labels: bool,
+    /// Move or hide labels that would overlap on screen.
+    pub declutter_labels: bool,
+    /// [`Pinned`](crate::Pinned) nodes when they are dragged.
+    pub pin_on_drag: bool,
+    /// Seed of the [`LayoutRng`], so the same graph and settings lay out the
+    /// same way on every run.
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            placement: Placement::default(),
+            ideal_length: IDEAL_LENGTH,
+            cooling_factor: COOLING_FACTOR,
+            node_mass: NODE_MASS,
+            compliance: COMPLIANCE,
+            weight_length_exponent: WEIGHT_LENGTH_EXPONENT,
+            weight_compliance_exponent: WEIGHT_COMPLIANCE_EXPONENT,
+            node_total: NODE_TOTAL,
+            repulsion_strength: REPULSION_STRENGTH,
+            barnes_hut_theta: BARNES_HUT_THETA,
+            center_gravity: CENTER_GRAVITY,
+            gravity_center: GravityCenter::default(),
+            convergence_energy: CONVERGENCE_ENERGY,
+            sleep_when_converged: true,
+            edge_width: EDGE_WIDTH,
+            edge_color: EDGE_COLOR,
+            arrow_size: ARROW_SIZE,
+            edge_curvature: EDGE_CURVATURE,
+            label_font_size: LABEL_FONT_SIZE,
+            label_offset: LABEL_OFFSET,
+            show_edge_labels: true,
+            declutter_labels: true,
+            pin_on_drag: true,
+            seed: SEED,
+        }
+    }
+}
+
+impl Config {
+    /// Rest length and compliance of the joint for an edge of `weight`.
+    /// Weights are floored at a tenth so near-zero weights don't blow joints
+    /// up to absurd lengths.
+    pub fn joint(&self, weight: f32) -> (f32, f32) {
+        let weight = weight.max(0.1);
+        (
+            self.ideal_length / weight.powf(self.weight_length_exponent),
+            self.compliance / weight.powf(self.weight_compliance_exponent),
+        )
+    }
+}
+
+/// Random source for everything the layout randomizes, from where new nodes
+/// are scattered to which way coincident nodes push each other. Reseeded
+/// whenever [`Config::seed`] changes.
+#[derive(Resource, Deref, DerefMut)]
+pub struct LayoutRng {
+    seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl LayoutRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl FromWorld for LayoutRng {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<Config>().seed)
+    }
+}
+
+pub(crate) fn reseed(config: Res<Config>, mut rng: ResMut<LayoutRng>) {
+    if rng.seed != config.seed {
+        *rng = LayoutRng::new(config.seed);
+    }
+}
@@ -0,0 +1,183 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_cursor::CursorLocation;
+
+use crate::{
+    config::Config,
+    edit::{AddEdge, SetPinned},
+    graph::{Edge, GraphNode, Pinned},
+    render::theme::Theme,
+    selection::Selected,
+};
+
+/// What dragging a node does. `E` switches between the modes and holding
+/// Shift while a drag starts uses [`Connect`](Self::Connect) for it.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DragMode {
+    /// Moves the node.
+    #[default]
+    Move,
+    /// Draws a new edge from the node to the one the drag ends on.
+    Connect,
+}
+
+/// Node an edge is being drawn from.
+#[derive(Resource)]
+struct PendingEdge(Entity);
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<DragMode>()
+        .add_observer(start_drag)
+        .add_observer(move_on_drag)
+        .add_observer(connect_on_drop)
+        .add_observer(end_drag)
+        .add_observer(forget_pending)
+        .add_observer(on_pin)
+        .add_observer(on_unpin)
+        .add_systems(
+            Update,
+            (
+                unpin_hotkeys,
+                toggle_mode,
+                draw_pending_edge.run_if(
+                    resource_exists::<PendingEdge>.and(resource_exists::<GizmoConfigStore>),
+                ),
+            ),
+        );
+}
+
+/// Pins the dragged node, like d3-force's fixed nodes, or starts an edge
+/// from it.
+fn start_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut cmd: Commands,
+    nodes: Query<(), With<GraphNode>>,
+    mode: Res<DragMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+    config: Res<Config>,
+    mut ev_w: EventWriter<SetPinned>,
+) {
+    let id = trigger.target;
+    if trigger.button != PointerButton::Primary || !nodes.contains(id) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if *mode == DragMode::Connect || shift {
+        cmd.insert_resource(PendingEdge(id));
+    } else if config.pin_on_drag {
+        ev_w.write(SetPinned {
+            node: id,
+            pinned: true,
+        });
+    }
+}
+
+fn move_on_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    mut transforms: Query<&mut Transform, With<GraphNode>>,
+    cursor: Res<CursorLocation>,
+    pending: Option<Res<PendingEdge>>,
+) {
+    if pending.is_some() {
+        return;
+    }
+    let Ok(mut transform) = transforms.get_mut(trigger.target) else {
+        return;
+    };
+    let Some(pos) = cursor.world_position() else {
+        return;
+    };
+    transform.translation = Vec3::new(pos.x, pos.y, 0.);
+}
+
+/// Dropping an edge drag on another node connects the two, unless the node
+/// it started from was deleted in the meantime.
+fn connect_on_drop(
+    trigger: Trigger<Pointer<DragDrop>>,
+    nodes: Query<(), With<GraphNode>>,
+    pending: Option<Res<PendingEdge>>,
+    mut ev_w: EventWriter<AddEdge>,
+) {
+    let target = trigger.target();
+    let Some(&PendingEdge(source)) = pending.as_deref() else {
+        return;
+    };
+    if source != target && nodes.contains(source) && nodes.contains(target) {
+        ev_w.write(AddEdge(Edge::new(source, target)));
+    }
+}
+
+fn end_drag(_: Trigger<Pointer<DragEnd>>, mut cmd: Commands) {
+    cmd.remove_resource::<PendingEdge>();
+}
+
+/// Drops the edge being drawn when its source node is deleted mid-drag.
+fn forget_pending(
+    trigger: Trigger<OnRemove, GraphNode>,
+    mut cmd: Commands,
+    pending: Option<Res<PendingEdge>>,
+) {
+    if pending.is_some_and(|p| p.0 == trigger.target()) {
+        cmd.remove_resource::<PendingEdge>();
+    }
+}
+
+/// Rubber band from the source node to the cursor.
+fn draw_pending_edge(
+    mut gizmos: Gizmos,
+    pending: Res<PendingEdge>,
+    nodes: Query<&Transform, With<GraphNode>>,
+    cursor: Res<CursorLocation>,
+    theme: Res<Theme>,
+) {
+    if let (Ok(source), Some(pos)) = (nodes.get(pending.0), cursor.world_position()) {
+        gizmos.line_2d(source.translation.xy(), pos, theme.selection);
+    }
+}
+
+/// `E` switches between moving nodes and drawing edges. Ctrl+E exports the
+/// layout instead.
+fn toggle_mode(keys: Res<ButtonInput<KeyCode>>, mut mode: ResMut<DragMode>) {
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyE) {
+        *mode = match *mode {
+            DragMode::Move => DragMode::Connect,
+            DragMode::Connect => DragMode::Move,
+        };
+    }
+}
+
+/// Pinned nodes become kinematic: the joints and forces no longer move them,
+/// but they still hold their neighbours in place.
+fn on_pin(trigger: Trigger<OnAdd, Pinned>, mut cmd: Commands) {
+    cmd.entity(trigger.target())
+        .insert((RigidBody::Kinematic, LinearVelocity::ZERO));
+}
+
+fn on_unpin(trigger: Trigger<OnRemove, Pinned>, mut cmd: Commands) {
+    if let Ok(mut node) = cmd.get_entity(trigger.target()) {
+        node.insert(RigidBody::Dynamic);
+    }
+}
+
+/// `U` releases the selected nodes and `Shift+U` every pinned node.
+fn unpin_hotkeys(
+    mut ev_w: EventWriter<SetPinned>,
+    keys: Res<ButtonInput<KeyCode>>,
+    pinned: Query<(Entity, Has<Selected>), With<Pinned>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyU) {
+        return;
+    }
+    let all = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for (id, selected) in &pinned {
+        if all || selected {
+            ev_w.write(SetPinned {
+                node: id,
+                pinned: false,
+            });
+        }
+    }
+}
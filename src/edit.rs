@@ -0,0 +1,304 @@
+use bevy::{platform::collections::HashSet, prelude::*, window::Window};
+use bevy_cursor::CursorLocation;
+
+use crate::{
+    graph::{Attributes, Edge, Graph, GraphNode, NodeId, NodeLabel, Pinned},
+    history::{GraphOp, History},
+    selection::Selection,
+    setup::GraphSpawner,
+    spec::{EdgeSpec, NodeSpec},
+};
+
+/// Longest gap between the two clicks of a double-click, in seconds.
+pub(crate) const DOUBLE_CLICK_SECS: f32 = 0.3;
+
+/// Farthest the pointer may move between the two clicks, in pixels.
+pub(crate) const DOUBLE_CLICK_SLOP: f32 = 5.;
+
+/// Spawns a node at `position`, with the next free numeric ID unless `id` is
+/// given.
+#[derive(Event, Clone, Debug)]
+pub struct AddNode {
+    pub id: Option<String>,
+    pub position: Vec2,
+}
+
+impl AddNode {
+    pub fn at(position: Vec2) -> Self {
+        Self { id: None, position }
+    }
+}
+
+/// Spawns an edge with its joint. Edges of a directed graph point from
+/// `source` to `target`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct AddEdge(pub Edge);
+
+/// Despawns nodes and edges along with their labels and drawing parts.
+/// Removing a node removes its edges too, whichever way it's despawned.
+#[derive(Event, Clone, Debug)]
+pub struct DeleteElements(pub Vec<Entity>);
+
+/// Pins or releases a node; see [`Pinned`].
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SetPinned {
+    pub node: Entity,
+    pub pinned: bool,
+}
+
+/// Sets a node attribute, or removes it if `value` is `None`. Setting `label`
+/// relabels the node.
+#[derive(Event, Clone, Debug)]
+pub struct SetAttribute {
+    pub node: Entity,
+    pub key: String,
+    pub value: Option<String>,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_event::<AddNode>()
+        .add_event::<AddEdge>()
+        .add_event::<DeleteElements>()
+        .add_event::<SetPinned>()
+        .add_event::<SetAttribute>()
+        .add_systems(
+            Update,
+            (
+                add_nodes,
+                add_edges,
+                set_pinned,
+                set_attributes,
+                delete_hotkey,
+                delete_elements,
+            )
+                .chain(),
+        )
+        .add_observer(add_on_double_click);
+}
+
+/// Writes `value` to `attributes`, keeping the node's [`NodeLabel`] in step.
+pub(crate) fn set_attribute(
+    cmd: &mut Commands,
+    node: Entity,
+    attributes: &mut Attributes,
+    key: &str,
+    value: Option<String>,
+) {
+    if key == "label" {
+        match &value {
+            Some(label) => cmd.entity(node).insert(NodeLabel(label.clone())),
+            None => cmd.entity(node).remove::<NodeLabel>(),
+        };
+    }
+    match value {
+        Some(value) => attributes.0.insert(key.to_string(), value),
+        None => attributes.0.remove(key),
+    };
+}
+
+fn add_edges(
+    mut events: EventReader<AddEdge>,
+    mut spawner: GraphSpawner,
+    nodes: Query<&NodeId>,
+    mut history: ResMut<History>,
+) {
+    let mut ops = Vec::new();
+    for &AddEdge(edge) in events.read() {
+        let graph = spawner.graph();
+        let (true, true, Ok(source), Ok(target)) = (
+            graph.contains_node(edge.source),
+            graph.contains_node(edge.target),
+            nodes.get(edge.source),
+            nodes.get(edge.target),
+        ) else {
+            warn!(
+                "not adding edge {} -> {}: unknown endpoint",
+                edge.source, edge.target
+            );
+            continue;
+        };
+        let spec = EdgeSpec::new(source.0.clone(), target.0.clone()).with_weight(edge.weight);
+        spawner.spawn_edge_spec(&spec, edge.source, edge.target);
+        ops.push(GraphOp::AddEdge(spec));
+    }
+    history.record(ops);
+}
+
+fn set_pinned(
+    mut cmd: Commands,
+    mut events: EventReader<SetPinned>,
+    nodes: Query<(&NodeId, Has<Pinned>), With<GraphNode>>,
+    mut history: ResMut<History>,
+) {
+    let mut ops = Vec::new();
+    for &SetPinned { node, pinned } in events.read() {
+        let Ok((id, was)) = nodes.get(node) else {
+            continue;
+        };
+        if was == pinned {
+            continue;
+        }
+        if pinned {
+            cmd.entity(node).insert(Pinned);
+        } else {
+            cmd.entity(node).remove::<Pinned>();
+        }
+        ops.push(GraphOp::SetPinned {
+            node: id.0.clone(),
+            pinned,
+        });
+    }
+    history.record(ops);
+}
+
+fn set_attributes(
+    mut cmd: Commands,
+    mut events: EventReader<SetAttribute>,
+    mut nodes: Query<(&NodeId, &mut Attributes), With<GraphNode>>,
+    mut history: ResMut<History>,
+) {
+    let mut ops = Vec::new();
+    for SetAttribute { node, key, value } in events.read() {
+        let Ok((id, mut attributes)) = nodes.get_mut(*node) else {
+            continue;
+        };
+        let old = attributes.0.get(key).cloned();
+        if old == *value {
+            continue;
+        }
+        set_attribute(&mut cmd, *node, &mut attributes, key, value.clone());
+        ops.push(GraphOp::SetAttribute {
+            node: id.0.clone(),
+            key: key.clone(),
+            old,
+            new: value.clone(),
+        });
+    }
+    history.record(ops);
+}
+
+/// `Delete` removes the selected nodes.
+fn delete_hotkey(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    mut ev_w: EventWriter<DeleteElements>,
+) {
+    if keys.just_pressed(KeyCode::Delete) && !selection.is_empty() {
+        ev_w.write(DeleteElements(selection.iter().collect()));
+    }
+}
+
+/// Despawns the elements, recording each one with everything needed to put
+/// it back. A node takes its edges with it, so those are recorded with the
+/// node rather than on their own.
+fn delete_elements(
+    mut cmd: Commands,
+    mut events: EventReader<DeleteElements>,
+    nodes: Query<(&NodeId, &Transform, &Attributes, Has<Pinned>), With<GraphNode>>,
+    edges: Query<(&Edge, Option<&Attributes>)>,
+    graph: Res<Graph>,
+    mut history: ResMut<History>,
+) {
+    let edge_spec = |id| {
+        let (edge, attributes) = edges.get(id).ok()?;
+        let (source, ..) = nodes.get(edge.source).ok()?;
+        let (target, ..) = nodes.get(edge.target).ok()?;
+        let mut spec = EdgeSpec::new(source.0.clone(), target.0.clone()).with_weight(edge.weight);
+        if let Some(attributes) = attributes {
+            spec.attributes = attributes.0.clone();
+        }
+        Some(spec)
+    };
+    let mut ops = Vec::new();
+    let mut recorded = HashSet::new();
+    for DeleteElements(ids) in events.read() {
+        for &id in ids {
+            if !recorded.insert(id) {
+                continue;
+            }
+            if let Ok((node_id, transform, attributes, pinned)) = nodes.get(id) {
+                let node = NodeSpec {
+                    id: node_id.0.clone(),
+                    position: Some(transform.translation.xy()),
+                    attributes: attributes.0.clone(),
+                };
+                let incident: Vec<_> = graph
+                    .neighbors(id)
+                    .filter(|&(_, edge)| recorded.insert(edge))
+                    .filter_map(|(_, edge)| edge_spec(edge))
+                    .collect();
+                ops.push(GraphOp::RemoveNode {
+                    node,
+                    edges: incident,
+                    pinned,
+                });
+            } else if let Some(spec) = edge_spec(id) {
+                ops.push(GraphOp::RemoveEdge(spec));
+            } else {
+                continue;
+            }
+            cmd.entity(id).despawn();
+        }
+    }
+    history.record(ops);
+}
+
+/// Double-clicking empty space, which the picking backends report on the
+/// window, adds a node under the cursor.
+fn add_on_double_click(
+    trigger: Trigger<Pointer<Click>>,
+    windows: Query<(), With<Window>>,
+    cursor: Res<CursorLocation>,
+    time: Res<Time>,
+    mut last: Local<Option<(f32, Vec2)>>,
+    mut ev_w: EventWriter<AddNode>,
+) {
+    if trigger.button != PointerButton::Primary || !windows.contains(trigger.target) {
+        return;
+    }
+    let now = (time.elapsed_secs(), trigger.pointer_location.position);
+    let double = last.is_some_and(|(at, pos)| {
+        now.0 - at <= DOUBLE_CLICK_SECS && now.1.distance(pos) <= DOUBLE_CLICK_SLOP
+    });
+    if !double {
+        *last = Some(now);
+        return;
+    }
+    *last = None;
+    if let Some(pos) = cursor.world_position() {
+        ev_w.write(AddNode::at(pos));
+    }
+}
+
+fn add_nodes(
+    mut events: EventReader<AddNode>,
+    mut spawner: GraphSpawner,
+    mut history: ResMut<History>,
+) {
+    // `Graph` only learns about new IDs once the commands are applied.
+    let mut added = HashSet::new();
+    let mut ops = Vec::new();
+    for AddNode { id, position } in events.read() {
+        let taken = |id: &str| added.contains(id) || spawner.graph().node_by_id(id).is_some();
+        let id = match id {
+            Some(id) if taken(id) => {
+                warn!("not adding node {id}: the ID is taken");
+                continue;
+            }
+            Some(id) => id.clone(),
+            None => (spawner.graph().node_count()..)
+                .map(|n| n.to_string())
+                .find(|id| !taken(id))
+                .unwrap_or_default(),
+        };
+        let node = NodeSpec::new(id.clone()).at(*position);
+        spawner.spawn_node(&node);
+        added.insert(id);
+        ops.push(GraphOp::AddNode {
+            node,
+            edges: Vec::new(),
+            pinned: false,
+        });
+    }
+    history.record(ops);
+}
@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    camera::FocusNode,
+    edit::{DOUBLE_CLICK_SECS, DOUBLE_CLICK_SLOP},
+    graph::{Graph, GraphNode},
+    layout::{PlacedNodes, place},
+};
+
+/// Ego-network view: only `center` and the nodes within `radius` hops of it
+/// are shown, and the layout runs again on just those. Everything else is
+/// [`FilteredOut`](crate::FilteredOut) until `center` is cleared, which
+/// moves the nodes back to where they were before. Double-clicking a node
+/// focuses on it and `Esc` returns to the full graph.
+#[derive(Resource, Clone, Debug)]
+pub struct EgoFocus {
+    pub center: Option<Entity>,
+    pub radius: usize,
+    members: HashSet<Entity>,
+    /// Positions from before focusing, to go back to.
+    saved: HashMap<Entity, Vec2>,
+}
+
+impl Default for EgoFocus {
+    fn default() -> Self {
+        Self {
+            center: None,
+            radius: 1,
+            members: HashSet::new(),
+            saved: HashMap::new(),
+        }
+    }
+}
+
+impl EgoFocus {
+    pub fn is_active(&self) -> bool {
+        self.center.is_some()
+    }
+
+    /// Whether `node` is shown, which every node is without a `center`.
+    pub fn contains(&self, node: Entity) -> bool {
+        self.center.is_none() || self.members.contains(&node)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<EgoFocus>()
+        .add_systems(
+            Update,
+            (
+                leave_on_escape,
+                find_members
+                    .before(crate::filter::apply_filter)
+                    .run_if(resource_changed::<EgoFocus>.or(resource_changed::<Graph>)),
+            )
+                .chain(),
+        )
+        .add_observer(focus_on_double_click);
+}
+
+fn focus_on_double_click(
+    trigger: Trigger<Pointer<Click>>,
+    nodes: Query<(), With<GraphNode>>,
+    time: Res<Time>,
+    mut last: Local<Option<(Entity, f32, Vec2)>>,
+    mut ego: ResMut<EgoFocus>,
+    mut ev_w: EventWriter<FocusNode>,
+) {
+    let node = trigger.target();
+    if trigger.button != PointerButton::Primary || !nodes.contains(node) {
+        return;
+    }
+    let now = (node, time.elapsed_secs(), trigger.pointer_location.position);
+    let double = last.is_some_and(|(n, at, pos)| {
+        n == node && now.1 - at <= DOUBLE_CLICK_SECS && now.2.distance(pos) <= DOUBLE_CLICK_SLOP
+    });
+    if !double {
+        *last = Some(now);
+        return;
+    }
+    *last = None;
+    ego.center = Some(node);
+    ev_w.write(FocusNode(node));
+}
+
+fn leave_on_escape(keys: Res<ButtonInput<KeyCode>>, mut ego: ResMut<EgoFocus>) {
+    if keys.just_pressed(KeyCode::Escape) && ego.is_active() {
+        ego.center = None;
+    }
+}
+
+/// Gathers the nodes around the center, saving every position on the way in
+/// and putting them back on the way out.
+fn find_members(mut ego: ResMut<EgoFocus>, graph: Res<Graph>, mut placed: PlacedNodes) {
+    let members = match ego.center {
+        Some(center) if graph.contains_node(center) => neighborhood(&graph, center, ego.radius),
+        _ => HashSet::new(),
+    };
+    if ego.center.is_some() && members.is_empty() {
+        // The center was deleted.
+        ego.center = None;
+    }
+    let e = ego.bypass_change_detection();
+    if e.center.is_some() && e.saved.is_empty() {
+        e.saved = graph
+            .nodes()
+            .filter_map(|id| Some((id, placed.position(id)?)))
+            .collect();
+    } else if e.center.is_none() && !e.saved.is_empty() {
+        place(&mut placed, e.saved.drain());
+    }
+    if members != e.members {
+        e.members = members;
+        ego.set_changed();
+    }
+}
+
+/// Nodes at most `radius` hops from `center`, either way along edges.
+fn neighborhood(graph: &Graph, center: Entity, radius: usize) -> HashSet<Entity> {
+    let mut seen = HashSet::from_iter([center]);
+    let mut queue = VecDeque::from([(center, 0)]);
+    while let Some((node, hops)) = queue.pop_front() {
+        if hops == radius {
+            continue;
+        }
+        for (neighbor, _) in graph.neighbors(node) {
+            if seen.insert(neighbor) {
+                queue.push_back((neighbor, hops + 1));
+            }
+        }
+    }
+    seen
+}
@@ -0,0 +1,144 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use crate::{
+    analysis::community::Community,
+    ego::EgoFocus,
+    graph::{Attributes, Graph, GraphNode, IncludeDisabled},
+    sample::GraphSample,
+};
+
+/// How an [`AttributeFilter`] compares a node's attribute with its value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Comparison {
+    #[default]
+    Equal,
+    NotEqual,
+    /// Numerically if both sides are numbers, lexically otherwise.
+    Less,
+    Greater,
+    Contains,
+}
+
+/// Keeps nodes whose attribute `key` compares to `value`; nodes without the
+/// attribute are left out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttributeFilter {
+    pub key: String,
+    pub comparison: Comparison,
+    pub value: String,
+}
+
+impl AttributeFilter {
+    pub fn matches(&self, attributes: Option<&Attributes>) -> bool {
+        let Some(actual) = attributes.and_then(|a| a.get(&self.key)) else {
+            return false;
+        };
+        let order = || match (
+            actual.trim().parse::<f64>(),
+            self.value.trim().parse::<f64>(),
+        ) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            _ => actual.cmp(self.value.as_str()),
+        };
+        match self.comparison {
+            Comparison::Equal => actual == self.value,
+            Comparison::NotEqual => actual != self.value,
+            Comparison::Less => order().is_lt(),
+            Comparison::Greater => order().is_gt(),
+            Comparison::Contains => actual.contains(&self.value),
+        }
+    }
+}
+
+/// Which nodes are shown. Nodes outside the degree range, failing the
+/// attribute filter, in a hidden [`Community`], outside the [`EgoFocus`] or
+/// left out of the [`GraphSample`] are [`FilteredOut`]: they
+/// are disabled along with their edges, leaving the layout, until the
+/// filter lets them back in. With `ghosts` on they stay in the layout,
+/// [`Dimmed`](crate::Dimmed), instead. Degrees count every edge, filtered
+/// or not.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NodeFilter {
+    pub min_degree: usize,
+    pub max_degree: Option<usize>,
+    pub attribute: Option<AttributeFilter>,
+    pub hidden_communities: HashSet<usize>,
+    pub ghosts: bool,
+}
+
+impl NodeFilter {
+    pub fn is_active(&self) -> bool {
+        self.min_degree > 0
+            || self.max_degree.is_some()
+            || self.attribute.is_some()
+            || !self.hidden_communities.is_empty()
+    }
+
+    /// Lets every node back in.
+    pub fn clear(&mut self) {
+        *self = Self {
+            ghosts: self.ghosts,
+            ..default()
+        };
+    }
+
+    pub fn passes(
+        &self,
+        degree: usize,
+        attributes: Option<&Attributes>,
+        community: Option<&Community>,
+    ) -> bool {
+        degree >= self.min_degree
+            && self.max_degree.is_none_or(|max| degree <= max)
+            && self
+                .attribute
+                .as_ref()
+                .is_none_or(|f| f.matches(attributes))
+            && community.is_none_or(|c| !self.hidden_communities.contains(&c.0))
+    }
+}
+
+/// Marks a node the [`NodeFilter`] leaves out.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct FilteredOut;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<NodeFilter>().add_systems(
+        Update,
+        apply_filter.before(crate::timeline::apply_timeline).run_if(
+            resource_changed::<NodeFilter>
+                .or(resource_changed::<EgoFocus>)
+                .or(resource_changed::<GraphSample>)
+                .or(resource_changed::<Graph>)
+                .or(any_match_filter::<(With<GraphNode>, Changed<Attributes>)>)
+                .or(any_match_filter::<Changed<Community>>),
+        ),
+    );
+}
+
+type Filterable = (
+    Entity,
+    Option<&'static Attributes>,
+    Option<&'static Community>,
+    Has<FilteredOut>,
+);
+
+pub(crate) fn apply_filter(
+    mut cmd: Commands,
+    filter: Res<NodeFilter>,
+    ego: Res<EgoFocus>,
+    sample: Res<GraphSample>,
+    graph: Res<Graph>,
+    nodes: Query<Filterable, (With<GraphNode>, IncludeDisabled)>,
+) {
+    for (id, attributes, community, filtered) in &nodes {
+        let pass = filter.passes(graph.degree(id), attributes, community)
+            && ego.contains(id)
+            && sample.contains(id);
+        if !pass && !filtered {
+            cmd.entity(id).insert(FilteredOut);
+        } else if pass && filtered {
+            cmd.entity(id).remove::<FilteredOut>();
+        }
+    }
+}
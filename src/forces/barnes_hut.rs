@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+
+/// Bodies deeper than this share a leaf instead of splitting further, which
+/// bounds the tree when several nodes sit on top of each other.
+const MAX_DEPTH: u32 = 24;
+const NONE: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+struct Cell {
+    center: Vec2,
+    half: f32,
+    mass: f32,
+    /// Mass-weighted position sum until [`QuadTree::build`] finishes, then
+    /// the centre of mass.
+    mass_center: Vec2,
+    /// Index of the first of four children, or [`NONE`] for leaves.
+    children: u32,
+    /// First body of a leaf; further bodies are chained through `next`.
+    body: u32,
+}
+
+impl Cell {
+    fn new(center: Vec2, half: f32) -> Self {
+        Self {
+            center,
+            half,
+            mass: 0.,
+            mass_center: Vec2::ZERO,
+            children: NONE,
+            body: NONE,
+        }
+    }
+
+    fn quadrant(&self, p: Vec2) -> u32 {
+        (p.x >= self.center.x) as u32 | (((p.y >= self.center.y) as u32) << 1)
+    }
+}
+
+/// Barnes–Hut quadtree over weighted points, answering "total repulsion on
+/// this point" in O(log n) by treating distant cells as single bodies.
+pub struct QuadTree {
+    cells: Vec<Cell>,
+    next: Vec<u32>,
+    points: Vec<(Vec2, f32)>,
+}
+
+impl QuadTree {
+    pub fn build(points: Vec<(Vec2, f32)>) -> Self {
+        let (min, max) = points.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &(p, _)| (min.min(p), max.max(p)),
+        );
+        let (center, half) = if points.is_empty() {
+            (Vec2::ZERO, 1.)
+        } else {
+            ((min + max) / 2., ((max - min).max_element() / 2.).max(1.))
+        };
+        let mut tree = Self {
+            cells: vec![Cell::new(center, half)],
+            next: vec![NONE; points.len()],
+            points,
+        };
+        for i in 0..tree.points.len() {
+            tree.insert(i as u32);
+        }
+        for cell in &mut tree.cells {
+            if cell.mass > 0. {
+                cell.mass_center /= cell.mass;
+            }
+        }
+        tree
+    }
+
+    fn insert(&mut self, body: u32) {
+        let (p, m) = self.points[body as usize];
+        let mut cell = 0;
+        let mut depth = 0;
+        loop {
+            let c = &mut self.cells[cell];
+            c.mass += m;
+            c.mass_center += p * m;
+            if c.children != NONE {
+                cell = (c.children + c.quadrant(p)) as usize;
+                depth += 1;
+                continue;
+            }
+            if c.body == NONE {
+                c.body = body;
+                return;
+            }
+            if depth >= MAX_DEPTH {
+                self.next[body as usize] = c.body;
+                self.cells[cell].body = body;
+                return;
+            }
+            self.split(cell);
+            depth += 1;
+            cell = (self.cells[cell].children + self.cells[cell].quadrant(p)) as usize;
+        }
+    }
+
+    /// Turns a leaf into four children and pushes its bodies one level down.
+    fn split(&mut self, cell: usize) {
+        let Cell { center, half, .. } = self.cells[cell];
+        let first = self.cells.len() as u32;
+        let h = half / 2.;
+        for q in 0..4 {
+            let offset = Vec2::new(
+                if q & 1 == 1 { h } else { -h },
+                if q & 2 == 2 { h } else { -h },
+            );
+            self.cells.push(Cell::new(center + offset, h));
+        }
+        let mut body = self.cells[cell].body;
+        self.cells[cell].children = first;
+        self.cells[cell].body = NONE;
+        while body != NONE {
+            let following = self.next[body as usize];
+            let (p, m) = self.points[body as usize];
+            let quadrant = self.cells[cell].quadrant(p);
+            let child = &mut self.cells[(first + quadrant) as usize];
+            child.mass += m;
+            child.mass_center += p * m;
+            self.next[body as usize] = child.body;
+            child.body = body;
+            body = following;
+        }
+    }
+
+    /// Sum of `kernel(offset, mass)` over every other body as seen from body
+    /// `index`, where `offset` points from the other body (or cell centre of
+    /// mass) to this one. Cells whose width over distance is below `theta` are
+    /// approximated as a single body.
+    pub fn accumulate(
+        &self,
+        index: usize,
+        theta: f32,
+        mut kernel: impl FnMut(Vec2, f32) -> Vec2,
+    ) -> Vec2 {
+        let p = self.points[index].0;
+        let mut total = Vec2::ZERO;
+        let mut stack = vec![0usize];
+        while let Some(i) = stack.pop() {
+            let cell = &self.cells[i];
+            if cell.mass == 0. {
+                continue;
+            }
+            if cell.children == NONE {
+                let mut body = cell.body;
+                while body != NONE {
+                    if body as usize != index {
+                        let (q, m) = self.points[body as usize];
+                        total += kernel(p - q, m);
+                    }
+                    body = self.next[body as usize];
+                }
+                continue;
+            }
+            let offset = p - cell.mass_center;
+            let dist = offset.length();
+            let contains = (p - cell.center).abs().max_element() <= cell.half;
+            if !contains && 2. * cell.half / dist < theta {
+                total += kernel(offset, cell.mass);
+            } else {
+                stack.extend((0..4).map(|q| (cell.children + q) as usize));
+            }
+        }
+        total
+    }
+}
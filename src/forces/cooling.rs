@@ -0,0 +1,128 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::Iterations;
+use crate::{config::Config, graph::GraphNode, layout::registry::drive_layout};
+
+/// Iterations in a row the energy has to fall before the adaptive schedule
+/// heats back up.
+const ADAPTIVE_PROGRESS: usize = 5;
+
+/// Annealing for the physics layout: a temperature, in ideal edge lengths,
+/// caps how far a node may move in one physics step and falls as
+/// [`Iterations`] grow, so the layout settles instead of jittering. Restarting
+/// the layout heats it back up.
+#[derive(Resource, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cooling {
+    pub schedule: CoolingSchedule,
+    pub initial_temperature: f32,
+    /// Floor the temperature settles at, so the layout still gives way when
+    /// nodes are dragged.
+    pub min_temperature: f32,
+    temperature: f32,
+    /// Iterations in a row the energy has fallen, for the adaptive schedule.
+    progress: usize,
+    energy: f32,
+}
+
+impl Default for Cooling {
+    fn default() -> Self {
+        Self {
+            schedule: CoolingSchedule::default(),
+            initial_temperature: 1.,
+            min_temperature: 0.02,
+            temperature: 1.,
+            progress: 0,
+            energy: f32::INFINITY,
+        }
+    }
+}
+
+/// How the [`Cooling`] temperature falls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoolingSchedule {
+    /// Straight down to `min_temperature` over `iterations`.
+    Linear { iterations: usize },
+    /// Multiplied by `decay` every iteration.
+    Exponential { decay: f32 },
+    /// Hu's adaptive scheme: multiplied by `decay` whenever the nodes' energy
+    /// rises and divided by it after a run of falls, so the layout cools only
+    /// as fast as it makes progress.
+    Adaptive { decay: f32 },
+}
+
+impl Default for CoolingSchedule {
+    fn default() -> Self {
+        Self::Exponential { decay: 0.99 }
+    }
+}
+
+impl Cooling {
+    /// Current displacement cap in ideal edge lengths per physics step.
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    /// Heats the layout back up to `initial_temperature`.
+    pub fn reset(&mut self) {
+        self.temperature = self.initial_temperature;
+        self.progress = 0;
+        self.energy = f32::INFINITY;
+    }
+
+    fn advance(&mut self, iteration: usize, energy: f32) {
+        let (hot, cold) = (self.initial_temperature, self.min_temperature);
+        self.temperature = match self.schedule {
+            CoolingSchedule::Linear { iterations } => {
+                hot.lerp(cold, (iteration as f32 / iterations.max(1) as f32).min(1.))
+            }
+            CoolingSchedule::Exponential { decay } => {
+                (hot * decay.powf(iteration as f32)).max(cold)
+            }
+            CoolingSchedule::Adaptive { decay } if energy < self.energy => {
+                self.progress += 1;
+                if self.progress < ADAPTIVE_PROGRESS {
+                    self.temperature
+                } else {
+                    self.progress = 0;
+                    (self.temperature / decay).min(hot)
+                }
+            }
+            CoolingSchedule::Adaptive { decay } => {
+                self.progress = 0;
+                (self.temperature * decay).max(cold)
+            }
+        };
+        self.energy = energy;
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Cooling>().add_systems(
+        Update,
+        cool.after(drive_layout)
+            .run_if(resource_changed::<Iterations>),
+    );
+}
+
+/// Takes the temperature down a notch per iteration and caps node speeds so
+/// no node moves further than it in a physics step.
+fn cool(
+    mut nodes: Query<(&Mass, &LinearVelocity, &mut MaxLinearSpeed), With<GraphNode>>,
+    mut cooling: ResMut<Cooling>,
+    iterations: Res<Iterations>,
+    config: Res<Config>,
+    fixed: Res<Time<Fixed>>,
+) {
+    let energy = nodes
+        .iter()
+        .map(|(mass, velocity, _)| 0.5 * mass.0 * velocity.length_squared())
+        .sum();
+    cooling.advance(iterations.0, energy);
+    let speed = cooling.temperature * config.ideal_length / fixed.timestep().as_secs_f32();
+    for (.., mut max_speed) in &mut nodes {
+        max_speed.set_if_neq(MaxLinearSpeed(speed));
+    }
+}
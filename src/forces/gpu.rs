@@ -0,0 +1,305 @@
+use avian2d::prelude::*;
+use bevy::{
+    asset::embedded_asset,
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSet,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_graph::{self, RenderGraph, RenderLabel},
+        render_resource::{
+            binding_types::{storage_buffer, storage_buffer_read_only, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        storage::{GpuShaderStorageBuffer, ShaderStorageBuffer},
+    },
+};
+
+use super::Iterations;
+use crate::{
+    config::Config,
+    graph::{Charge, GraphNode},
+    layout::{control::layout_running, registry::LayoutRegistry},
+};
+
+const SHADER_PATH: &str = "embedded://bevy_graph/forces/repulsion.wgsl";
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Generations wrap here so they stay exact when sent as an `f32`.
+const GENERATIONS: u32 = 1 << 24;
+
+/// Exact all-pairs repulsion computed by a compute pass instead of the
+/// Barnes–Hut tree. Forces are read back asynchronously, so the ones applied
+/// in a frame were computed from positions a frame or two old.
+pub(crate) struct GpuRepulsionPlugin;
+
+impl Plugin for GpuRepulsionPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "repulsion.wgsl");
+        app.init_resource::<GpuRepulsion>()
+            .add_plugins(ExtractResourcePlugin::<GpuBuffers>::default())
+            .add_systems(Startup, setup_buffers)
+            .add_systems(
+                Update,
+                upload_positions.run_if(layout_running.and(not(layout_converged))),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<RepulsionPipeline>().add_systems(
+            Render,
+            prepare_bind_group.in_set(RenderSet::PrepareBindGroups),
+        );
+        render_app
+            .world_mut()
+            .resource_mut::<RenderGraph>()
+            .add_node(RepulsionLabel, RepulsionNode);
+    }
+}
+
+#[derive(Resource, ExtractResource, Clone)]
+struct GpuBuffers {
+    positions: Handle<ShaderStorageBuffer>,
+    forces: Handle<ShaderStorageBuffer>,
+    count: u32,
+    k: f32,
+}
+
+/// Node order of the last upload and the latest forces read back. The
+/// generation goes up whenever the node order changes and comes back with
+/// the forces, so forces computed for an older order are never applied.
+#[derive(Resource, Default)]
+pub(super) struct GpuRepulsion {
+    nodes: Vec<Entity>,
+    generation: u32,
+    forces: Vec<Vec4>,
+}
+
+impl GpuRepulsion {
+    /// The forces read back, if they were computed for the current nodes.
+    fn current(&self) -> Option<impl Iterator<Item = Vec2> + '_> {
+        let generation = self.generation as f32;
+        let current =
+            self.forces.len() == self.nodes.len() && self.forces.iter().all(|f| f.z == generation);
+        current.then(|| self.forces.iter().map(|f| f.xy()))
+    }
+}
+
+/// Nothing moves once the layout has settled, so neither do the forces.
+fn layout_converged(registry: Res<LayoutRegistry>) -> bool {
+    registry.is_converged()
+}
+
+fn setup_buffers(mut cmd: Commands, mut buffers: ResMut<Assets<ShaderStorageBuffer>>) {
+    let positions = buffers.add(ShaderStorageBuffer::from(vec![Vec4::ZERO]));
+    let forces = buffers.add(forces_buffer(1));
+    cmd.spawn(Readback::buffer(forces.clone())).observe(
+        |trigger: Trigger<ReadbackComplete>, mut repulsion: ResMut<GpuRepulsion>| {
+            repulsion.forces = trigger.event().to_shader_type();
+        },
+    );
+    cmd.insert_resource(GpuBuffers {
+        positions,
+        forces,
+        count: 0,
+        k: 0.,
+    });
+}
+
+/// Buffers can't be empty, so room for at least one force is always kept. The
+/// pass is skipped while there are no nodes, so the padding is never read.
+fn forces_buffer(len: usize) -> ShaderStorageBuffer {
+    let mut buffer = ShaderStorageBuffer::with_size(
+        len.max(1) * size_of::<Vec4>(),
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    buffer.buffer_description.usage |= BufferUsages::COPY_SRC;
+    buffer
+}
+
+/// Uploads each node as `(x, y, charge, generation)`.
+fn upload_positions(
+    nodes: Query<(Entity, &Transform, Option<&Charge>), With<GraphNode>>,
+    config: Res<Config>,
+    mut gpu: ResMut<GpuBuffers>,
+    mut repulsion: ResMut<GpuRepulsion>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+) {
+    let ids: Vec<_> = nodes.iter().map(|(id, ..)| id).collect();
+    if ids != repulsion.nodes {
+        repulsion.generation = (repulsion.generation + 1) % GENERATIONS;
+        if ids.len() != repulsion.nodes.len() {
+            buffers.insert(&gpu.forces, forces_buffer(ids.len()));
+        }
+    }
+    let generation = repulsion.generation as f32;
+    let positions: Vec<_> = nodes
+        .iter()
+        .map(|(_, t, charge)| {
+            let q = charge.map_or(1., |c| c.0);
+            t.translation.xy().extend(q).extend(generation)
+        })
+        .collect();
+    if let Some(buffer) = buffers.get_mut(&gpu.positions) {
+        buffer.set_data(if positions.is_empty() {
+            vec![Vec4::ZERO]
+        } else {
+            positions
+        });
+    }
+    gpu.count = ids.len() as u32;
+    gpu.k = config.repulsion_strength * config.ideal_length.powi(2);
+    repulsion.nodes = ids;
+}
+
+pub(super) fn apply_repulsion(
+    mut nodes: Query<&mut ExternalForce, With<GraphNode>>,
+    repulsion: Res<GpuRepulsion>,
+    mut i: ResMut<Iterations>,
+) {
+    i.0 += 1;
+    let Some(forces) = repulsion.current() else {
+        return;
+    };
+    for (&id, push) in repulsion.nodes.iter().zip(forces) {
+        if let Ok(mut force) = nodes.get_mut(id) {
+            force.apply_force(push);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct RepulsionBindGroup {
+    bind_group: BindGroup,
+    count: u32,
+}
+
+fn prepare_bind_group(
+    mut cmd: Commands,
+    pipeline: Res<RepulsionPipeline>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    gpu: Option<Res<GpuBuffers>>,
+    buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+) {
+    let bound = gpu
+        .as_ref()
+        .and_then(|gpu| Some((gpu, buffers.get(&gpu.positions)?, buffers.get(&gpu.forces)?)));
+    let Some((gpu, positions, forces)) = bound else {
+        cmd.remove_resource::<RepulsionBindGroup>();
+        return;
+    };
+    let mut k = UniformBuffer::from(gpu.k);
+    k.write_buffer(&device, &queue);
+    let bind_group = device.create_bind_group(
+        "repulsion_bind_group",
+        &pipeline.layout,
+        &BindGroupEntries::sequential((
+            &k,
+            positions.buffer.as_entire_buffer_binding(),
+            forces.buffer.as_entire_buffer_binding(),
+        )),
+    );
+    cmd.insert_resource(RepulsionBindGroup {
+        bind_group,
+        count: gpu.count,
+    });
+}
+
+#[derive(Resource)]
+struct RepulsionPipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for RepulsionPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "repulsion_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    uniform_buffer::<f32>(false),
+                    storage_buffer_read_only::<Vec<Vec4>>(false),
+                    storage_buffer::<Vec<Vec4>>(false),
+                ),
+            ),
+        );
+        let shader = world.load_asset(SHADER_PATH);
+        let pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some("repulsion_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    push_constant_ranges: Vec::new(),
+                    shader,
+                    shader_defs: Vec::new(),
+                    entry_point: "main".into(),
+                    zero_initialize_workgroup_memory: false,
+                });
+        Self { layout, pipeline }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct RepulsionLabel;
+
+struct RepulsionNode;
+
+impl render_graph::Node for RepulsionNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some(bind_group) = world.get_resource::<RepulsionBindGroup>() else {
+            return Ok(());
+        };
+        let pipeline = world.resource::<RepulsionPipeline>();
+        let Some(compute) = world
+            .resource::<PipelineCache>()
+            .get_compute_pipeline(pipeline.pipeline)
+        else {
+            return Ok(());
+        };
+        if bind_group.count == 0 {
+            return Ok(());
+        }
+        let mut pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("repulsion_pass"),
+                    ..default()
+                });
+        pass.set_bind_group(0, &bind_group.bind_group, &[]);
+        pass.set_pipeline(compute);
+        pass.dispatch_workgroups(bind_group.count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forces_from_another_generation_are_dropped() {
+        let mut repulsion = GpuRepulsion {
+            nodes: vec![Entity::from_raw(0), Entity::from_raw(1)],
+            generation: 2,
+            forces: vec![Vec4::new(1., 0., 1., 0.), Vec4::new(-1., 0., 1., 0.)],
+        };
+        assert!(repulsion.current().is_none());
+        repulsion.forces.iter_mut().for_each(|f| f.z = 2.);
+        let forces: Vec<_> = repulsion.current().unwrap().collect();
+        assert_eq!(forces, [Vec2::X, Vec2::NEG_X]);
+    }
+}
@@ -0,0 +1,266 @@
+use avian2d::{math::PI, prelude::*};
+use bevy::{
+    prelude::*,
+    tasks::{ComputeTaskPool, ParallelSlice, TaskPool},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    config::{Config, LayoutRng},
+    graph::{Charge, GraphNode},
+    layout::registry::run_system,
+};
+
+mod barnes_hut;
+mod cooling;
+#[cfg(feature = "gpu-layout")]
+mod gpu;
+
+pub use barnes_hut::QuadTree;
+pub use cooling::{Cooling, CoolingSchedule};
+
+#[derive(Event)]
+pub struct DeltaV(pub Entity, pub Vec2);
+
+#[derive(Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Iterations(pub usize);
+
+/// Frames in a row the nodes' kinetic energy has to stay under
+/// [`Config::convergence_energy`] before the physics layout counts as settled.
+pub(crate) const SETTLE_FRAMES: usize = 30;
+
+/// Bodies per task when per-body force work is spread over the compute pool.
+/// Fixed rather than split by thread count, so anything seeded per chunk
+/// comes out the same on every machine.
+const PAR_CHUNK: usize = 256;
+
+/// Buffers [`par_accumulate`] splits its work between. Also fixed, so the
+/// buffers are summed in the same order everywhere.
+const PAR_BUFFERS: usize = 8;
+
+/// Frames in a row the nodes have been under the energy cutoff.
+#[derive(Resource, Default)]
+struct Calm(usize);
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins(cooling::plugin)
+        .insert_resource(Iterations(0))
+        .init_resource::<Calm>()
+        .add_event::<DeltaV>()
+        .add_systems(Update, clear_forces)
+        .add_systems(
+            PostUpdate,
+            (process_delta_v, measure_calm.after(PhysicsSet::Sync)),
+        );
+    // Without a renderer (headless apps) the GPU pass has nowhere to run.
+    #[cfg(feature = "gpu-layout")]
+    if app.get_sub_app(bevy::render::RenderApp).is_some() {
+        app.add_plugins(gpu::GpuRepulsionPlugin);
+    }
+}
+
+/// Point [`Config::center_gravity`] pulls nodes towards.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GravityCenter {
+    /// The mean position of all nodes, which keeps components together
+    /// without holding the graph in place.
+    #[default]
+    Centroid,
+    /// The world origin.
+    Origin,
+}
+
+/// One frame of the physics layout: repulsion plus centering gravity.
+pub(crate) fn physics_step(world: &mut World) {
+    repel_nodes(world);
+    run_system(world, gravitate);
+}
+
+/// Repulsion step of the physics layout, on the GPU when that backend is
+/// running and through the Barnes–Hut tree otherwise.
+fn repel_nodes(world: &mut World) {
+    #[cfg(feature = "gpu-layout")]
+    if world.contains_resource::<gpu::GpuRepulsion>() {
+        return run_system(world, gpu::apply_repulsion);
+    }
+    run_system(world, repel);
+}
+
+/// Pulls every node towards the [`GravityCenter`] with a force growing
+/// linearly with its distance, so drifting components always come back.
+fn gravitate(
+    mut nodes: Query<(&Transform, &Mass, &mut ExternalForce), With<GraphNode>>,
+    config: Res<Config>,
+) {
+    if config.center_gravity == 0. || nodes.is_empty() {
+        return;
+    }
+    let center = match config.gravity_center {
+        GravityCenter::Origin => Vec2::ZERO,
+        GravityCenter::Centroid => {
+            nodes.iter().map(|(t, ..)| t.translation.xy()).sum::<Vec2>() / nodes.iter().len() as f32
+        }
+    };
+    nodes
+        .par_iter_mut()
+        .for_each(|(transform, mass, mut force)| {
+            force.apply_force(
+                (center - transform.translation.xy()) * config.center_gravity * mass.0,
+            );
+        });
+}
+
+/// Whether the physics layout has settled, so it can stop pushing nodes.
+pub(crate) fn below_cutoff(world: &World) -> bool {
+    world.resource::<Calm>().0 >= SETTLE_FRAMES
+}
+
+/// Starts the physics layout over from the initial temperature, waking any
+/// nodes that were put to sleep.
+pub(crate) fn reheat(world: &mut World) {
+    world.resource_mut::<Calm>().0 = 0;
+    world.resource_mut::<Iterations>().0 = 0;
+    world.resource_mut::<Cooling>().reset();
+    let sleeping: Vec<_> = world
+        .query_filtered::<Entity, (With<GraphNode>, With<Sleeping>)>()
+        .iter(world)
+        .collect();
+    for id in sleeping {
+        world.entity_mut(id).remove::<Sleeping>();
+    }
+}
+
+fn measure_calm(
+    nodes: Query<(&Mass, &LinearVelocity), With<GraphNode>>,
+    config: Res<Config>,
+    mut calm: ResMut<Calm>,
+) {
+    let n = nodes.iter().len();
+    let energy: f32 = nodes
+        .iter()
+        .map(|(mass, velocity)| 0.5 * mass.0 * velocity.length_squared())
+        .sum();
+    if n > 0 && energy / (n as f32) < config.convergence_energy {
+        calm.0 += 1;
+    } else {
+        calm.0 = 0;
+    }
+}
+
+/// Zeroes the layout force on every node so the force systems can accumulate
+/// this frame's total from scratch. Forces that are already zero are left
+/// alone, since touching them would keep sleeping bodies awake.
+pub(crate) fn clear_forces(mut forces: Query<&mut ExternalForce, With<GraphNode>>) {
+    for mut force in &mut forces {
+        if force.force() != Vec2::ZERO {
+            force.clear();
+        }
+    }
+}
+
+/// Every node repels every other node, using a Barnes–Hut quadtree so the
+/// cost stays O(n log n). Charges are the bodies' masses in the tree, so a
+/// cell pushes with the total charge inside it.
+fn repel(
+    mut nodes: Query<(&Transform, Option<&Charge>, &mut ExternalForce), With<GraphNode>>,
+    config: Res<Config>,
+    mut rng: ResMut<LayoutRng>,
+    mut i: ResMut<Iterations>,
+) {
+    i.0 += 1;
+    let points: Vec<_> = nodes
+        .iter()
+        .map(|(t, charge, _)| (t.translation.xy(), charge.map_or(1., |c| c.0)))
+        .collect();
+    let charges: Vec<f32> = points.iter().map(|&(_, q)| q).collect();
+    let tree = QuadTree::build(points);
+    let k = config.repulsion_strength * config.ideal_length.powi(2);
+    // Each chunk nudges coincident nodes apart with its own generator,
+    // seeded from the layout's.
+    let seed: u64 = rng.r#gen();
+    let pushes = par_map_chunks(&charges, |start, charges| {
+        let mut rng = StdRng::seed_from_u64(seed ^ start as u64);
+        (start..)
+            .zip(charges)
+            .map(|(index, &q)| {
+                tree.accumulate(index, config.barnes_hut_theta, |offset, m| {
+                    repulsive_force(offset, k * q * m, &mut rng)
+                })
+            })
+            .collect()
+    });
+    for ((.., mut force), push) in nodes.iter_mut().zip(pushes) {
+        force.apply_force(push);
+    }
+}
+
+/// Runs `f` over `items` a [`PAR_CHUNK`] at a time on the compute task pool
+/// and joins the results in order. `f` gets the index of its chunk's first
+/// item along with the chunk.
+pub(crate) fn par_map_chunks<T: Sync, R: Send + 'static>(
+    items: &[T],
+    f: impl Fn(usize, &[T]) -> Vec<R> + Send + Sync,
+) -> Vec<R> {
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    items
+        .par_chunk_map(pool, PAR_CHUNK, |chunk, items| f(chunk * PAR_CHUNK, items))
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Forces on `n` bodies made up of contributions from `items`, such as edges
+/// pulling on both their ends. Each task adds its share of `items` into its
+/// own buffer with `f` and the buffers are summed at the end, so threads
+/// never write to the same force.
+pub(crate) fn par_accumulate<T: Sync>(
+    items: &[T],
+    n: usize,
+    f: impl Fn(&T, &mut [Vec2]) + Send + Sync,
+) -> Vec<Vec2> {
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let chunk = items.len().div_ceil(PAR_BUFFERS).max(1);
+    items
+        .par_chunk_map(pool, chunk, |_, items| {
+            let mut forces = vec![Vec2::ZERO; n];
+            for item in items {
+                f(item, &mut forces);
+            }
+            forces
+        })
+        .into_iter()
+        .reduce(|mut total, forces| {
+            for (t, f) in total.iter_mut().zip(forces) {
+                *t += f;
+            }
+            total
+        })
+        .unwrap_or_else(|| vec![Vec2::ZERO; n])
+}
+
+/// Applies the frame's [`DeltaV`]s. Nodes can be deleted between an event
+/// being sent and read, so events for entities that are gone, or no longer
+/// nodes, are dropped along with the rest once read.
+fn process_delta_v(
+    mut ev_r: EventReader<DeltaV>,
+    mut query: Query<&mut LinearVelocity, With<GraphNode>>,
+) {
+    for &DeltaV(id, dv) in ev_r.read() {
+        let Ok(mut v) = query.get_mut(id) else {
+            continue;
+        };
+        v.0 += dv;
+    }
+}
+
+/// FR-style `k / d` push along `offset`, which points away from the source.
+fn repulsive_force(offset: Vec2, k: f32, rng: &mut impl Rng) -> Vec2 {
+    let dist_sq = offset.length_squared();
+    if dist_sq == 0. {
+        let angle = rng.gen_range(-PI..=PI);
+        return Vec2::from_angle(angle) * k;
+    }
+    offset * (k / dist_sq)
+}
@@ -0,0 +1,26 @@
+@group(0) @binding(0) var<uniform> k: f32;
+// Each node as (x, y, charge, generation).
+@group(0) @binding(1) var<storage, read> positions: array<vec4<f32>>;
+// Each force as (x, y, generation, 0), so the CPU can tell which upload it
+// belongs to.
+@group(0) @binding(2) var<storage, read_write> forces: array<vec4<f32>>;
+
+// Same `k q_i q_j / d` push as the CPU path, summed over every other node.
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    let count = arrayLength(&positions);
+    if i >= count {
+        return;
+    }
+    let p = positions[i].xy;
+    var total = vec2<f32>(0.0);
+    for (var j = 0u; j < count; j++) {
+        let offset = p - positions[j].xy;
+        let dist_sq = dot(offset, offset);
+        if j != i && dist_sq > 0.0 {
+            total += offset * (k * positions[j].z / dist_sq);
+        }
+    }
+    forces[i] = vec4<f32>(total * positions[i].z, positions[i].w, 0.0);
+}
@@ -0,0 +1,359 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::entity_disabling::Disabled,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphNode;
+
+/// Stable, user-facing identifier of a node, as found in the source data.
+#[derive(Component, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(pub String);
+
+/// Text drawn next to a node. Spawned from the `label` attribute of the
+/// source data.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeLabel(pub String);
+
+/// Holds a node where it is: the layout moves the other nodes around it.
+/// Dragged nodes are pinned when [`Config::pin_on_drag`](crate::Config::pin_on_drag)
+/// is set; `U` unpins the selected nodes.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pinned;
+
+/// How strongly a node repels others in the physics layout, `1` when absent.
+/// The push between two nodes scales with the product of their charges, so
+/// giving hubs more charge spreads out their neighbourhoods. Spawned from the
+/// `charge` attribute of the source data.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Charge(pub f32);
+
+/// Radius of the node's circle in world units.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeRadius(pub f32);
+
+/// Free-form key/value data carried over from the source file, on both node
+/// and edge entities.
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attributes(pub BTreeMap<String, String>);
+
+impl Attributes {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        self.get(key)?.trim().parse().ok()
+    }
+}
+
+/// An edge of the layout graph. Lives on the same entity as the
+/// [`DistanceJoint`](avian2d::prelude::DistanceJoint) that pulls its endpoints
+/// together, except for self-loops, whose `source` and `target` are the same
+/// node and which get no joint. Re-insert the component to change endpoints
+/// so [`Graph`] stays in sync; `weight` can be mutated in place.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edge {
+    pub source: Entity,
+    pub target: Entity,
+    pub weight: f32,
+}
+
+impl Edge {
+    pub fn new(source: Entity, target: Entity) -> Self {
+        Self {
+            source,
+            target,
+            weight: 1.,
+        }
+    }
+
+    pub fn with_weight(self, weight: f32) -> Self {
+        Self { weight, ..self }
+    }
+
+    pub fn other(&self, node: Entity) -> Entity {
+        if node == self.source {
+            self.target
+        } else {
+            self.source
+        }
+    }
+}
+
+/// Extra weight for keeping a node's label when labels would overlap. Labels
+/// are otherwise kept in order of node degree.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabelPriority(pub f32);
+
+/// Text drawn along the middle of an [`Edge`]. Spawned from the `label`
+/// attribute of the source data.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeLabel(pub String);
+
+/// Rest length of an edge's joint, overriding the one
+/// [`Config::joint`](crate::Config::joint) derives from the edge's weight.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeLength(pub f32);
+
+/// Compliance, the inverse of stiffness, of an edge's joint, overriding the
+/// one [`Config::joint`](crate::Config::joint) derives from the edge's weight.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeCompliance(pub f32);
+
+/// Which ends of an [`Edge`] get an arrowhead. Edges without this component
+/// are drawn as plain lines; edges of a directed [`GraphSpec`](crate::GraphSpec)
+/// get [`Forward`](Self::Forward) when spawned.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeDirection {
+    /// From `source` to `target`.
+    #[default]
+    Forward,
+    /// From `target` to `source`.
+    Backward,
+    /// Both ways.
+    Both,
+}
+
+impl EdgeDirection {
+    pub fn points_to_target(self) -> bool {
+        matches!(self, Self::Forward | Self::Both)
+    }
+
+    pub fn points_to_source(self) -> bool {
+        matches!(self, Self::Backward | Self::Both)
+    }
+}
+
+/// Query filter matching every entity that belongs to the graph.
+pub(crate) type GraphElement = Or<(With<GraphNode>, With<Edge>)>;
+
+/// Query filter letting disabled entities through, such as the elements the
+/// [`Timeline`](crate::Timeline) hides.
+pub(crate) type IncludeDisabled = Or<(With<Disabled>, Without<Disabled>)>;
+
+#[derive(Default, Debug)]
+struct Links {
+    outgoing: Vec<(Entity, Entity)>,
+    incoming: Vec<(Entity, Entity)>,
+}
+
+/// Topology of every [`GraphNode`] and [`Edge`] in the world, kept up to date
+/// by observers so systems can walk the graph without touching physics joints.
+#[derive(Resource, Default, Debug)]
+pub struct Graph {
+    nodes: HashMap<Entity, Links>,
+    edges: HashMap<Entity, (Entity, Entity)>,
+    ids: HashMap<String, Entity>,
+    pub(crate) directed: bool,
+}
+
+impl Graph {
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn contains_node(&self, node: Entity) -> bool {
+        self.nodes.contains_key(&node)
+    }
+
+    pub fn node_by_id(&self, id: &str) -> Option<Entity> {
+        self.ids.get(id).copied()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    /// `(edge, source, target)` for every edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Entity, Entity, Entity)> + '_ {
+        self.edges.iter().map(|(&e, &(s, t))| (e, s, t))
+    }
+
+    pub fn endpoints(&self, edge: Entity) -> Option<(Entity, Entity)> {
+        self.edges.get(&edge).copied()
+    }
+
+    /// `(neighbor, edge)` pairs reached by following edges out of `node`.
+    pub fn successors(&self, node: Entity) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.nodes
+            .get(&node)
+            .into_iter()
+            .flat_map(|l| l.outgoing.iter().copied())
+    }
+
+    /// `(neighbor, edge)` pairs whose edges point into `node`.
+    pub fn predecessors(&self, node: Entity) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.nodes
+            .get(&node)
+            .into_iter()
+            .flat_map(|l| l.incoming.iter().copied())
+    }
+
+    /// `(neighbor, edge)` pairs regardless of edge direction.
+    pub fn neighbors(&self, node: Entity) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.successors(node).chain(self.predecessors(node))
+    }
+
+    pub fn degree(&self, node: Entity) -> usize {
+        self.nodes
+            .get(&node)
+            .map_or(0, |l| l.outgoing.len() + l.incoming.len())
+    }
+
+    pub fn edges_between(&self, a: Entity, b: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.neighbors(a)
+            .filter(move |&(n, _)| n == b)
+            .map(|(_, e)| e)
+    }
+
+    /// Nodes grouped into connected components, ignoring edge direction.
+    pub fn components(&self) -> Vec<Vec<Entity>> {
+        let mut seen = HashSet::new();
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut i = 0;
+            while let Some(&node) = component.get(i) {
+                for (neighbor, _) in self.neighbors(node) {
+                    if seen.insert(neighbor) {
+                        component.push(neighbor);
+                    }
+                }
+                i += 1;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    fn link(&mut self, edge: Entity, source: Entity, target: Entity) {
+        self.edges.insert(edge, (source, target));
+        self.nodes
+            .entry(source)
+            .or_default()
+            .outgoing
+            .push((target, edge));
+        self.nodes
+            .entry(target)
+            .or_default()
+            .incoming
+            .push((source, edge));
+    }
+
+    fn unlink(&mut self, edge: Entity) {
+        let Some((source, target)) = self.edges.remove(&edge) else {
+            return;
+        };
+        if let Some(l) = self.nodes.get_mut(&source) {
+            l.outgoing.retain(|&(_, e)| e != edge);
+        }
+        if let Some(l) = self.nodes.get_mut(&target) {
+            l.incoming.retain(|&(_, e)| e != edge);
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Graph>()
+        .register_type::<GraphNode>()
+        .register_type::<NodeId>()
+        .register_type::<Pinned>()
+        .register_type::<Attributes>()
+        .register_type::<Edge>()
+        .register_type::<EdgeLength>()
+        .register_type::<EdgeCompliance>()
+        .register_type::<EdgeDirection>()
+        .add_observer(on_add_node)
+        .add_observer(on_remove_node)
+        .add_observer(on_insert_edge)
+        .add_observer(on_replace_edge)
+        .add_observer(on_insert_id)
+        .add_observer(on_replace_id);
+}
+
+fn on_add_node(trigger: Trigger<OnAdd, GraphNode>, mut graph: ResMut<Graph>) {
+    graph.nodes.entry(trigger.target()).or_default();
+}
+
+/// Edges can't outlive their endpoints: their joints would point at nothing.
+fn on_remove_node(
+    trigger: Trigger<OnRemove, GraphNode>,
+    mut cmd: Commands,
+    mut graph: ResMut<Graph>,
+) {
+    let Some(links) = graph.nodes.remove(&trigger.target()) else {
+        return;
+    };
+    for (_, edge) in links.outgoing.into_iter().chain(links.incoming) {
+        graph.unlink(edge);
+        cmd.entity(edge).try_despawn();
+    }
+}
+
+fn on_insert_edge(trigger: Trigger<OnInsert, Edge>, edges: Query<&Edge>, mut graph: ResMut<Graph>) {
+    let id = trigger.target();
+    let Ok(edge) = edges.get(id) else {
+        return;
+    };
+    graph.link(id, edge.source, edge.target);
+}
+
+fn on_replace_edge(trigger: Trigger<OnReplace, Edge>, mut graph: ResMut<Graph>) {
+    graph.unlink(trigger.target());
+}
+
+fn on_insert_id(trigger: Trigger<OnInsert, NodeId>, ids: Query<&NodeId>, mut graph: ResMut<Graph>) {
+    let Ok(id) = ids.get(trigger.target()) else {
+        return;
+    };
+    graph.ids.insert(id.0.clone(), trigger.target());
+}
+
+fn on_replace_id(
+    trigger: Trigger<OnReplace, NodeId>,
+    ids: Query<&NodeId>,
+    mut graph: ResMut<Graph>,
+) {
+    let Ok(id) = ids.get(trigger.target()) else {
+        return;
+    };
+    if graph.ids.get(&id.0) == Some(&trigger.target()) {
+        graph.ids.remove(&id.0);
+    }
+}
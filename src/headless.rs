@@ -0,0 +1,112 @@
+use std::{fs, path::PathBuf};
+
+use avian2d::prelude::*;
+use bevy::{input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    io::{self, GraphSnapshot},
+    layout::registry::LayoutRegistry,
+    selection::Selection,
+};
+
+/// Lays the [`GraphSpec`](crate::GraphSpec) out without a window or
+/// renderer, for batch pipelines and CI. Add it next to `MinimalPlugins`
+/// instead of [`GraphLayoutPlugin`](crate::GraphLayoutPlugin): it steps the
+/// active algorithm as fast as it can until it converges or
+/// `max_iterations` frames have passed, writes the positions to `output`
+/// and exits.
+///
+/// Every frame advances time by exactly one fixed timestep, so with the same
+/// [`Config::seed`](crate::Config::seed) a run gives the same layout every
+/// time.
+#[derive(Clone, Debug)]
+pub struct HeadlessLayoutPlugin {
+    pub max_iterations: usize,
+    /// Layout JSON for a `.json` path, otherwise any format
+    /// [`io::save`] writes.
+    pub output: PathBuf,
+}
+
+/// The run a [`HeadlessLayoutPlugin`] was set up for.
+#[derive(Resource, Clone, Debug)]
+pub struct Headless {
+    pub max_iterations: usize,
+    pub output: PathBuf,
+    /// Frames stepped so far.
+    pub iterations: usize,
+}
+
+impl Plugin for HeadlessLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PhysicsSchedulePlugin>() {
+            app.add_plugins(PhysicsPlugins::default());
+        }
+        // The hotkey systems read keyboard input, which just never comes.
+        if !app.is_plugin_added::<InputPlugin>() {
+            app.add_plugins(InputPlugin);
+        }
+        if !app.is_plugin_added::<TransformPlugin>() {
+            app.add_plugins(TransformPlugin);
+        }
+        // Avian's collider constructors look for scenes to spawn in, though
+        // there never are any.
+        app.init_resource::<SceneSpawner>();
+        // Read by the edit hotkeys; nothing ever gets selected.
+        app.init_resource::<Selection>();
+        let timestep = app
+            .world()
+            .get_resource::<Time<Fixed>>()
+            .map_or(Time::<Fixed>::default().timestep(), Time::timestep);
+        super::simulation(app);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+            .insert_resource(Headless {
+                max_iterations: self.max_iterations,
+                output: self.output.clone(),
+                iterations: 0,
+            })
+            .add_systems(Last, finish);
+    }
+}
+
+/// Writes the positions and exits once the layout converged or ran out of
+/// iterations.
+fn finish(
+    mut headless: ResMut<Headless>,
+    registry: Res<LayoutRegistry>,
+    snapshot: GraphSnapshot,
+    mut exit: EventWriter<AppExit>,
+) {
+    headless.iterations += 1;
+    let converged = registry.is_converged();
+    if !converged && headless.iterations < headless.max_iterations {
+        return;
+    }
+    if converged {
+        info!("layout converged after {} iterations", headless.iterations);
+    } else {
+        warn!(
+            "layout didn't converge within {} iterations",
+            headless.max_iterations
+        );
+    }
+    let path = &headless.output;
+    let spec = snapshot.spec();
+    let written = if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+    {
+        fs::write(path, io::layout::write(&spec)).map_err(io::GraphIoError::from)
+    } else {
+        io::save(path, &spec)
+    };
+    match written {
+        Ok(()) => {
+            info!("wrote layout to {}", path.display());
+            exit.write(AppExit::Success);
+        }
+        Err(e) => {
+            error!("failed to write layout to {}: {e}", path.display());
+            exit.write(AppExit::error());
+        }
+    }
+}
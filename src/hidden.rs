@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::{
+    analysis::spanning::OffTree,
+    graph::{Edge, GraphNode},
+    render::{edges::EdgeSystems, lod::Subsampled},
+};
+
+/// Marks a node or edge the user hid, from the context menu or the
+/// inspector. Nodes and edges are drawn only while nothing hides them, be it
+/// this, the [`SpanningTree`](crate::SpanningTree) or the
+/// [`LevelOfDetail`](crate::LevelOfDetail), so insert or remove the marker
+/// rather than setting `Visibility`, which follows every reason.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component)]
+pub struct Hidden;
+
+/// Nodes and edges, with whether each reason to hide them applies.
+type Elements<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Visibility,
+        Has<Hidden>,
+        Has<OffTree>,
+        Has<Subsampled>,
+    ),
+    Or<(With<GraphNode>, With<Edge>)>,
+>;
+
+/// Elements one of the reasons was just given to.
+type NewlyHidden = Or<(Added<Hidden>, Added<OffTree>, Added<Subsampled>)>;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<Hidden>().add_systems(
+        PostUpdate,
+        sync_visibility
+            .after(EdgeSystems::Trace)
+            .before(EdgeSystems::Draw),
+    );
+}
+
+/// Hides elements while any reason applies and shows them once the last one
+/// is gone, touching only those whose reasons changed.
+fn sync_visibility(
+    mut elements: Elements,
+    added: Query<Entity, NewlyHidden>,
+    mut hidden: RemovedComponents<Hidden>,
+    mut off_tree: RemovedComponents<OffTree>,
+    mut subsampled: RemovedComponents<Subsampled>,
+) {
+    let changed: Vec<Entity> = added
+        .iter()
+        .chain(hidden.read())
+        .chain(off_tree.read())
+        .chain(subsampled.read())
+        .collect();
+    for id in changed {
+        let Ok((mut visibility, hidden, off_tree, subsampled)) = elements.get_mut(id) else {
+            continue;
+        };
+        visibility.set_if_neq(if hidden || off_tree || subsampled {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shown_only_once_every_reason_is_gone() {
+        let mut app = App::new();
+        app.add_plugins(plugin);
+        let node = app
+            .world_mut()
+            .spawn((GraphNode, Visibility::default()))
+            .id();
+        let visibility = |app: &App| *app.world().get::<Visibility>(node).unwrap();
+
+        app.world_mut()
+            .entity_mut(node)
+            .insert((Hidden, OffTree, Subsampled));
+        app.update();
+        assert_eq!(visibility(&app), Visibility::Hidden);
+
+        app.world_mut().entity_mut(node).remove::<OffTree>();
+        app.update();
+        assert_eq!(visibility(&app), Visibility::Hidden);
+
+        app.world_mut().entity_mut(node).remove::<Subsampled>();
+        app.update();
+        assert_eq!(visibility(&app), Visibility::Hidden);
+
+        app.world_mut().entity_mut(node).remove::<Hidden>();
+        app.update();
+        assert_eq!(visibility(&app), Visibility::Inherited);
+    }
+}
@@ -0,0 +1,393 @@
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    edit::set_attribute,
+    graph::{Attributes, Edge, GraphNode, Pinned},
+    setup::GraphSpawner,
+    spec::{EdgeSpec, NodeSpec},
+};
+
+/// Reversible graph edit. Nodes are named by [`NodeId`](crate::NodeId)
+/// rather than entity, so an edit still applies after undo respawned the
+/// nodes it touches. Added and removed nodes carry their position in `node`
+/// and whether they're [`Pinned`].
+#[derive(Clone, Debug)]
+pub(crate) enum GraphOp {
+    AddNode {
+        node: NodeSpec,
+        edges: Vec<EdgeSpec>,
+        pinned: bool,
+    },
+    RemoveNode {
+        node: NodeSpec,
+        edges: Vec<EdgeSpec>,
+        pinned: bool,
+    },
+    AddEdge(EdgeSpec),
+    RemoveEdge(EdgeSpec),
+    SetAttribute {
+        node: String,
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SetPinned {
+        node: String,
+        pinned: bool,
+    },
+}
+
+impl GraphOp {
+    fn inverse(&self) -> Self {
+        match self.clone() {
+            Self::AddNode {
+                node,
+                edges,
+                pinned,
+            } => Self::RemoveNode {
+                node,
+                edges,
+                pinned,
+            },
+            Self::RemoveNode {
+                node,
+                edges,
+                pinned,
+            } => Self::AddNode {
+                node,
+                edges,
+                pinned,
+            },
+            Self::AddEdge(edge) => Self::RemoveEdge(edge),
+            Self::RemoveEdge(edge) => Self::AddEdge(edge),
+            Self::SetAttribute {
+                node,
+                key,
+                old,
+                new,
+            } => Self::SetAttribute {
+                node,
+                key,
+                old: new,
+                new: old,
+            },
+            Self::SetPinned { node, pinned } => Self::SetPinned {
+                node,
+                pinned: !pinned,
+            },
+        }
+    }
+}
+
+/// Edits made through [`AddNode`](crate::AddNode),
+/// [`AddEdge`](crate::AddEdge), [`DeleteElements`](crate::DeleteElements),
+/// [`SetPinned`](crate::SetPinned) and [`SetAttribute`](crate::SetAttribute),
+/// one step per frame's worth of each. `Ctrl+Z` undoes a step and `Ctrl+Y`
+/// or `Ctrl+Shift+Z` redoes it.
+#[derive(Resource, Default, Debug)]
+pub struct History {
+    undo: Vec<Vec<GraphOp>>,
+    redo: Vec<Vec<GraphOp>>,
+}
+
+impl History {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Pushes a step, dropping everything that could be redone.
+    pub(crate) fn record(&mut self, ops: Vec<GraphOp>) {
+        if ops.is_empty() {
+            return;
+        }
+        self.undo.push(ops);
+        self.redo.clear();
+    }
+}
+
+/// Reverts the last step in the [`History`].
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Undo;
+
+/// Reapplies the last undone step.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Redo;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<History>()
+        .add_event::<Undo>()
+        .add_event::<Redo>()
+        .add_systems(Update, (hotkeys, undo_redo).chain());
+}
+
+fn hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut undo: EventWriter<Undo>,
+    mut redo: EventWriter<Redo>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)) {
+        redo.write(Redo);
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        undo.write(Undo);
+    }
+}
+
+/// Takes at most one step a frame, since `Graph` only catches up with the
+/// nodes a step respawns once the commands are applied.
+fn undo_redo(
+    mut undos: EventReader<Undo>,
+    mut redos: EventReader<Redo>,
+    mut history: ResMut<History>,
+    mut replay: Replay,
+) {
+    let undo = undos.read().count() > 0;
+    let redo = redos.read().count() > 0;
+    if undo {
+        if let Some(ops) = history.undo.pop() {
+            let inverse: Vec<_> = ops.iter().rev().map(GraphOp::inverse).collect();
+            replay.apply(&inverse);
+            history.redo.push(ops);
+        }
+    } else if redo && let Some(ops) = history.redo.pop() {
+        replay.apply(&ops);
+        history.undo.push(ops);
+    }
+}
+
+#[derive(SystemParam)]
+struct Replay<'w, 's> {
+    spawner: GraphSpawner<'w, 's>,
+    edges: Query<'w, 's, &'static Edge>,
+    attributes: Query<'w, 's, &'static mut Attributes, With<GraphNode>>,
+}
+
+impl Replay<'_, '_> {
+    fn apply(&mut self, ops: &[GraphOp]) {
+        // Nodes respawned by this step, which `Graph` doesn't know yet.
+        let mut spawned = HashMap::new();
+        for op in ops {
+            match op {
+                GraphOp::AddNode {
+                    node,
+                    edges,
+                    pinned,
+                } => {
+                    let id = self.spawner.spawn_node(node);
+                    if *pinned {
+                        self.spawner.cmd.entity(id).insert(Pinned);
+                    }
+                    spawned.insert(node.id.clone(), id);
+                    for edge in edges {
+                        self.add_edge(edge, &spawned);
+                    }
+                }
+                GraphOp::RemoveNode { node, .. } => {
+                    if let Some(id) = self.node(&node.id, &spawned) {
+                        self.spawner.cmd.entity(id).despawn();
+                    }
+                }
+                GraphOp::AddEdge(edge) => self.add_edge(edge, &spawned),
+                GraphOp::RemoveEdge(edge) => {
+                    let (Some(source), Some(target)) = (
+                        self.node(&edge.source, &spawned),
+                        self.node(&edge.target, &spawned),
+                    ) else {
+                        continue;
+                    };
+                    let found = self
+                        .spawner
+                        .graph()
+                        .edges_between(source, target)
+                        .find(|&e| {
+                            self.edges
+                                .get(e)
+                                .is_ok_and(|e| e.source == source && e.weight == edge.weight)
+                        });
+                    if let Some(id) = found {
+                        self.spawner.cmd.entity(id).despawn();
+                    }
+                }
+                GraphOp::SetAttribute { node, key, new, .. } => {
+                    let Some(id) = self.node(node, &spawned) else {
+                        continue;
+                    };
+                    if let Ok(mut attributes) = self.attributes.get_mut(id) {
+                        set_attribute(&mut self.spawner.cmd, id, &mut attributes, key, new.clone());
+                    }
+                }
+                GraphOp::SetPinned { node, pinned } => {
+                    let Some(id) = self.node(node, &spawned) else {
+                        continue;
+                    };
+                    if *pinned {
+                        self.spawner.cmd.entity(id).insert(Pinned);
+                    } else {
+                        self.spawner.cmd.entity(id).remove::<Pinned>();
+                    }
+                }
+            }
+        }
+    }
+
+    fn node(&self, id: &str, spawned: &HashMap<String, Entity>) -> Option<Entity> {
+        let node = spawned
+            .get(id)
+            .copied()
+            .or_else(|| self.spawner.graph().node_by_id(id));
+        if node.is_none() {
+            warn!("can't replay edit of node {id}: it no longer exists");
+        }
+        node
+    }
+
+    fn add_edge(&mut self, edge: &EdgeSpec, spawned: &HashMap<String, Entity>) {
+        if let (Some(source), Some(target)) = (
+            self.node(&edge.source, spawned),
+            self.node(&edge.target, spawned),
+        ) {
+            self.spawner.spawn_edge_spec(edge, source, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        config::{Config, LayoutRng},
+        edit::{self, DeleteElements, SetPinned},
+        graph::{self, Graph, NodeId},
+        selection::Selection,
+        spec::GraphSpec,
+    };
+
+    #[test]
+    fn inverse_swaps_and_reverts() {
+        let node = NodeSpec::new("a").at(Vec2::new(1., 2.));
+        let edges = vec![EdgeSpec::new("a", "b")];
+        let GraphOp::RemoveNode {
+            node: removed,
+            edges: removed_edges,
+            pinned: true,
+        } = (GraphOp::AddNode {
+            node: node.clone(),
+            edges,
+            pinned: true,
+        })
+        .inverse()
+        else {
+            panic!("adding a node should invert to removing it");
+        };
+        assert_eq!(removed.id, "a");
+        assert_eq!(removed.position, Some(Vec2::new(1., 2.)));
+        assert_eq!(removed_edges.len(), 1);
+
+        let set = GraphOp::SetAttribute {
+            node: "a".into(),
+            key: "color".into(),
+            old: None,
+            new: Some("red".into()),
+        };
+        let GraphOp::SetAttribute { old, new, .. } = set.inverse() else {
+            panic!("setting an attribute should invert to setting it back");
+        };
+        assert_eq!((old.as_deref(), new), (Some("red"), None));
+
+        let GraphOp::SetPinned { pinned, .. } = (GraphOp::SetPinned {
+            node: "a".into(),
+            pinned: true,
+        })
+        .inverse() else {
+            panic!("pinning should invert to releasing");
+        };
+        assert!(!pinned);
+        assert!(matches!(
+            GraphOp::RemoveEdge(EdgeSpec::new("a", "b")).inverse(),
+            GraphOp::AddEdge(e) if e.source == "a" && e.target == "b"
+        ));
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(Config::default())
+            .init_resource::<LayoutRng>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Selection>()
+            .add_plugins((graph::plugin, edit::plugin, plugin));
+        app.world_mut()
+            .run_system_once(|mut spawner: GraphSpawner| {
+                let spec = GraphSpec::new()
+                    .node(NodeSpec::new("a").at(Vec2::new(10., 20.)))
+                    .node("b")
+                    .edge("a", "b");
+                spawner.spawn(&spec);
+            })
+            .unwrap();
+        app.update();
+        app
+    }
+
+    fn node(app: &App, id: &str) -> Option<Entity> {
+        app.world().resource::<Graph>().node_by_id(id)
+    }
+
+    /// Sends `event` and runs frames until it and the commands it queues
+    /// have been applied.
+    fn step(app: &mut App, event: impl Event) {
+        app.world_mut().send_event(event);
+        app.update();
+        app.update();
+    }
+
+    #[test]
+    fn undo_restores_deleted_node_and_redo_deletes_it_again() {
+        let mut app = app();
+        let a = node(&app, "a").unwrap();
+        step(
+            &mut app,
+            SetPinned {
+                node: a,
+                pinned: true,
+            },
+        );
+        step(&mut app, DeleteElements(vec![a]));
+        assert!(node(&app, "a").is_none());
+        assert_eq!(app.world().resource::<Graph>().edge_count(), 0);
+
+        step(&mut app, Undo);
+        let a = node(&app, "a").expect("undo should respawn the node");
+        let world = app.world();
+        assert!(world.get::<Pinned>(a).is_some());
+        assert_eq!(
+            world.get::<Transform>(a).unwrap().translation.xy(),
+            Vec2::new(10., 20.)
+        );
+        assert_eq!(world.resource::<Graph>().degree(a), 1);
+
+        step(&mut app, Redo);
+        assert!(node(&app, "a").is_none());
+
+        step(&mut app, Undo);
+        step(&mut app, Undo);
+        let a = node(&app, "a").unwrap();
+        assert!(app.world().get::<Pinned>(a).is_none());
+        assert!(!app.world().resource::<History>().can_undo());
+        assert!(app.world().resource::<History>().can_redo());
+        assert_eq!(app.world().get::<NodeId>(a).unwrap().0, "a");
+    }
+}
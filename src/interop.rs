@@ -0,0 +1,121 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use petgraph::{
+    EdgeType,
+    stable_graph::{NodeIndex, StableGraph},
+    visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef},
+};
+
+use crate::{
+    graph::Graph,
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+};
+
+/// Petgraph node and edge weights [`GraphSpec::from_petgraph`] understands.
+/// Numbers weight edges, and everything but `()` labels what it's on.
+pub trait PetgraphWeight {
+    /// Value for the `label` attribute.
+    fn label(&self) -> Option<String> {
+        None
+    }
+
+    /// Edge weight, see [`EdgeSpec::weight`].
+    fn weight(&self) -> Option<f32> {
+        None
+    }
+}
+
+impl PetgraphWeight for () {}
+
+impl PetgraphWeight for String {
+    fn label(&self) -> Option<String> {
+        Some(self.clone())
+    }
+}
+
+impl PetgraphWeight for &str {
+    fn label(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+macro_rules! numeric_weight {
+    ($($t:ty),*) => {$(
+        impl PetgraphWeight for $t {
+            fn label(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+
+            fn weight(&self) -> Option<f32> {
+                Some(*self as f32)
+            }
+        }
+    )*};
+}
+
+numeric_weight!(f32, f64, i32, i64, u32, u64, usize);
+
+impl Graph {
+    /// The live topology as a petgraph graph, for running petgraph
+    /// algorithms on it. Node weights are the node entities and edge weights
+    /// the edge entities; edges run from source to target, so pick
+    /// [`Undirected`](petgraph::Undirected) for an undirected graph.
+    pub fn to_petgraph<Ty: EdgeType>(&self) -> petgraph::Graph<Entity, Entity, Ty> {
+        self.to_stable_graph().into()
+    }
+
+    /// Like [`to_petgraph`](Self::to_petgraph), as a graph whose indices
+    /// stay valid as nodes are removed from it. Nodes are added in entity
+    /// order, so the indices don't depend on hashing.
+    pub fn to_stable_graph<Ty: EdgeType>(&self) -> StableGraph<Entity, Entity, Ty> {
+        let mut graph = StableGraph::with_capacity(self.node_count(), self.edge_count());
+        let mut nodes: Vec<Entity> = self.nodes().collect();
+        nodes.sort();
+        let indices: HashMap<Entity, NodeIndex> =
+            nodes.into_iter().map(|n| (n, graph.add_node(n))).collect();
+        let mut edges: Vec<_> = self.edges().collect();
+        edges.sort();
+        for (edge, source, target) in edges {
+            if let (Some(&a), Some(&b)) = (indices.get(&source), indices.get(&target)) {
+                graph.add_edge(a, b, edge);
+            }
+        }
+        graph
+    }
+}
+
+impl GraphSpec {
+    /// Spec of a petgraph [`Graph`](petgraph::Graph),
+    /// [`StableGraph`] or any other graph petgraph can walk. Nodes are named
+    /// by their index; see [`PetgraphWeight`] for what becomes of the
+    /// weights. Spawn the result with [`SpawnGraph`](crate::SpawnGraph) or
+    /// insert it before startup.
+    pub fn from_petgraph<G>(graph: G) -> Self
+    where
+        G: IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp,
+        G::NodeWeight: PetgraphWeight,
+        G::EdgeWeight: PetgraphWeight,
+    {
+        let id = |node| graph.to_index(node).to_string();
+        let mut spec = GraphSpec {
+            directed: graph.is_directed(),
+            ..default()
+        };
+        for node in graph.node_references() {
+            let mut n = NodeSpec::new(id(node.id()));
+            if let Some(label) = node.weight().label() {
+                n = n.with_attribute("label", label);
+            }
+            spec.nodes.push(n);
+        }
+        for edge in graph.edge_references() {
+            let weight = edge.weight();
+            let mut e = EdgeSpec::new(id(edge.source()), id(edge.target()))
+                .with_weight(weight.weight().unwrap_or(1.));
+            if let Some(label) = weight.label() {
+                e = e.with_attribute("label", label);
+            }
+            spec.edges.push(e);
+        }
+        spec
+    }
+}
@@ -0,0 +1,189 @@
+use super::{GraphIoError, read_weights};
+use crate::spec::{EdgeSpec, GraphSpec};
+
+const HEADER_NAMES: [&str; 9] = [
+    "source", "target", "from", "to", "src", "dst", "weight", "node1", "node2",
+];
+
+/// Parses a `source,target[,weight]` edge list. Tabs are used as the delimiter
+/// when the first line contains one. A header row is detected by its column
+/// names, or by a non-numeric weight column above numeric ones, and names any
+/// extra columns, which become edge attributes. The weight is the column
+/// headed `weight`, or the third column of a file without a header; invalid
+/// weights are ignored with a warning. Lines starting with `#` are skipped
+/// and nodes are created on first mention.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .peekable();
+    let Some(first) = lines.peek().copied() else {
+        return Ok(GraphSpec::new());
+    };
+    let delimiter = if first.contains('\t') { '\t' } else { ',' };
+    let rows: Vec<Vec<String>> = lines.map(|l| split(l, delimiter)).collect();
+
+    let header = is_header(&rows);
+    let columns: Vec<String> = if header {
+        rows[0].iter().map(|c| c.to_ascii_lowercase()).collect()
+    } else {
+        Vec::new()
+    };
+
+    let weight_column = if header {
+        columns
+            .iter()
+            .skip(2)
+            .position(|c| c == "weight")
+            .map(|j| j + 2)
+    } else {
+        Some(2)
+    };
+
+    let mut spec = GraphSpec::new();
+    for (i, row) in rows.iter().enumerate().skip(header as usize) {
+        let [source, target, rest @ ..] = row.as_slice() else {
+            return Err(GraphIoError::Parse(format!(
+                "line {}: expected at least two columns",
+                i + 1
+            )));
+        };
+        let mut edge = EdgeSpec::new(source.clone(), target.clone());
+        for (j, value) in rest.iter().enumerate().map(|(j, v)| (j + 2, v)) {
+            if Some(j) == weight_column {
+                if !value.is_empty() {
+                    edge.attributes.insert("weight".into(), value.clone());
+                }
+                continue;
+            }
+            let key = columns
+                .get(j)
+                .cloned()
+                .unwrap_or_else(|| format!("column{}", j + 1));
+            edge.attributes.insert(key, value.clone());
+        }
+        spec.edges.push(edge);
+    }
+    read_weights(&mut spec);
+    spec.add_missing_nodes();
+    Ok(spec)
+}
+
+fn is_header(rows: &[Vec<String>]) -> bool {
+    let Some(first) = rows.first() else {
+        return false;
+    };
+    if first
+        .iter()
+        .any(|c| HEADER_NAMES.contains(&c.to_ascii_lowercase().as_str()))
+    {
+        return true;
+    }
+    let numeric = |row: &Vec<String>| row.get(2).is_some_and(|w| w.parse::<f32>().is_ok());
+    first.len() > 2 && !numeric(first) && rows.get(1).is_some_and(numeric)
+}
+
+/// Splits one line, honouring double-quoted fields with `""` escapes.
+fn split(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            _ if c == delimiter && !quoted => {
+                fields.push(field.trim().to_owned());
+                field.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_owned());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(spec: &GraphSpec) -> Vec<(&str, &str, f32)> {
+        spec.edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.weight))
+            .collect()
+    }
+
+    #[test]
+    fn detects_header_by_column_names() {
+        let spec = parse("Source,Target,Weight,kind\na,b,2,road\nb,c,3,rail\n").unwrap();
+        assert_eq!(edges(&spec), [("a", "b", 2.), ("b", "c", 3.)]);
+        assert_eq!(
+            spec.edges[1].attributes.get("kind").map(String::as_str),
+            Some("rail")
+        );
+        let ids: Vec<&str> = spec.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn detects_header_by_non_numeric_weight() {
+        let spec = parse("from_id,to_id,strength\n1,2,0.5\n").unwrap();
+        assert_eq!(edges(&spec), [("1", "2", 1.)]);
+        assert_eq!(
+            spec.edges[0].attributes.get("strength").map(String::as_str),
+            Some("0.5")
+        );
+    }
+
+    #[test]
+    fn headerless_third_column_is_weight() {
+        let spec = parse("# comment\na\tb\t4\n\nb\tc\n").unwrap();
+        assert_eq!(edges(&spec), [("a", "b", 4.), ("b", "c", 1.)]);
+    }
+
+    #[test]
+    fn weight_column_found_anywhere_after_endpoints() {
+        let spec = parse("source,target,label,weight\na,b,x,7\n").unwrap();
+        assert_eq!(edges(&spec), [("a", "b", 7.)]);
+        assert_eq!(
+            spec.edges[0].attributes.get("label").map(String::as_str),
+            Some("x")
+        );
+    }
+
+    #[test]
+    fn quoted_fields_keep_delimiters_and_quotes() {
+        let spec = parse("\"New York, NY\",\"say \"\"hi\"\"\"\n").unwrap();
+        assert_eq!(spec.edges[0].source, "New York, NY");
+        assert_eq!(spec.edges[0].target, "say \"hi\"");
+    }
+
+    #[test]
+    fn rejects_single_column_rows() {
+        assert!(parse("a,b\nc\n").is_err());
+    }
+
+    #[test]
+    fn invalid_weights_fall_back_to_default() {
+        let spec = parse("a,b,2.5\nb,c,heavy\nc,a,-1\n").unwrap();
+        let weights: Vec<f32> = spec.edges.iter().map(|e| e.weight).collect();
+        assert_eq!(weights, [2.5, 1., 1.]);
+    }
+
+    #[test]
+    fn header_without_weight_keeps_third_column() {
+        let spec = parse("source,target,label\na,b,likes\n").unwrap();
+        let edge = &spec.edges[0];
+        assert_eq!(edge.weight, 1.);
+        assert_eq!(
+            edge.attributes.get("label").map(String::as_str),
+            Some("likes")
+        );
+    }
+}
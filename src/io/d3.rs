@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde_json::{Map, Value};
+
+use super::{GraphIoError, read_weights};
+use crate::spec::{EdgeSpec, GraphSpec, NodeSpec};
+
+/// Parses d3-force's `{ "nodes": [...], "links": [...] }` shape.
+///
+/// Node `id`s may be strings or numbers; links refer to them by id, or by
+/// array index when nodes carry no id. A link's `weight`, or else its `value`,
+/// becomes the edge weight, `x`/`y` seed positions, and every other scalar
+/// field (such as `group`) becomes an attribute.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let root: Value = serde_json::from_str(contents)?;
+    let Some(nodes) = root.get("nodes").and_then(Value::as_array) else {
+        return Err(GraphIoError::Parse("missing `nodes` array".into()));
+    };
+    let links = root
+        .get("links")
+        .or_else(|| root.get("edges"))
+        .and_then(Value::as_array);
+
+    let by_index = nodes.iter().all(|n| n.get("id").is_none());
+
+    let mut spec = GraphSpec::new();
+    spec.directed = root
+        .get("directed")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    for (i, node) in nodes.iter().enumerate() {
+        let Some(obj) = node.as_object() else {
+            return Err(GraphIoError::Parse(format!("node {i} is not an object")));
+        };
+        let id = obj
+            .get("id")
+            .and_then(scalar)
+            .unwrap_or_else(|| i.to_string());
+        let mut n = NodeSpec::new(id);
+        if let (Some(x), Some(y)) = (
+            obj.get("x").and_then(Value::as_f64),
+            obj.get("y").and_then(Value::as_f64),
+        ) {
+            n.position = Some(Vec2::new(x as f32, y as f32));
+        }
+        n.attributes = attributes(obj, &["id", "x", "y", "vx", "vy", "fx", "fy", "index"]);
+        spec.nodes.push(n);
+    }
+
+    for (i, link) in links.into_iter().flatten().enumerate() {
+        let Some(obj) = link.as_object() else {
+            return Err(GraphIoError::Parse(format!("link {i} is not an object")));
+        };
+        let endpoint = |key: &str| -> Result<String, GraphIoError> {
+            let value = obj
+                .get(key)
+                .ok_or_else(|| GraphIoError::Parse(format!("link {i} is missing `{key}`")))?;
+            // d3 replaces endpoints with the node objects once the simulation runs.
+            let value = value.get("id").unwrap_or(value);
+            match value.as_u64() {
+                Some(index) if by_index => spec
+                    .nodes
+                    .get(index as usize)
+                    .map(|n| n.id.clone())
+                    .ok_or_else(|| GraphIoError::Parse(format!("link {i}: no node {index}"))),
+                _ => scalar(value)
+                    .ok_or_else(|| GraphIoError::Parse(format!("link {i}: bad `{key}`"))),
+            }
+        };
+        let mut edge = EdgeSpec::new(endpoint("source")?, endpoint("target")?);
+        edge.attributes = attributes(obj, &["source", "target", "index"]);
+        if let Some(value) = edge.attributes.get("value").cloned() {
+            edge.attributes.entry("weight".into()).or_insert(value);
+        }
+        spec.edges.push(edge);
+    }
+    read_weights(&mut spec);
+    spec.add_missing_nodes();
+    Ok(spec)
+}
+
+pub(super) fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+pub(super) fn attributes(obj: &Map<String, Value>, skip: &[&str]) -> BTreeMap<String, String> {
+    obj.iter()
+        .filter(|(k, _)| !skip.contains(&k.as_str()))
+        .filter_map(|(k, v)| Some((k.clone(), scalar(v)?)))
+        .collect()
+}
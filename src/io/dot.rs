@@ -0,0 +1,511 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{GraphIoError, read_weights};
+use crate::spec::{EdgeSpec, GraphSpec, NodeSpec};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// Bare identifier or numeral; only these can be keywords.
+    Id(String),
+    /// Double-quoted or HTML string.
+    Quoted(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Semi,
+    Comma,
+    Colon,
+    Plus,
+    EdgeOp,
+}
+
+/// Parses a Graphviz DOT file. Node and edge attributes (including
+/// `node [..]`/`edge [..]` defaults) become attributes; `pos` seeds node
+/// positions and `weight` feeds the edge weight.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let mut parser = Parser {
+        tokens: tokenize(contents)?,
+        pos: 0,
+        spec: GraphSpec::new(),
+        index: HashMap::default(),
+    };
+    parser.graph()?;
+    let mut spec = parser.spec;
+    for node in &mut spec.nodes {
+        node.position = node.attributes.get("pos").and_then(|p| parse_pos(p));
+    }
+    read_weights(&mut spec);
+    Ok(spec)
+}
+
+/// Writes DOT with a `pos="x,y"` attribute per positioned node, so
+/// `neato -n` and friends reproduce the layout.
+pub fn write(spec: &GraphSpec) -> String {
+    let (keyword, op) = if spec.directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+    let mut out = format!("{keyword} {{\n");
+    for node in &spec.nodes {
+        let mut attrs: Vec<(&str, String)> = node
+            .attributes
+            .iter()
+            .filter(|(k, _)| k.as_str() != "pos")
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect();
+        if let Some(pos) = node.position {
+            attrs.push(("pos", format!("{},{}", pos.x, pos.y)));
+        }
+        let _ = writeln!(out, "  {}{};", quote(&node.id), attr_list(&attrs));
+    }
+    for edge in &spec.edges {
+        let mut attrs: Vec<(&str, String)> = edge
+            .attributes
+            .iter()
+            .filter(|(k, _)| k.as_str() != "weight")
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect();
+        if edge.weight != 1. || edge.attributes.contains_key("weight") {
+            attrs.push(("weight", edge.weight.to_string()));
+        }
+        let _ = writeln!(
+            out,
+            "  {} {op} {}{};",
+            quote(&edge.source),
+            quote(&edge.target),
+            attr_list(&attrs)
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('"', "\\\""))
+}
+
+fn attr_list(attrs: &[(&str, String)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let list: Vec<String> = attrs
+        .iter()
+        .map(|(k, v)| {
+            let bare = k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !k.starts_with(|c: char| c.is_ascii_digit());
+            let key = if bare { k.to_string() } else { quote(k) };
+            format!("{key}={}", quote(v))
+        })
+        .collect();
+    format!(" [{}]", list.join(", "))
+}
+
+/// `"x,y"` with an optional trailing `!`, in points.
+pub(crate) fn parse_pos(pos: &str) -> Option<Vec2> {
+    let (x, y) = pos.trim().trim_end_matches('!').split_once(',')?;
+    Some(Vec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, GraphIoError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' | '+' => {
+                tokens.push(match c {
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '=' => Token::Equals,
+                    ';' => Token::Semi,
+                    ',' => Token::Comma,
+                    ':' => Token::Colon,
+                    _ => Token::Plus,
+                });
+                i += 1;
+            }
+            '-' if matches!(next, Some('-' | '>')) => {
+                tokens.push(Token::EdgeOp);
+                i += 2;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(GraphIoError::Parse("unterminated string".into())),
+                        Some('"') => break,
+                        Some('\\') if chars.get(i + 1) == Some(&'"') => {
+                            s.push('"');
+                            i += 1;
+                        }
+                        Some('\\') if chars.get(i + 1) == Some(&'\n') => i += 1,
+                        Some(&ch) => s.push(ch),
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(Token::Quoted(s));
+            }
+            '<' => {
+                let mut depth = 0;
+                let start = i;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(GraphIoError::Parse("unterminated HTML string".into())),
+                        Some('<') => depth += 1,
+                        Some('>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                tokens.push(Token::Quoted(chars[start + 1..i].iter().collect()));
+                i += 1;
+            }
+            _ if is_id_char(c) || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && is_id_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Id(chars[start..i].iter().collect()));
+            }
+            _ => {
+                return Err(GraphIoError::Parse(format!("unexpected character `{c}`")));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || !c.is_ascii()
+}
+
+#[derive(Clone, Default)]
+struct Scope {
+    node: BTreeMap<String, String>,
+    edge: BTreeMap<String, String>,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    spec: GraphSpec,
+    index: HashMap<String, usize>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), GraphIoError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(GraphIoError::Parse(format!(
+                "expected {token:?}, found {:?}",
+                self.peek()
+            )))
+        }
+    }
+
+    fn keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(s)) if s.eq_ignore_ascii_case(kw))
+    }
+
+    fn id(&mut self) -> Result<String, GraphIoError> {
+        match self.bump() {
+            Some(Token::Id(s)) => Ok(s),
+            Some(Token::Quoted(mut s)) => {
+                while self.peek() == Some(&Token::Plus) {
+                    self.pos += 1;
+                    match self.bump() {
+                        Some(Token::Quoted(more)) => s.push_str(&more),
+                        t => {
+                            return Err(GraphIoError::Parse(format!(
+                                "expected string, found {t:?}"
+                            )));
+                        }
+                    }
+                }
+                Ok(s)
+            }
+            t => Err(GraphIoError::Parse(format!(
+                "expected identifier, found {t:?}"
+            ))),
+        }
+    }
+
+    fn graph(&mut self) -> Result<(), GraphIoError> {
+        if self.keyword("strict") {
+            self.pos += 1;
+        }
+        if self.keyword("digraph") {
+            self.spec.directed = true;
+        } else if !self.keyword("graph") {
+            return Err(GraphIoError::Parse("expected `graph` or `digraph`".into()));
+        }
+        self.pos += 1;
+        if self.peek() != Some(&Token::LBrace) {
+            self.id()?;
+        }
+        self.expect(Token::LBrace)?;
+        self.stmt_list(&mut Scope::default())?;
+        self.expect(Token::RBrace)
+    }
+
+    /// Returns every node mentioned, so subgraphs can act as edge operands.
+    fn stmt_list(&mut self, scope: &mut Scope) -> Result<Vec<String>, GraphIoError> {
+        let mut mentioned = Vec::new();
+        while !matches!(self.peek(), None | Some(Token::RBrace)) {
+            mentioned.extend(self.stmt(scope)?);
+            while self.eat(&Token::Semi) || self.eat(&Token::Comma) {}
+        }
+        Ok(mentioned)
+    }
+
+    fn stmt(&mut self, scope: &mut Scope) -> Result<Vec<String>, GraphIoError> {
+        let attr_target = ["graph", "node", "edge"]
+            .into_iter()
+            .find(|kw| self.keyword(kw))
+            .filter(|_| self.tokens.get(self.pos + 1) == Some(&Token::LBracket));
+        if let Some(kw) = attr_target {
+            self.pos += 1;
+            let attrs = self.attr_lists()?;
+            match kw {
+                "node" => scope.node.extend(attrs),
+                "edge" => scope.edge.extend(attrs),
+                _ => {}
+            }
+            return Ok(Vec::new());
+        }
+
+        let first = if self.keyword("subgraph") || self.peek() == Some(&Token::LBrace) {
+            self.subgraph(scope)?
+        } else {
+            let id = self.id()?;
+            if self.eat(&Token::Equals) {
+                self.id()?;
+                return Ok(Vec::new());
+            }
+            self.port()?;
+            vec![id]
+        };
+
+        if self.peek() != Some(&Token::EdgeOp) {
+            let attrs = if self.peek() == Some(&Token::LBracket) {
+                self.attr_lists()?
+            } else {
+                BTreeMap::new()
+            };
+            for id in &first {
+                self.node(id, scope, &attrs);
+            }
+            return Ok(first);
+        }
+
+        let mut operands = vec![first];
+        while self.eat(&Token::EdgeOp) {
+            if self.keyword("subgraph") || self.peek() == Some(&Token::LBrace) {
+                operands.push(self.subgraph(scope)?);
+            } else {
+                let id = self.id()?;
+                self.port()?;
+                operands.push(vec![id]);
+            }
+        }
+        let mut attrs = scope.edge.clone();
+        if self.peek() == Some(&Token::LBracket) {
+            attrs.extend(self.attr_lists()?);
+        }
+        for ids in &operands {
+            for id in ids {
+                self.node(id, scope, &BTreeMap::new());
+            }
+        }
+        for pair in operands.windows(2) {
+            for source in &pair[0] {
+                for target in &pair[1] {
+                    let mut edge = EdgeSpec::new(source.clone(), target.clone());
+                    edge.attributes = attrs.clone();
+                    self.spec.edges.push(edge);
+                }
+            }
+        }
+        Ok(operands.into_iter().flatten().collect())
+    }
+
+    fn subgraph(&mut self, scope: &Scope) -> Result<Vec<String>, GraphIoError> {
+        if self.keyword("subgraph") {
+            self.pos += 1;
+            if self.peek() != Some(&Token::LBrace) {
+                self.id()?;
+            }
+        }
+        self.expect(Token::LBrace)?;
+        let mentioned = self.stmt_list(&mut scope.clone())?;
+        self.expect(Token::RBrace)?;
+        Ok(mentioned)
+    }
+
+    fn port(&mut self) -> Result<(), GraphIoError> {
+        while self.eat(&Token::Colon) {
+            self.id()?;
+        }
+        Ok(())
+    }
+
+    fn attr_lists(&mut self) -> Result<BTreeMap<String, String>, GraphIoError> {
+        let mut attrs = BTreeMap::new();
+        while self.eat(&Token::LBracket) {
+            while !self.eat(&Token::RBracket) {
+                let key = self.id()?;
+                let value = if self.eat(&Token::Equals) {
+                    self.id()?
+                } else {
+                    "true".into()
+                };
+                attrs.insert(key, value);
+                while self.eat(&Token::Semi) || self.eat(&Token::Comma) {}
+            }
+        }
+        Ok(attrs)
+    }
+
+    fn node(&mut self, id: &str, scope: &Scope, attrs: &BTreeMap<String, String>) {
+        let index = match self.index.get(id) {
+            Some(&i) => i,
+            None => {
+                let mut node = NodeSpec::new(id);
+                node.attributes = scope.node.clone();
+                self.spec.nodes.push(node);
+                self.index.insert(id.to_owned(), self.spec.nodes.len() - 1);
+                self.spec.nodes.len() - 1
+            }
+        };
+        self.spec.nodes[index].attributes.extend(attrs.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute<'a>(attributes: &'a BTreeMap<String, String>, key: &str) -> Option<&'a str> {
+        attributes.get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn parses_nodes_with_attributes_and_defaults() {
+        let spec = parse(
+            r#"digraph G {
+                node [shape=box];
+                a [label="Node A", pos="10,20!"];
+                b;
+            }"#,
+        )
+        .unwrap();
+        assert!(spec.directed);
+        let ids: Vec<&str> = spec.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        let a = &spec.nodes[0];
+        assert_eq!(attribute(&a.attributes, "label"), Some("Node A"));
+        assert_eq!(attribute(&a.attributes, "shape"), Some("box"));
+        assert_eq!(a.position, Some(Vec2::new(10., 20.)));
+        assert_eq!(attribute(&spec.nodes[1].attributes, "shape"), Some("box"));
+        assert_eq!(spec.nodes[1].position, None);
+    }
+
+    #[test]
+    fn parses_edge_chains_with_attributes() {
+        let spec = parse(
+            r#"graph {
+                edge [color=red];
+                a -- b -- "c d" [weight=2.5];
+                b -- a;
+            }"#,
+        )
+        .unwrap();
+        assert!(!spec.directed);
+        let edges: Vec<(&str, &str, f32)> = spec
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.weight))
+            .collect();
+        assert_eq!(edges, [("a", "b", 2.5), ("b", "c d", 2.5), ("b", "a", 1.)]);
+        assert!(
+            spec.edges
+                .iter()
+                .all(|e| attribute(&e.attributes, "color") == Some("red"))
+        );
+        assert_eq!(spec.nodes.len(), 3);
+    }
+
+    #[test]
+    fn parses_subgraphs_and_comments() {
+        let spec = parse(
+            "strict digraph {\n  // a comment\n  a -> { b c }\n  subgraph cluster_x { d -> a }\n}",
+        )
+        .unwrap();
+        let edges: Vec<(&str, &str)> = spec
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str()))
+            .collect();
+        assert_eq!(edges, [("a", "b"), ("a", "c"), ("d", "a")]);
+    }
+
+    #[test]
+    fn rejects_unterminated_graph() {
+        assert!(parse("digraph { a -> b").is_err());
+    }
+}
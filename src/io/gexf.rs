@@ -0,0 +1,486 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use quick_xml::{
+    Reader,
+    escape::escape,
+    events::{BytesStart, Event},
+};
+
+use super::{
+    GraphIoError, read_weights,
+    xml::{attr, required},
+};
+use crate::{
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+    timeline::parse_time,
+};
+
+/// Attributes that map onto GEXF's own fields rather than `<attvalue>`s.
+const RESERVED: [&str; 8] = [
+    "label", "color", "size", "weight", "directed", "start", "end", "spells",
+];
+
+struct AttrDef {
+    title: String,
+    default: Option<String>,
+}
+
+/// Parses GEXF 1.x. `viz:position` seeds node positions, `viz:color` and
+/// `viz:size` become the `color` (hex) and `size` attributes. The `start`
+/// and `end` of dynamic nodes and edges, and their `<spell>`s, become the
+/// attributes a [`Lifetime`](crate::Lifetime) is read from.
+///
+/// Edges are undirected unless the graph's `defaultedgetype` or their own
+/// `type` says `directed`. A graph with both kinds is directed, with its
+/// undirected edges tagged `directed = false`.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let mut reader = Reader::from_str(contents);
+    reader.config_mut().trim_text(true);
+
+    let mut spec = GraphSpec::new();
+    let mut defs: HashMap<(String, String), AttrDef> = HashMap::default();
+    let mut class = String::from("node");
+    let mut current_def: Option<(String, String)> = None;
+    let mut in_default = false;
+    let mut node: Option<NodeSpec> = None;
+    let mut edge: Option<EdgeSpec> = None;
+    let mut default_directed = false;
+
+    loop {
+        let (e, empty) = match reader.read_event()? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::Text(t) => {
+                if let Some(def) = current_def
+                    .as_ref()
+                    .filter(|_| in_default)
+                    .and_then(|k| defs.get_mut(k))
+                {
+                    def.default = Some(t.unescape()?.into_owned());
+                }
+                continue;
+            }
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"node" => spec.nodes.extend(node.take()),
+                    b"edge" => spec.edges.extend(edge.take()),
+                    b"attribute" => current_def = None,
+                    b"default" => in_default = false,
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        match e.local_name().as_ref() {
+            b"graph" => {
+                default_directed = attr(&e, b"defaultedgetype")?.as_deref() == Some("directed")
+            }
+            b"attributes" => class = attr(&e, b"class")?.unwrap_or_else(|| "node".into()),
+            b"attribute" => {
+                let id = required(&e, b"id")?;
+                let title = attr(&e, b"title")?.unwrap_or_else(|| id.clone());
+                let key = (class.clone(), id);
+                defs.insert(
+                    key.clone(),
+                    AttrDef {
+                        title,
+                        default: None,
+                    },
+                );
+                if !empty {
+                    current_def = Some(key);
+                }
+            }
+            b"default" => in_default = !empty,
+            b"node" => {
+                let mut n = NodeSpec::new(required(&e, b"id")?);
+                if let Some(label) = attr(&e, b"label")? {
+                    n.attributes.insert("label".into(), label);
+                }
+                lifetime(&e, &mut n.attributes)?;
+                if empty {
+                    spec.nodes.push(n);
+                } else {
+                    node = Some(n);
+                }
+            }
+            b"edge" => {
+                let mut ed = EdgeSpec::new(required(&e, b"source")?, required(&e, b"target")?);
+                if let Some(weight) = attr(&e, b"weight")? {
+                    ed.attributes.insert("weight".into(), weight);
+                }
+                if let Some(label) = attr(&e, b"label")? {
+                    ed.attributes.insert("label".into(), label);
+                }
+                if let Some(kind) = attr(&e, b"type")? {
+                    let directed = kind == "directed";
+                    ed.attributes
+                        .insert("directed".into(), directed.to_string());
+                }
+                lifetime(&e, &mut ed.attributes)?;
+                if empty {
+                    spec.edges.push(ed);
+                } else {
+                    edge = Some(ed);
+                }
+            }
+            b"attvalue" => {
+                let Some(id) = attr(&e, b"for")?.or(attr(&e, b"id")?) else {
+                    continue;
+                };
+                let value = attr(&e, b"value")?.unwrap_or_default();
+                let (class, attributes) = match (&mut node, &mut edge) {
+                    (_, Some(ed)) => ("edge", &mut ed.attributes),
+                    (Some(n), None) => ("node", &mut n.attributes),
+                    _ => continue,
+                };
+                let title = defs
+                    .get(&(class.to_owned(), id.clone()))
+                    .map_or(id, |d| d.title.clone());
+                attributes.insert(title, value);
+            }
+            b"color" => {
+                let channel = |name: &[u8]| -> Result<Option<f32>, GraphIoError> {
+                    Ok(attr(&e, name)?.and_then(|v| v.parse().ok()))
+                };
+                let color = Srgba::rgba_u8(
+                    channel(b"r")?.unwrap_or(0.) as u8,
+                    channel(b"g")?.unwrap_or(0.) as u8,
+                    channel(b"b")?.unwrap_or(0.) as u8,
+                    (channel(b"a")?.unwrap_or(1.) * 255.) as u8,
+                );
+                if let Some(attributes) = viz_target(&mut node, &mut edge) {
+                    attributes.insert("color".into(), color.to_hex());
+                }
+            }
+            b"size" | b"thickness" => {
+                if let (Some(size), Some(attributes)) =
+                    (attr(&e, b"value")?, viz_target(&mut node, &mut edge))
+                {
+                    attributes.insert("size".into(), size);
+                }
+            }
+            b"spell" => {
+                let (start, end) = (time(&e, b"start")?, time(&e, b"end")?);
+                if let Some(attributes) = viz_target(&mut node, &mut edge) {
+                    let span = format!(
+                        "{}:{}",
+                        start.unwrap_or(f64::NEG_INFINITY),
+                        end.unwrap_or(f64::INFINITY)
+                    );
+                    let spells = attributes.entry("spells".into()).or_default();
+                    if !spells.is_empty() {
+                        spells.push(' ');
+                    }
+                    spells.push_str(&span);
+                }
+            }
+            b"position" => {
+                if let (Some(n), None) = (&mut node, &edge) {
+                    let x = attr(&e, b"x")?.and_then(|v| v.parse().ok()).unwrap_or(0.);
+                    let y = attr(&e, b"y")?.and_then(|v| v.parse().ok()).unwrap_or(0.);
+                    n.position = Some(Vec2::new(x, y));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for ((class, _), def) in &defs {
+        let Some(default) = &def.default else {
+            continue;
+        };
+        let targets: Box<dyn Iterator<Item = _>> = if class == "edge" {
+            Box::new(spec.edges.iter_mut().map(|e| &mut e.attributes))
+        } else {
+            Box::new(spec.nodes.iter_mut().map(|n| &mut n.attributes))
+        };
+        for attributes in targets {
+            attributes
+                .entry(def.title.clone())
+                .or_insert_with(|| default.clone());
+        }
+    }
+    let directed = |edge: &EdgeSpec| {
+        edge.attributes
+            .get("directed")
+            .map_or(default_directed, |d| d == "true")
+    };
+    spec.directed = spec.edges.iter().any(directed);
+    for edge in &mut spec.edges {
+        if spec.directed && !directed(edge) {
+            edge.attributes.insert("directed".into(), "false".into());
+        } else {
+            edge.attributes.remove("directed");
+        }
+    }
+    read_weights(&mut spec);
+    spec.add_missing_nodes();
+    Ok(spec)
+}
+
+/// Time in the `name` or `nameopen` attribute, as a number.
+fn time(e: &BytesStart, name: &[u8]) -> Result<Option<f64>, GraphIoError> {
+    let open = [name, b"open"].concat();
+    Ok(attr(e, name)?
+        .or(attr(e, &open)?)
+        .and_then(|t| parse_time(&t)))
+}
+
+/// Copies an element's own interval into `start` and `end` attributes.
+fn lifetime(e: &BytesStart, attributes: &mut BTreeMap<String, String>) -> Result<(), GraphIoError> {
+    for name in ["start", "end"] {
+        if let Some(t) = time(e, name.as_bytes())? {
+            attributes.insert(name.into(), t.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn viz_target<'a>(
+    node: &'a mut Option<NodeSpec>,
+    edge: &'a mut Option<EdgeSpec>,
+) -> Option<&'a mut BTreeMap<String, String>> {
+    match (node, edge) {
+        (_, Some(ed)) => Some(&mut ed.attributes),
+        (Some(n), None) => Some(&mut n.attributes),
+        _ => None,
+    }
+}
+
+/// Writes GEXF 1.3 with `viz:position` from each node's position. Graphs
+/// with `start`, `end` or `spells` attributes are written in dynamic mode,
+/// with those as the elements' own intervals and `<spells>`.
+pub fn write(spec: &GraphSpec) -> String {
+    let mut out = String::new();
+    let node_keys = attribute_keys(spec.nodes.iter().map(|n| &n.attributes));
+    let edge_keys = attribute_keys(spec.edges.iter().map(|e| &e.attributes));
+    let edge_type = if spec.directed {
+        "directed"
+    } else {
+        "undirected"
+    };
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">\n");
+    let dynamic = spec
+        .nodes
+        .iter()
+        .map(|n| &n.attributes)
+        .chain(spec.edges.iter().map(|e| &e.attributes))
+        .any(|a| {
+            ["start", "end", "spells"]
+                .iter()
+                .any(|k| a.contains_key(*k))
+        });
+    let mode = if dynamic {
+        "mode=\"dynamic\" timeformat=\"double\""
+    } else {
+        "mode=\"static\""
+    };
+    let _ = writeln!(out, "  <graph {mode} defaultedgetype=\"{edge_type}\">");
+    write_attribute_defs(&mut out, "node", &node_keys);
+    write_attribute_defs(&mut out, "edge", &edge_keys);
+
+    out.push_str("    <nodes>\n");
+    for node in &spec.nodes {
+        let label = node.attributes.get("label").unwrap_or(&node.id);
+        let _ = write!(
+            out,
+            "      <node id=\"{}\" label=\"{}\"",
+            escape(node.id.as_str()),
+            escape(label.as_str())
+        );
+        write_interval(&mut out, &node.attributes);
+        out.push_str(">\n");
+        write_attvalues(&mut out, &node_keys, &node.attributes);
+        write_spells(&mut out, &node.attributes);
+        write_color(&mut out, node.attributes.get("color"));
+        if let Some(pos) = node.position {
+            let _ = writeln!(
+                out,
+                "        <viz:position x=\"{}\" y=\"{}\" z=\"0.0\"/>",
+                pos.x, pos.y
+            );
+        }
+        if let Some(size) = node.attributes.get("size") {
+            let _ = writeln!(
+                out,
+                "        <viz:size value=\"{}\"/>",
+                escape(size.as_str())
+            );
+        }
+        out.push_str("      </node>\n");
+    }
+    out.push_str("    </nodes>\n    <edges>\n");
+    for (i, edge) in spec.edges.iter().enumerate() {
+        let _ = write!(
+            out,
+            "      <edge id=\"{i}\" source=\"{}\" target=\"{}\" weight=\"{}\"",
+            escape(edge.source.as_str()),
+            escape(edge.target.as_str()),
+            edge.weight
+        );
+        if let Some(label) = edge.attributes.get("label") {
+            let _ = write!(out, " label=\"{}\"", escape(label.as_str()));
+        }
+        if spec.directed && edge.attributes.get("directed").map(String::as_str) == Some("false") {
+            out.push_str(" type=\"undirected\"");
+        }
+        write_interval(&mut out, &edge.attributes);
+        out.push_str(">\n");
+        write_attvalues(&mut out, &edge_keys, &edge.attributes);
+        write_spells(&mut out, &edge.attributes);
+        write_color(&mut out, edge.attributes.get("color"));
+        if let Some(size) = edge.attributes.get("size") {
+            let _ = writeln!(
+                out,
+                "        <viz:thickness value=\"{}\"/>",
+                escape(size.as_str())
+            );
+        }
+        out.push_str("      </edge>\n");
+    }
+    out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    out
+}
+
+fn attribute_keys<'a>(attrs: impl Iterator<Item = &'a BTreeMap<String, String>>) -> Vec<String> {
+    let keys: BTreeSet<&String> = attrs.flat_map(|a| a.keys()).collect();
+    keys.into_iter()
+        .filter(|k| !RESERVED.contains(&k.as_str()))
+        .cloned()
+        .collect()
+}
+
+fn write_attribute_defs(out: &mut String, class: &str, keys: &[String]) {
+    if keys.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "    <attributes class=\"{class}\">");
+    for (i, key) in keys.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "      <attribute id=\"{i}\" title=\"{}\" type=\"string\"/>",
+            escape(key.as_str())
+        );
+    }
+    out.push_str("    </attributes>\n");
+}
+
+fn write_attvalues(out: &mut String, keys: &[String], attributes: &BTreeMap<String, String>) {
+    let values: Vec<_> = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, k)| attributes.get(k).map(|v| (i, v)))
+        .collect();
+    if values.is_empty() {
+        return;
+    }
+    out.push_str("        <attvalues>\n");
+    for (i, value) in values {
+        let _ = writeln!(
+            out,
+            "          <attvalue for=\"{i}\" value=\"{}\"/>",
+            escape(value.as_str())
+        );
+    }
+    out.push_str("        </attvalues>\n");
+}
+
+/// ` start` and ` end` XML attributes from the `start` and `end` attributes.
+fn write_interval(out: &mut String, attributes: &BTreeMap<String, String>) {
+    for name in ["start", "end"] {
+        if let Some(t) = attributes
+            .get(name)
+            .and_then(|t| parse_time(t))
+            .filter(|t| t.is_finite())
+        {
+            let _ = write!(out, " {name}=\"{t}\"");
+        }
+    }
+}
+
+/// `<spells>` from the `spells` attribute, leaving out unbounded ends.
+fn write_spells(out: &mut String, attributes: &BTreeMap<String, String>) {
+    let Some(spells) = attributes.get("spells") else {
+        return;
+    };
+    out.push_str("        <spells>\n");
+    for span in spells.split_whitespace() {
+        let Some((start, end)) = span.split_once(':') else {
+            continue;
+        };
+        out.push_str("          <spell");
+        for (name, t) in [("start", start), ("end", end)] {
+            if let Some(t) = parse_time(t).filter(|t| t.is_finite()) {
+                let _ = write!(out, " {name}=\"{t}\"");
+            }
+        }
+        out.push_str("/>\n");
+    }
+    out.push_str("        </spells>\n");
+}
+
+fn write_color(out: &mut String, color: Option<&String>) {
+    let Some(color) = color.and_then(|c| Srgba::hex(c).ok()) else {
+        return;
+    };
+    let [r, g, b, _] = color.to_u8_array();
+    let _ = writeln!(
+        out,
+        "        <viz:color r=\"{r}\" g=\"{g}\" b=\"{b}\" a=\"{}\"/>",
+        color.alpha
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dynamic_graph_round_trips() {
+        let source = r#"<gexf version="1.3">
+  <graph mode="dynamic" defaultedgetype="directed" timeformat="double">
+    <nodes>
+      <node id="a" label="A" start="1" end="5"/>
+      <node id="b" label="B">
+        <spells>
+          <spell start="2" end="3"/>
+          <spell start="7"/>
+        </spells>
+      </node>
+    </nodes>
+    <edges>
+      <edge id="0" source="a" target="b" start="2"/>
+    </edges>
+  </graph>
+</gexf>"#;
+        let spec = parse(source).unwrap();
+        let written = write(&spec);
+        assert!(written.contains("mode=\"dynamic\""));
+        assert!(!written.contains("attvalue"));
+        let reread = parse(&written).unwrap();
+        for (a, b) in spec.nodes.iter().zip(&reread.nodes) {
+            assert_eq!(a.attributes, b.attributes);
+        }
+        assert_eq!(reread.nodes[0].attributes.get("start").unwrap(), "1");
+        assert_eq!(
+            reread.nodes[1].attributes.get("spells").unwrap(),
+            "2:3 7:inf"
+        );
+        assert_eq!(reread.edges[0].attributes.get("start").unwrap(), "2");
+    }
+
+    #[test]
+    fn static_graph_stays_static() {
+        let spec = GraphSpec::new().node("a").node("b").edge("a", "b");
+        assert!(write(&spec).contains("mode=\"static\""));
+    }
+}
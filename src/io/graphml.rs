@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use bevy::platform::collections::HashMap;
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+
+use super::{
+    GraphIoError, read_weights,
+    xml::{attr, required},
+};
+use crate::spec::{EdgeSpec, GraphSpec, NodeSpec};
+
+struct Key {
+    name: String,
+    domain: String,
+    default: Option<String>,
+}
+
+enum Element {
+    Node(NodeSpec),
+    Edge(EdgeSpec),
+}
+
+/// Parses GraphML. `<data>` values become attributes named after their
+/// `<key>`'s `attr.name`, and a key named `weight`, in any case, feeds the
+/// edge weight.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let mut reader = Reader::from_str(contents);
+    reader.config_mut().trim_text(true);
+
+    let mut spec = GraphSpec::new();
+    let mut keys: HashMap<String, Key> = HashMap::default();
+    let mut current_key: Option<String> = None;
+    let mut element: Option<Element> = None;
+    let mut data_key: Option<String> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"key" => current_key = Some(read_key(&e, &mut keys)?),
+                b"default" | b"data" => {
+                    text.clear();
+                    if e.local_name().as_ref() == b"data" {
+                        data_key = attr(&e, b"key")?;
+                    }
+                }
+                b"graph" => {
+                    spec.directed |= attr(&e, b"edgedefault")?.as_deref() == Some("directed")
+                }
+                b"node" if element.is_none() => element = Some(Element::Node(read_node(&e)?)),
+                b"edge" if element.is_none() => element = Some(Element::Edge(read_edge(&e)?)),
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"key" => {
+                    read_key(&e, &mut keys)?;
+                }
+                b"node" if element.is_none() => spec.nodes.push(read_node(&e)?),
+                b"edge" if element.is_none() => spec.edges.push(read_edge(&e)?),
+                _ => {}
+            },
+            Event::Text(t) => text.push_str(&t.unescape()?),
+            Event::CData(t) => text.push_str(&String::from_utf8_lossy(&t)),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"key" => current_key = None,
+                b"default" => {
+                    if let Some(key) = current_key.as_ref().and_then(|k| keys.get_mut(k)) {
+                        key.default = Some(text.clone());
+                    }
+                }
+                b"data" => {
+                    if let (Some(id), Some(el)) = (data_key.take(), element.as_mut()) {
+                        let name = keys.get(&id).map_or(id.clone(), |k| k.name.clone());
+                        match el {
+                            Element::Node(n) => {
+                                n.attributes.insert(name, text.clone());
+                            }
+                            Element::Edge(ed) => {
+                                ed.attributes.insert(name, text.clone());
+                            }
+                        }
+                    }
+                }
+                b"node" => {
+                    if let Some(Element::Node(n)) = element.take() {
+                        spec.nodes.push(n);
+                    }
+                }
+                b"edge" => {
+                    if let Some(Element::Edge(ed)) = element.take() {
+                        spec.edges.push(ed);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    for node in &mut spec.nodes {
+        apply_defaults(&mut node.attributes, &keys, "node");
+    }
+    for edge in &mut spec.edges {
+        apply_defaults(&mut edge.attributes, &keys, "edge");
+    }
+    read_weights(&mut spec);
+    spec.add_missing_nodes();
+    Ok(spec)
+}
+
+fn read_key(e: &BytesStart, keys: &mut HashMap<String, Key>) -> Result<String, GraphIoError> {
+    let id = required(e, b"id")?;
+    let key = Key {
+        name: attr(e, b"attr.name")?.unwrap_or_else(|| id.clone()),
+        domain: attr(e, b"for")?.unwrap_or_else(|| "all".into()),
+        default: None,
+    };
+    keys.insert(id.clone(), key);
+    Ok(id)
+}
+
+fn read_node(e: &BytesStart) -> Result<NodeSpec, GraphIoError> {
+    Ok(NodeSpec::new(required(e, b"id")?))
+}
+
+fn read_edge(e: &BytesStart) -> Result<EdgeSpec, GraphIoError> {
+    Ok(EdgeSpec::new(
+        required(e, b"source")?,
+        required(e, b"target")?,
+    ))
+}
+
+fn apply_defaults(
+    attributes: &mut BTreeMap<String, String>,
+    keys: &HashMap<String, Key>,
+    domain: &str,
+) {
+    for key in keys.values() {
+        if key.domain != domain && key.domain != "all" {
+            continue;
+        }
+        if let Some(default) = &key.default {
+            attributes
+                .entry(key.name.clone())
+                .or_insert_with(|| default.clone());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fs, path::PathBuf};
+
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde_json::{Value, json};
+
+use super::{GraphIoError, GraphSnapshot};
+use crate::{graph::Graph, spec::GraphSpec};
+
+/// Writes the current node positions as layout JSON.
+#[derive(Event, Clone, Debug)]
+pub struct ExportLayout(pub PathBuf);
+
+/// Moves nodes to the positions stored in a layout JSON file.
+#[derive(Event, Clone, Debug)]
+pub struct ImportLayout(pub PathBuf);
+
+/// File used by the Ctrl+E / Ctrl+L shortcuts.
+#[derive(Resource, Clone, Debug)]
+pub struct LayoutFile(pub PathBuf);
+
+impl Default for LayoutFile {
+    fn default() -> Self {
+        Self("layout.json".into())
+    }
+}
+
+/// `[{ "id": ..., "x": ..., "y": ... }, ...]`, one entry per positioned node.
+pub fn write(spec: &GraphSpec) -> String {
+    let nodes: Vec<Value> = spec
+        .nodes
+        .iter()
+        .filter_map(|n| {
+            let pos = n.position?;
+            Some(json!({ "id": n.id, "x": pos.x, "y": pos.y }))
+        })
+        .collect();
+    serde_json::to_string_pretty(&nodes).unwrap_or_default()
+}
+
+pub fn read(contents: &str) -> Result<HashMap<String, Vec2>, GraphIoError> {
+    let root: Value = serde_json::from_str(contents)?;
+    let Some(entries) = root.as_array() else {
+        return Err(GraphIoError::Parse("layout must be an array".into()));
+    };
+    entries
+        .iter()
+        .map(|e| {
+            let id = match e.get("id") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => return Err(GraphIoError::Parse("layout entry without `id`".into())),
+            };
+            let coord = |k: &str| {
+                e.get(k)
+                    .and_then(Value::as_f64)
+                    .ok_or_else(|| GraphIoError::Parse(format!("`{id}` is missing `{k}`")))
+            };
+            Ok((
+                id.clone(),
+                Vec2::new(coord("x")? as f32, coord("y")? as f32),
+            ))
+        })
+        .collect()
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<ExportLayout>()
+        .add_event::<ImportLayout>()
+        .init_resource::<LayoutFile>()
+        .add_systems(
+            Update,
+            (layout_hotkeys, export_layout, import_layout).chain(),
+        );
+}
+
+fn layout_hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    file: Res<LayoutFile>,
+    mut export: EventWriter<ExportLayout>,
+    mut import: EventWriter<ImportLayout>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyE) {
+        export.write(ExportLayout(file.0.clone()));
+    }
+    if keys.just_pressed(KeyCode::KeyL) {
+        import.write(ImportLayout(file.0.clone()));
+    }
+}
+
+fn export_layout(mut events: EventReader<ExportLayout>, snapshot: GraphSnapshot) {
+    for ExportLayout(path) in events.read() {
+        match fs::write(path, write(&snapshot.spec())) {
+            Ok(()) => info!("exported layout to {}", path.display()),
+            Err(e) => error!("failed to export layout to {}: {e}", path.display()),
+        }
+    }
+}
+
+fn import_layout(
+    mut events: EventReader<ImportLayout>,
+    graph: Res<Graph>,
+    mut nodes: Query<(&mut Transform, &mut LinearVelocity)>,
+) {
+    for ImportLayout(path) in events.read() {
+        let positions = match fs::read_to_string(path)
+            .map_err(GraphIoError::from)
+            .and_then(|c| read(&c))
+        {
+            Ok(p) => p,
+            Err(e) => {
+                error!("failed to import layout from {}: {e}", path.display());
+                continue;
+            }
+        };
+        for (id, pos) in positions {
+            let Some(Ok((mut transform, mut velocity))) =
+                graph.node_by_id(&id).map(|e| nodes.get_mut(e))
+            else {
+                continue;
+            };
+            transform.translation = pos.extend(transform.translation.z);
+            velocity.0 = Vec2::ZERO;
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
+};
+
+use super::{GraphIoError, LoadGraph, parse, watch::GraphWatcher};
+use crate::{
+    config::Config,
+    graph::GraphElement,
+    history::History,
+    setup::{GraphSpawner, seed_positions},
+    spec::GraphSpec,
+};
+
+/// Nodes and edges added per frame once a file is parsed.
+const SPAWN_BATCH: usize = 5000;
+
+/// Bytes read from the file at a time.
+const READ_CHUNK: usize = 1 << 20;
+
+/// How far a [`LoadGraph`] has got, there only while one is going.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct LoadProgress {
+    pub stage: LoadStage,
+    /// Fraction of the stage that is done, from 0 to 1.
+    pub done: f32,
+}
+
+/// Steps of a [`LoadGraph`]. The file is read and parsed on a background
+/// thread, then its nodes and edges are added a batch per frame so the
+/// window stays responsive and the layout gets going on what's in already.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadStage {
+    Reading,
+    Parsing,
+    Spawning,
+}
+
+/// A parsed spec and where its nodes start.
+type Parsed = (GraphSpec, Vec<Vec2>);
+
+/// The load in flight. Loading another file drops it, cancelling the task.
+#[derive(Resource)]
+enum Loading {
+    Parsing {
+        path: PathBuf,
+        task: Task<Result<Parsed, GraphIoError>>,
+        /// Bytes read so far, out of `size`.
+        read: Arc<AtomicUsize>,
+        size: usize,
+    },
+    Spawning {
+        path: PathBuf,
+        spec: GraphSpec,
+        seeds: Vec<Vec2>,
+        ids: HashMap<String, Entity>,
+        /// Nodes and edges spawned so far.
+        nodes: usize,
+        edges: usize,
+    },
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            start_loading.run_if(on_event::<LoadGraph>),
+            continue_loading.run_if(resource_exists::<Loading>),
+        )
+            .chain(),
+    );
+}
+
+fn start_loading(mut cmd: Commands, mut events: EventReader<LoadGraph>, config: Res<Config>) {
+    let Some(LoadGraph(path)) = events.read().last() else {
+        return;
+    };
+    let size = fs::metadata(path).map_or(0, |m| m.len() as usize);
+    let read = Arc::new(AtomicUsize::new(0));
+    let (counter, file) = (read.clone(), path.clone());
+    let (placement, ideal_length) = (config.placement, config.ideal_length);
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let contents = read_counting(&file, &counter)?;
+        let spec = parse(&file, &contents)?;
+        let seeds = seed_positions(&spec, placement, ideal_length);
+        Ok((spec, seeds))
+    });
+    cmd.insert_resource(Loading::Parsing {
+        path: path.clone(),
+        task,
+        read,
+        size,
+    });
+    cmd.insert_resource(LoadProgress {
+        stage: LoadStage::Reading,
+        done: 0.,
+    });
+}
+
+/// Reads the file at `path`, bumping `read` as the bytes come in.
+fn read_counting(path: &Path, read: &AtomicUsize) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK];
+    loop {
+        let n = file.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..n]);
+        read.fetch_add(n, Ordering::Relaxed);
+    }
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Waits for the parse, then swaps the graph for the new one a batch at a
+/// time.
+fn continue_loading(
+    mut cmd: Commands,
+    mut loading: ResMut<Loading>,
+    mut progress: ResMut<LoadProgress>,
+    mut spawner: GraphSpawner,
+    existing: Query<Entity, GraphElement>,
+    mut history: ResMut<History>,
+    watcher: Option<ResMut<GraphWatcher>>,
+) {
+    match &mut *loading {
+        Loading::Parsing {
+            path,
+            task,
+            read,
+            size,
+        } => {
+            let Some(parsed) = block_on(future::poll_once(task)) else {
+                let read = read.load(Ordering::Relaxed);
+                *progress = if read < *size {
+                    LoadProgress {
+                        stage: LoadStage::Reading,
+                        done: read as f32 / *size as f32,
+                    }
+                } else {
+                    LoadProgress {
+                        stage: LoadStage::Parsing,
+                        done: 0.,
+                    }
+                };
+                return;
+            };
+            let (spec, seeds) = match parsed {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    error!("failed to load {}: {e}", path.display());
+                    cmd.remove_resource::<Loading>();
+                    cmd.remove_resource::<LoadProgress>();
+                    return;
+                }
+            };
+            for id in &existing {
+                spawner.cmd.entity(id).despawn();
+            }
+            spawner.set_directed(spec.directed);
+            history.clear();
+            *loading = Loading::Spawning {
+                path: std::mem::take(path),
+                spec,
+                seeds,
+                ids: HashMap::default(),
+                nodes: 0,
+                edges: 0,
+            };
+        }
+        Loading::Spawning {
+            path,
+            spec,
+            seeds,
+            ids,
+            nodes,
+            edges,
+        } => {
+            // Nodes first, so every edge finds its ends.
+            let n = (spec.nodes.len() - *nodes).min(SPAWN_BATCH);
+            let range = *nodes..*nodes + n;
+            spawner.spawn_nodes(
+                &spec.nodes[range.clone()],
+                seeds.get(range).unwrap_or_default(),
+                ids,
+            );
+            *nodes += n;
+            let m = (spec.edges.len() - *edges).min(SPAWN_BATCH - n);
+            spawner.spawn_edges(&spec.edges[*edges..*edges + m], ids);
+            *edges += m;
+
+            let total = spec.nodes.len() + spec.edges.len();
+            *progress = LoadProgress {
+                stage: LoadStage::Spawning,
+                done: (*nodes + *edges) as f32 / total.max(1) as f32,
+            };
+            if *nodes + *edges < total {
+                return;
+            }
+            info!(
+                "loaded {} nodes and {} edges from {}",
+                spec.nodes.len(),
+                spec.edges.len(),
+                path.display()
+            );
+            if let Some(mut watcher) = watcher {
+                watcher.watch(&*path);
+            }
+            cmd.remove_resource::<Loading>();
+            cmd.remove_resource::<LoadProgress>();
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    graph::{Attributes, Edge, Graph, GraphNode, IncludeDisabled, NodeId},
+    setup::GraphSpawner,
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+};
+
+pub mod csv;
+pub mod d3;
+pub mod dot;
+pub mod gexf;
+pub mod graphml;
+pub mod layout;
+mod loading;
+pub mod pajek;
+pub mod scene;
+#[cfg(feature = "websocket")]
+pub mod stream;
+pub mod svg;
+pub mod watch;
+mod xml;
+
+pub use loading::{LoadProgress, LoadStage};
+
+#[derive(Debug)]
+pub enum GraphIoError {
+    Io(std::io::Error),
+    Parse(String),
+    UnsupportedFormat(PathBuf),
+}
+
+impl fmt::Display for GraphIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse(msg) => write!(f, "parse error: {msg}"),
+            Self::UnsupportedFormat(path) => {
+                write!(f, "unsupported graph format: {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphIoError {}
+
+impl From<std::io::Error> for GraphIoError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for GraphIoError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Parse(e.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for GraphIoError {
+    fn from(e: quick_xml::Error) -> Self {
+        Self::Parse(e.to_string())
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for GraphIoError {
+    fn from(e: quick_xml::events::attributes::AttrError) -> Self {
+        Self::Parse(e.to_string())
+    }
+}
+
+/// Reads a graph file, picking the importer from its extension.
+pub fn load(path: impl AsRef<Path>) -> Result<GraphSpec, GraphIoError> {
+    let path = path.as_ref();
+    parse(path, &fs::read_to_string(path)?)
+}
+
+/// Parses the `contents` of the file at `path` with the importer for its
+/// extension.
+fn parse(path: &Path, contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("graphml") => graphml::parse(contents),
+        Some("dot" | "gv") => dot::parse(contents),
+        Some("gexf") => gexf::parse(contents),
+        Some("json") => d3::parse(contents),
+        Some("csv" | "tsv") => csv::parse(contents),
+        Some("net") => pajek::parse(contents),
+        _ => Err(GraphIoError::UnsupportedFormat(path.to_owned())),
+    }
+}
+
+/// Sets each edge's weight from its `weight` attribute, whatever its case, so
+/// every importer weighs edges the same way. Formats with a weight field of
+/// their own, such as a CSV third column or a GEXF `weight`, file it under
+/// that attribute first. Weights that aren't finite and non-negative are
+/// ignored with a warning.
+pub(crate) fn read_weights(spec: &mut GraphSpec) {
+    for edge in &mut spec.edges {
+        let Some(value) = edge
+            .attributes
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("weight"))
+            .map(|(_, v)| v)
+        else {
+            continue;
+        };
+        match parse_weight(value) {
+            Some(weight) => edge.weight = weight,
+            None => warn!(
+                "edge {} -> {}: ignoring weight `{value}`",
+                edge.source, edge.target
+            ),
+        }
+    }
+}
+
+/// `value` as an edge weight, if it's a finite number no less than zero.
+pub(crate) fn parse_weight(value: &str) -> Option<f32> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|w: &f32| w.is_finite() && *w >= 0.)
+}
+
+/// Writes a graph file, picking the exporter from its extension.
+pub fn save(path: impl AsRef<Path>, spec: &GraphSpec) -> Result<(), GraphIoError> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let contents = match ext.as_deref() {
+        Some("gexf") => gexf::write(spec),
+        Some("dot" | "gv") => dot::write(spec),
+        _ => return Err(GraphIoError::UnsupportedFormat(path.to_owned())),
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Replaces the current graph with the contents of a file, read in the
+/// background and added over several frames; see [`LoadProgress`].
+#[derive(Event, Clone, Debug)]
+pub struct LoadGraph(pub PathBuf);
+
+/// Adds the nodes and edges of a spec to the live graph, see
+/// [`LoadGraph`] to replace it instead. Nodes whose ID is already taken are
+/// the existing node, so the spec's edges can reach into the live graph.
+#[derive(Event, Clone, Debug)]
+pub struct SpawnGraph(pub GraphSpec);
+
+/// Writes the live graph, with current positions, to a file.
+#[derive(Event, Clone, Debug)]
+pub struct SaveGraph(pub PathBuf);
+
+type SnapshotNode = (
+    &'static NodeId,
+    &'static Transform,
+    Option<&'static Attributes>,
+);
+
+/// Reads the live graph back into a [`GraphSpec`], positions included, along
+/// with the elements the [`Timeline`](crate::Timeline) hides right now.
+#[derive(SystemParam)]
+pub struct GraphSnapshot<'w, 's> {
+    graph: Res<'w, Graph>,
+    nodes: Query<'w, 's, SnapshotNode, (With<GraphNode>, IncludeDisabled)>,
+    edges: Query<'w, 's, (&'static Edge, Option<&'static Attributes>), IncludeDisabled>,
+}
+
+impl GraphSnapshot<'_, '_> {
+    pub fn spec(&self) -> GraphSpec {
+        let mut spec = GraphSpec {
+            directed: self.graph.is_directed(),
+            ..default()
+        };
+        for (id, transform, attributes) in &self.nodes {
+            let mut node = NodeSpec::new(id.0.clone()).at(transform.translation.xy());
+            if let Some(a) = attributes {
+                node.attributes = a.0.clone();
+            }
+            spec.nodes.push(node);
+        }
+        for (edge, attributes) in &self.edges {
+            let (Ok((source, ..)), Ok((target, ..))) =
+                (self.nodes.get(edge.source), self.nodes.get(edge.target))
+            else {
+                continue;
+            };
+            let mut e = EdgeSpec::new(source.0.clone(), target.0.clone()).with_weight(edge.weight);
+            if let Some(a) = attributes {
+                e.attributes = a.0.clone();
+            }
+            spec.edges.push(e);
+        }
+        spec.nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        spec.edges
+            .sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+        spec
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins((
+        layout::plugin,
+        loading::plugin,
+        scene::plugin,
+        svg::plugin,
+        watch::plugin,
+    ))
+    .add_event::<LoadGraph>()
+    .add_event::<SaveGraph>()
+    .add_event::<SpawnGraph>()
+    .add_systems(Update, (spawn_graph, save_graph));
+    #[cfg(feature = "websocket")]
+    app.add_plugins(stream::plugin);
+}
+
+fn spawn_graph(mut events: EventReader<SpawnGraph>, mut spawner: GraphSpawner) {
+    for SpawnGraph(spec) in events.read() {
+        spawner.merge(spec);
+    }
+}
+
+fn save_graph(mut events: EventReader<SaveGraph>, snapshot: GraphSnapshot) {
+    for SaveGraph(path) in events.read() {
+        match save(path, &snapshot.spec()) {
+            Ok(()) => info!("saved graph to {}", path.display()),
+            Err(e) => error!("failed to save {}: {e}", path.display()),
+        }
+    }
+}
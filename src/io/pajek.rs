@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use super::{GraphIoError, read_weights};
+use crate::spec::{EdgeSpec, GraphSpec, NodeSpec};
+
+/// Pajek coordinates live in the unit square; this maps them onto roughly the
+/// same area the random initial placement covers.
+const PAJEK_SCALE: f32 = 1000.;
+
+enum Section {
+    None,
+    Vertices,
+    Arcs,
+    Edges,
+    ArcsList,
+    EdgesList,
+}
+
+/// Parses a Pajek `.net` file. Vertices are identified by their number and
+/// keep their quoted name as the `label` attribute; `*Arcs` make the graph
+/// directed, in which case `*Edges` entries are tagged `directed = false`. A
+/// numeric third column on an edge line is its weight.
+pub fn parse(contents: &str) -> Result<GraphSpec, GraphIoError> {
+    let mut spec = GraphSpec::new();
+    let mut section = Section::None;
+    let mut undirected = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let err = |msg: &str| GraphIoError::Parse(format!("line {}: {msg}", i + 1));
+        let tokens = split(line);
+        if let Some(keyword) = line.strip_prefix('*') {
+            let keyword = keyword
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            section = match keyword.as_str() {
+                "vertices" => {
+                    let n: usize = tokens
+                        .get(1)
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| err("missing vertex count"))?;
+                    spec.nodes = (1..=n).map(|v| NodeSpec::new(v.to_string())).collect();
+                    Section::Vertices
+                }
+                "arcs" => Section::Arcs,
+                "edges" => Section::Edges,
+                "arcslist" => Section::ArcsList,
+                "edgeslist" => Section::EdgesList,
+                _ => Section::None,
+            };
+            continue;
+        }
+        let vertex = |t: &String| -> Result<String, GraphIoError> {
+            t.parse::<usize>()
+                .map(|v| v.to_string())
+                .map_err(|_| err(&format!("invalid vertex `{t}`")))
+        };
+        match section {
+            Section::None => {}
+            Section::Vertices => {
+                let index: usize = tokens[0].parse().map_err(|_| err("invalid vertex"))?;
+                let Some(node) = index.checked_sub(1).and_then(|i| spec.nodes.get_mut(i)) else {
+                    return Err(err("vertex out of range"));
+                };
+                if let Some(label) = tokens.get(1) {
+                    node.attributes.insert("label".into(), label.clone());
+                }
+                let coord = |j: usize| tokens.get(j).and_then(|c| c.parse::<f32>().ok());
+                if let (Some(x), Some(y)) = (coord(2), coord(3)) {
+                    node.position = Some(Vec2::new(x - 0.5, 0.5 - y) * PAJEK_SCALE);
+                }
+            }
+            Section::Arcs | Section::Edges => {
+                let (Some(s), Some(t)) = (tokens.first(), tokens.get(1)) else {
+                    return Err(err("expected two vertices"));
+                };
+                let mut edge = EdgeSpec::new(vertex(s)?, vertex(t)?);
+                if let Some(weight) = tokens.get(2).filter(|w| w.parse::<f32>().is_ok()) {
+                    edge.attributes.insert("weight".into(), weight.clone());
+                }
+                if matches!(section, Section::Edges) {
+                    undirected.push(spec.edges.len());
+                } else {
+                    spec.directed = true;
+                }
+                spec.edges.push(edge);
+            }
+            Section::ArcsList | Section::EdgesList => {
+                let source = vertex(&tokens[0])?;
+                for t in &tokens[1..] {
+                    if matches!(section, Section::EdgesList) {
+                        undirected.push(spec.edges.len());
+                    } else {
+                        spec.directed = true;
+                    }
+                    spec.edges.push(EdgeSpec::new(source.clone(), vertex(t)?));
+                }
+            }
+        }
+    }
+
+    if spec.directed {
+        for i in undirected {
+            spec.edges[i]
+                .attributes
+                .insert("directed".into(), "false".into());
+        }
+    }
+    read_weights(&mut spec);
+    spec.add_missing_nodes();
+    Ok(spec)
+}
+
+/// Whitespace-separated tokens, with `"..."` kept together.
+fn split(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            tokens.push(quoted[..end].to_owned());
+            rest = quoted.get(end + 1..).unwrap_or_default();
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            tokens.push(rest[..end].to_owned());
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    tokens
+}
@@ -0,0 +1,262 @@
+use std::{any::TypeId, fs, path::PathBuf};
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::{DynamicEntity, serde::SceneDeserializer},
+};
+use serde::de::DeserializeSeed;
+
+use super::GraphIoError;
+use crate::{
+    config::Config,
+    graph::{
+        Attributes, Edge, EdgeCompliance, EdgeDirection, EdgeLength, GraphElement, GraphNode,
+        IncludeDisabled, NodeId, Pinned,
+    },
+    history::History,
+    setup::GraphSpawner,
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+};
+
+/// Writes the whole session, i.e. nodes with their positions, attributes
+/// and pins, edges with their overrides, and the [`Config`], as a Bevy scene
+/// in RON.
+#[derive(Event, Clone, Debug)]
+pub struct SaveScene(pub PathBuf);
+
+/// Replaces the graph and the [`Config`] with a scene written by
+/// [`SaveScene`].
+#[derive(Event, Clone, Debug)]
+pub struct LoadScene(pub PathBuf);
+
+/// File used by the Ctrl+S / Ctrl+O shortcuts.
+#[derive(Resource, Clone, Debug)]
+pub struct SceneFile(pub PathBuf);
+
+impl Default for SceneFile {
+    fn default() -> Self {
+        Self("session.scn.ron".into())
+    }
+}
+
+/// Saved edge along with what [`EdgeSpec`] can't carry.
+struct SceneEdge {
+    spec: EdgeSpec,
+    direction: Option<EdgeDirection>,
+    length: Option<EdgeLength>,
+    compliance: Option<EdgeCompliance>,
+}
+
+/// Contents of a scene file, ready to respawn.
+struct Scene {
+    directed: bool,
+    nodes: Vec<(NodeSpec, bool)>,
+    edges: Vec<SceneEdge>,
+    config: Option<Config>,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Config>()
+        .add_event::<SaveScene>()
+        .add_event::<LoadScene>()
+        .init_resource::<SceneFile>()
+        .add_systems(Update, (scene_hotkeys, save_scene, load_scene).chain());
+}
+
+fn scene_hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    file: Res<SceneFile>,
+    mut save: EventWriter<SaveScene>,
+    mut load: EventWriter<LoadScene>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyS) {
+        save.write(SaveScene(file.0.clone()));
+    }
+    if keys.just_pressed(KeyCode::KeyO) {
+        load.write(LoadScene(file.0.clone()));
+    }
+}
+
+fn save_scene(
+    mut events: EventReader<SaveScene>,
+    world: &World,
+    elements: Query<Entity, (GraphElement, IncludeDisabled)>,
+    registry: Res<AppTypeRegistry>,
+) {
+    for SaveScene(path) in events.read() {
+        let scene = DynamicSceneBuilder::from_world(world)
+            .deny_all()
+            .allow_component::<GraphNode>()
+            .allow_component::<NodeId>()
+            .allow_component::<Transform>()
+            .allow_component::<Attributes>()
+            .allow_component::<Pinned>()
+            .allow_component::<Edge>()
+            .allow_component::<EdgeDirection>()
+            .allow_component::<EdgeLength>()
+            .allow_component::<EdgeCompliance>()
+            .allow_resource::<Config>()
+            .extract_entities(elements.iter())
+            .extract_resources()
+            .build();
+        let written = scene
+            .serialize(&registry.read())
+            .map_err(|e| GraphIoError::Parse(e.to_string()))
+            .and_then(|contents| Ok(fs::write(path, contents)?));
+        match written {
+            Ok(()) => info!("saved scene to {}", path.display()),
+            Err(e) => error!("failed to save scene to {}: {e}", path.display()),
+        }
+    }
+}
+
+fn load_scene(
+    mut cmd: Commands,
+    mut events: EventReader<LoadScene>,
+    registry: Res<AppTypeRegistry>,
+) {
+    let Some(LoadScene(path)) = events.read().last() else {
+        return;
+    };
+    let scene = fs::read_to_string(path)
+        .map_err(GraphIoError::from)
+        .and_then(|contents| read(&contents, &registry.read()));
+    match scene {
+        Ok(scene) => {
+            // The config goes in first so the new nodes and joints follow it.
+            cmd.queue(move |world: &mut World| {
+                if let Some(config) = scene.config.clone() {
+                    world.insert_resource(config);
+                }
+                if let Err(e) = world.run_system_cached_with(respawn, scene) {
+                    error!("failed to spawn scene: {e}");
+                }
+            });
+        }
+        Err(e) => error!("failed to load scene from {}: {e}", path.display()),
+    }
+}
+
+fn respawn(
+    In(scene): In<Scene>,
+    mut spawner: GraphSpawner,
+    existing: Query<Entity, GraphElement>,
+    mut history: ResMut<History>,
+) {
+    for id in &existing {
+        spawner.cmd.entity(id).despawn();
+    }
+    let mut pinned = Vec::new();
+    let nodes = scene
+        .nodes
+        .into_iter()
+        .map(|(node, pin)| {
+            if pin {
+                pinned.push(node.id.clone());
+            }
+            node
+        })
+        .collect();
+    let ids = spawner.spawn(&GraphSpec {
+        nodes,
+        edges: Vec::new(),
+        directed: scene.directed,
+    });
+    for &id in pinned.iter().filter_map(|id| ids.get(id)) {
+        spawner.cmd.entity(id).insert(Pinned);
+    }
+    for edge in scene.edges {
+        let (Some(&source), Some(&target)) =
+            (ids.get(&edge.spec.source), ids.get(&edge.spec.target))
+        else {
+            continue;
+        };
+        let id = spawner.spawn_edge_spec(&edge.spec, source, target);
+        let mut entity = spawner.cmd.entity(id);
+        match edge.direction {
+            Some(direction) => entity.insert(direction),
+            None => entity.remove::<EdgeDirection>(),
+        };
+        if let Some(length) = edge.length {
+            entity.insert(length);
+        }
+        if let Some(compliance) = edge.compliance {
+            entity.insert(compliance);
+        }
+    }
+    history.clear();
+}
+
+/// Picks a scene file apart into nodes, edges and the config.
+fn read(contents: &str, registry: &TypeRegistry) -> Result<Scene, GraphIoError> {
+    let mut deserializer = ron::de::Deserializer::from_str(contents)
+        .map_err(|e| GraphIoError::Parse(e.to_string()))?;
+    let scene = SceneDeserializer {
+        type_registry: registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|e| GraphIoError::Parse(e.to_string()))?;
+    let node_ids: HashMap<Entity, String> = scene
+        .entities
+        .iter()
+        .filter_map(|e| Some((e.entity, component::<NodeId>(e)?.0)))
+        .collect();
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for entity in &scene.entities {
+        let attributes = component::<Attributes>(entity).unwrap_or_default().0;
+        if let Some(id) = node_ids.get(&entity.entity) {
+            let mut node = NodeSpec::new(id.clone());
+            if let Some(transform) = component::<Transform>(entity) {
+                node = node.at(transform.translation.xy());
+            }
+            node.attributes = attributes;
+            nodes.push((node, component::<Pinned>(entity).is_some()));
+        } else if let Some(edge) = component::<Edge>(entity) {
+            let (Some(source), Some(target)) =
+                (node_ids.get(&edge.source), node_ids.get(&edge.target))
+            else {
+                warn!("skipping saved edge {}: unknown endpoint", entity.entity);
+                continue;
+            };
+            let mut spec = EdgeSpec::new(source.clone(), target.clone()).with_weight(edge.weight);
+            spec.attributes = attributes;
+            edges.push(SceneEdge {
+                spec,
+                direction: component(entity),
+                length: component(entity),
+                compliance: component(entity),
+            });
+        }
+    }
+    let config = scene
+        .resources
+        .iter()
+        .find_map(|r| from_reflect::<Config>(r.as_ref()));
+    Ok(Scene {
+        directed: edges.iter().any(|e| e.direction.is_some()),
+        nodes,
+        edges,
+        config,
+    })
+}
+
+fn component<T: FromReflect>(entity: &DynamicEntity) -> Option<T> {
+    entity
+        .components
+        .iter()
+        .find_map(|c| from_reflect(c.as_ref()))
+}
+
+fn from_reflect<T: FromReflect>(value: &dyn PartialReflect) -> Option<T> {
+    value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_id() == TypeId::of::<T>())
+        .then(|| T::from_reflect(value))
+        .flatten()
+}
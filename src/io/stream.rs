@@ -0,0 +1,460 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use serde_json::{Map, Value};
+use tungstenite::{HandshakeError, Message, WebSocket, http::Uri};
+
+use super::{
+    GraphIoError,
+    d3::{attributes, scalar},
+};
+use crate::{
+    commands::GraphCommands,
+    graph::{Edge, GraphElement},
+    setup::GraphSpawner,
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+};
+
+/// Seconds to wait before reconnecting once the connection drops.
+const RETRY_SECS: u64 = 2;
+/// How often the stream thread looks up from waiting to check whether its
+/// [`GraphStream`] is gone.
+const POLL: Duration = Duration::from_millis(250);
+/// How long to try each address of the server for.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One change to the live graph, as streamed by a [`GraphStream`].
+#[derive(Clone, Debug)]
+pub enum StreamMessage {
+    /// Spawns a node, or sets the given attributes if the ID is taken.
+    AddNode(NodeSpec),
+    RemoveNode(String),
+    /// Spawns an edge, along with any endpoint that doesn't exist yet.
+    AddEdge(EdgeSpec),
+    /// Removes every edge from `source` to `target`.
+    RemoveEdge {
+        source: String,
+        target: String,
+    },
+    /// Sets attributes of a node, removing those set to `None`.
+    UpdateNode {
+        id: String,
+        attributes: BTreeMap<String, Option<String>>,
+    },
+    /// Like [`UpdateNode`](Self::UpdateNode), for every edge from `source` to
+    /// `target`, optionally reweighting them.
+    UpdateEdge {
+        source: String,
+        target: String,
+        weight: Option<f32>,
+        attributes: BTreeMap<String, Option<String>>,
+    },
+    /// Removes the whole graph.
+    Clear,
+}
+
+impl StreamMessage {
+    /// Parses a text message: a JSON object, or an array of them, whose `op`
+    /// is one of `add_node`, `remove_node`, `add_edge`, `remove_edge`,
+    /// `update_node`, `update_edge` and `clear`. Nodes are named by `id` and
+    /// edges by `source` and `target`; `x`/`y` place a new node, `weight`
+    /// weights an edge and every other scalar field is an attribute, which
+    /// the updates remove when it's `null`.
+    pub fn parse(text: &str) -> Result<Vec<Self>, GraphIoError> {
+        match serde_json::from_str(text)? {
+            Value::Array(messages) => messages.iter().map(Self::from_json).collect(),
+            message => Ok(vec![Self::from_json(&message)?]),
+        }
+    }
+
+    fn from_json(message: &Value) -> Result<Self, GraphIoError> {
+        let Some(obj) = message.as_object() else {
+            return Err(GraphIoError::Parse("message is not an object".into()));
+        };
+        let field = |key: &str| {
+            obj.get(key)
+                .and_then(scalar)
+                .ok_or_else(|| GraphIoError::Parse(format!("message is missing `{key}`")))
+        };
+        let weight = obj.get("weight").and_then(Value::as_f64).map(|w| w as f32);
+        let op = obj.get("op").and_then(Value::as_str).unwrap_or_default();
+        Ok(match op {
+            "add_node" => {
+                let mut node = NodeSpec::new(field("id")?);
+                if let (Some(x), Some(y)) = (
+                    obj.get("x").and_then(Value::as_f64),
+                    obj.get("y").and_then(Value::as_f64),
+                ) {
+                    node.position = Some(Vec2::new(x as f32, y as f32));
+                }
+                node.attributes = attributes(obj, &["op", "id", "x", "y"]);
+                Self::AddNode(node)
+            }
+            "remove_node" => Self::RemoveNode(field("id")?),
+            "add_edge" => {
+                let mut edge = EdgeSpec::new(field("source")?, field("target")?)
+                    .with_weight(weight.unwrap_or(1.));
+                edge.attributes = attributes(obj, &["op", "source", "target", "weight"]);
+                Self::AddEdge(edge)
+            }
+            "remove_edge" => Self::RemoveEdge {
+                source: field("source")?,
+                target: field("target")?,
+            },
+            "update_node" => Self::UpdateNode {
+                id: field("id")?,
+                attributes: changes(obj, &["op", "id"]),
+            },
+            "update_edge" => Self::UpdateEdge {
+                source: field("source")?,
+                target: field("target")?,
+                weight,
+                attributes: changes(obj, &["op", "source", "target", "weight"]),
+            },
+            "clear" => Self::Clear,
+            "" => return Err(GraphIoError::Parse("message is missing `op`".into())),
+            op => return Err(GraphIoError::Parse(format!("unknown op `{op}`"))),
+        })
+    }
+}
+
+fn changes(obj: &Map<String, Value>, skip: &[&str]) -> BTreeMap<String, Option<String>> {
+    obj.iter()
+        .filter(|(k, _)| !skip.contains(&k.as_str()))
+        .filter_map(|(k, v)| match v {
+            Value::Null => Some((k.clone(), None)),
+            v => Some((k.clone(), Some(scalar(v)?))),
+        })
+        .collect()
+}
+
+/// Applies [`StreamMessage`]s read from a WebSocket endpoint to the live
+/// graph as they arrive, for watching build graphs, service meshes and the
+/// like evolve. The connection runs on its own thread and is reopened
+/// whenever it drops; each text message holds one or more changes, see
+/// [`StreamMessage::parse`]. Like [`GraphCommands`], streamed edits aren't
+/// recorded in the [`History`](crate::History). Dropping the resource closes
+/// the connection and stops the thread.
+///
+/// Only plain `ws://` URLs are supported.
+#[derive(Resource, Debug)]
+pub struct GraphStream {
+    url: String,
+    messages: Mutex<Receiver<StreamMessage>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl GraphStream {
+    /// Starts connecting to `url` in the background. Fails only if the URL
+    /// is malformed; connection errors are logged and retried.
+    pub fn connect(url: impl Into<String>) -> Result<Self, GraphIoError> {
+        let url = url.into();
+        let endpoint = Endpoint::parse(&url)?;
+        let (sender, receiver) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = shutdown.clone();
+        thread::Builder::new()
+            .name("graph-stream".into())
+            .spawn(move || run(&endpoint, &sender, &stop))?;
+        Ok(Self {
+            url,
+            messages: Mutex::new(receiver),
+            shutdown,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for GraphStream {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, receive.run_if(resource_exists::<GraphStream>));
+}
+
+fn receive(mut cmd: Commands, stream: Res<GraphStream>) {
+    let Ok(messages) = stream.messages.lock() else {
+        return;
+    };
+    for message in messages.try_iter() {
+        // One at a time, so each sees the nodes the ones before it added.
+        cmd.queue(move |world: &mut World| {
+            if let Err(e) = world.run_system_cached_with(apply, message) {
+                warn!("failed to apply stream message: {e}");
+            }
+        });
+    }
+}
+
+fn apply(
+    In(message): In<StreamMessage>,
+    mut spawner: GraphSpawner,
+    edges: Query<&Edge>,
+    elements: Query<Entity, GraphElement>,
+) {
+    let graph = spawner.graph();
+    match message {
+        StreamMessage::AddNode(node) => match graph.node_by_id(&node.id) {
+            Some(id) => {
+                for (key, value) in node.attributes {
+                    spawner.cmd.set_attribute(id, key, Some(value));
+                }
+            }
+            None => {
+                spawner.spawn_node(&node);
+            }
+        },
+        StreamMessage::RemoveNode(id) => {
+            if let Some(node) = graph.node_by_id(&id) {
+                spawner.cmd.entity(node).despawn();
+            }
+        }
+        StreamMessage::AddEdge(edge) => spawner.merge(&GraphSpec {
+            nodes: vec![
+                NodeSpec::new(edge.source.clone()),
+                NodeSpec::new(edge.target.clone()),
+            ],
+            edges: vec![edge],
+            ..default()
+        }),
+        StreamMessage::RemoveEdge { source, target } => {
+            for edge in edges_between(&spawner, &source, &target) {
+                spawner.cmd.entity(edge).despawn();
+            }
+        }
+        StreamMessage::UpdateNode { id, attributes } => {
+            let Some(node) = graph.node_by_id(&id) else {
+                warn!("not updating node {id}: no such node");
+                return;
+            };
+            for (key, value) in attributes {
+                spawner.cmd.set_attribute(node, key, value);
+            }
+        }
+        StreamMessage::UpdateEdge {
+            source,
+            target,
+            weight,
+            attributes,
+        } => {
+            for id in edges_between(&spawner, &source, &target) {
+                if let (Some(weight), Ok(&edge)) = (weight, edges.get(id)) {
+                    // Reinserting resizes the joint too.
+                    spawner.insert_edge(id, edge.with_weight(weight));
+                }
+                for (key, value) in &attributes {
+                    spawner.cmd.set_attribute(id, key, value.clone());
+                }
+            }
+        }
+        StreamMessage::Clear => {
+            for id in &elements {
+                spawner.cmd.entity(id).despawn();
+            }
+        }
+    }
+}
+
+/// Edges from `source` to `target`, or between them either way in an
+/// undirected graph.
+fn edges_between(spawner: &GraphSpawner, source: &str, target: &str) -> Vec<Entity> {
+    let graph = spawner.graph();
+    let (Some(source), Some(target)) = (graph.node_by_id(source), graph.node_by_id(target)) else {
+        return Vec::new();
+    };
+    graph
+        .edges_between(source, target)
+        .filter(|&e| !graph.is_directed() || graph.endpoints(e) == Some((source, target)))
+        .collect()
+}
+
+/// Where a `ws://` URL points.
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    address: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> Result<Self, GraphIoError> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|e| GraphIoError::Parse(format!("{url}: {e}")))?;
+        if uri.scheme_str() != Some("ws") {
+            return Err(GraphIoError::Parse(format!(
+                "{url}: only ws:// URLs are supported"
+            )));
+        }
+        let Some(host) = uri.host().filter(|h| !h.is_empty()) else {
+            return Err(GraphIoError::Parse(format!("{url}: missing host")));
+        };
+        Ok(Self {
+            url: url.to_string(),
+            address: format!("{host}:{}", uri.port_u16().unwrap_or(80)),
+        })
+    }
+}
+
+/// Why a connection ended without an error.
+enum Disconnect {
+    Server,
+    App,
+}
+
+fn run(endpoint: &Endpoint, sender: &Sender<StreamMessage>, shutdown: &AtomicBool) {
+    loop {
+        let result = session(endpoint, sender, shutdown);
+        if shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+        match result {
+            Ok(Disconnect::App) => return,
+            Ok(Disconnect::Server) => info!("{} closed the stream", endpoint.url),
+            Err(e) => warn!("graph stream from {} failed: {e}", endpoint.url),
+        }
+        for _ in 0..Duration::from_secs(RETRY_SECS).div_duration_f32(POLL) as u32 {
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+            thread::sleep(POLL);
+        }
+    }
+}
+
+/// Reads messages until the connection closes or nobody listens anymore.
+fn session(
+    endpoint: &Endpoint,
+    sender: &Sender<StreamMessage>,
+    shutdown: &AtomicBool,
+) -> tungstenite::Result<Disconnect> {
+    let mut socket = handshake(endpoint)?;
+    info!("streaming graph from {}", endpoint.url);
+    loop {
+        let text = match socket.read() {
+            // Reads time out so the thread notices `shutdown` even while the
+            // server is quiet.
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                if shutdown.load(Ordering::Relaxed) {
+                    return Ok(close(&mut socket));
+                }
+                continue;
+            }
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(Disconnect::Server),
+            Ok(Message::Text(text)) => text.to_string(),
+            Ok(Message::Binary(bytes)) => match String::from_utf8(bytes.into()) {
+                Ok(text) => text,
+                Err(e) => {
+                    warn!("skipping stream message: {e}");
+                    continue;
+                }
+            },
+            // Answering pings and closes is left to tungstenite, which does
+            // so on the next read.
+            Ok(_) => continue,
+            Err(e) => return Err(e),
+        };
+        match StreamMessage::parse(&text) {
+            Ok(messages) => {
+                for message in messages {
+                    if sender.send(message).is_err() {
+                        return Ok(close(&mut socket));
+                    }
+                }
+            }
+            Err(e) => warn!("skipping stream message: {e}"),
+        }
+    }
+}
+
+/// Says goodbye to the server on the app's behalf.
+fn close(socket: &mut WebSocket<TcpStream>) -> Disconnect {
+    let _ = socket.close(None);
+    let _ = socket.flush();
+    Disconnect::App
+}
+
+fn connect(endpoint: &Endpoint) -> io::Result<TcpStream> {
+    let mut last = io::Error::new(io::ErrorKind::NotFound, "host has no address");
+    for address in endpoint.address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(socket) => return Ok(socket),
+            Err(e) => last = e,
+        }
+    }
+    Err(last)
+}
+
+fn handshake(endpoint: &Endpoint) -> tungstenite::Result<WebSocket<TcpStream>> {
+    let socket = connect(endpoint)?;
+    socket.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    let (socket, _) = tungstenite::client(endpoint.url.as_str(), socket).map_err(|e| match e {
+        HandshakeError::Failure(e) => e,
+        HandshakeError::Interrupted(_) => io::Error::from(io::ErrorKind::TimedOut).into(),
+    })?;
+    socket.get_ref().set_read_timeout(Some(POLL))?;
+    Ok(socket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_messages() {
+        let messages = StreamMessage::parse(
+            r#"[
+                {"op": "add_node", "id": "a", "x": 1, "y": 2, "team": "core"},
+                {"op": "add_edge", "source": "a", "target": 2, "weight": 3},
+                {"op": "update_edge", "source": "a", "target": "b", "team": null},
+                {"op": "clear"}
+            ]"#,
+        )
+        .unwrap();
+        let [
+            StreamMessage::AddNode(node),
+            StreamMessage::AddEdge(edge),
+            StreamMessage::UpdateEdge {
+                weight, attributes, ..
+            },
+            StreamMessage::Clear,
+        ] = messages.as_slice()
+        else {
+            panic!("unexpected messages: {messages:?}");
+        };
+        assert_eq!(node.id, "a");
+        assert_eq!(node.position, Some(Vec2::new(1., 2.)));
+        assert_eq!(
+            node.attributes.get("team").map(String::as_str),
+            Some("core")
+        );
+        assert_eq!((edge.target.as_str(), edge.weight), ("2", 3.));
+        assert_eq!(*weight, None);
+        assert_eq!(attributes.get("team"), Some(&None));
+
+        assert!(StreamMessage::parse(r#"{"op": "remove_node"}"#).is_err());
+        assert!(StreamMessage::parse(r#"{"op": "explode"}"#).is_err());
+        assert!(StreamMessage::parse(r#"{"id": "a"}"#).is_err());
+    }
+}
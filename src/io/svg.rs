@@ -0,0 +1,248 @@
+use std::{fmt::Write, fs, path::PathBuf};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    analysis::spanning::OffTree,
+    config::Config,
+    graph::{Edge, EdgeDirection, GraphNode, NodeRadius},
+    hidden::Hidden,
+    render::{
+        culling::Culled,
+        edges::{ARROW, EdgePath, EdgeStroke, arrow_tips, route_paths},
+        lod::Subsampled,
+        shapes::{NodeShape, capsule_size},
+    },
+};
+
+/// Writes the graph as it currently looks to an SVG file.
+#[derive(Event, Clone, Debug)]
+pub struct ExportSvg(pub PathBuf);
+
+pub struct SvgNode {
+    pub position: Vec2,
+    pub radius: f32,
+    pub shape: NodeShape,
+    pub color: Color,
+}
+
+pub struct SvgEdge {
+    /// Polyline the edge is drawn along, in world space.
+    pub path: Vec<Vec2>,
+    pub width: f32,
+    pub color: Color,
+    /// Corners of each arrowhead, in world space.
+    pub arrows: Vec<[Vec2; 3]>,
+}
+
+const PADDING: f32 = 20.;
+
+/// Renders nodes in their shapes over their edges' lines and arrowheads.
+/// World y points up, so it is flipped for SVG.
+pub fn write(nodes: &[SvgNode], edges: &[SvgEdge], background: Color) -> String {
+    // Capsules reach furthest from their node's centre, about 1.3 radii.
+    let node_bounds = nodes
+        .iter()
+        .flat_map(|n| [n.position - n.radius * 1.5, n.position + n.radius * 1.5]);
+    let edge_bounds = edges.iter().flat_map(|e| e.path.iter().copied());
+    let (min, max) = node_bounds.chain(edge_bounds).fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), p| (min.min(p), max.max(p)),
+    );
+    let (min, max) = if nodes.is_empty() {
+        (Vec2::ZERO, Vec2::ZERO)
+    } else {
+        (min - PADDING, max + PADDING)
+    };
+    let size = max - min;
+    let flip = |p: Vec2| Vec2::new(p.x - min.x, max.y - p.y);
+    let points = |ps: &mut dyn Iterator<Item = Vec2>| {
+        ps.map(|p| {
+            let p = flip(p);
+            format!("{},{}", p.x, p.y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">",
+        size.x, size.y, size.x, size.y
+    );
+    let _ = writeln!(
+        out,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(background)
+    );
+    out.push_str("  <g stroke-linecap=\"round\" stroke-linejoin=\"round\" fill=\"none\">\n");
+    for edge in edges.iter().filter(|e| e.path.len() >= 2) {
+        let _ = writeln!(
+            out,
+            "    <polyline points=\"{}\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{}\"/>",
+            points(&mut edge.path.iter().copied()),
+            hex(edge.color),
+            edge.color.alpha(),
+            edge.width
+        );
+    }
+    out.push_str("  </g>\n  <g>\n");
+    for edge in edges {
+        for arrow in &edge.arrows {
+            let _ = writeln!(
+                out,
+                "    <polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+                points(&mut arrow.iter().copied()),
+                hex(edge.color),
+                edge.color.alpha()
+            );
+        }
+    }
+    out.push_str("  </g>\n  <g>\n");
+    for node in nodes {
+        let p = flip(node.position);
+        let fill = format!(
+            "fill=\"{}\" fill-opacity=\"{}\"",
+            hex(node.color),
+            node.color.alpha()
+        );
+        if let Some(corners) = node.shape.corners(node.radius) {
+            let _ = writeln!(
+                out,
+                "    <polygon points=\"{}\" {fill}/>",
+                points(&mut corners.into_iter().map(|c| node.position + c))
+            );
+        } else if node.shape == NodeShape::Capsule {
+            let (round, half) = capsule_size(node.radius);
+            let _ = writeln!(
+                out,
+                "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{round}\" {fill}/>",
+                p.x - half - round,
+                p.y - round,
+                2. * (half + round),
+                2. * round
+            );
+        } else {
+            let _ = writeln!(
+                out,
+                "    <circle cx=\"{}\" cy=\"{}\" r=\"{}\" {fill}/>",
+                p.x, p.y, node.radius
+            );
+        }
+    }
+    out.push_str("  </g>\n</svg>\n");
+    out
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.to_srgba().to_u8_array();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+type SvgNodeData = (
+    &'static Transform,
+    &'static NodeRadius,
+    Option<&'static NodeShape>,
+    Option<&'static MeshMaterial2d<ColorMaterial>>,
+);
+
+/// An edge, with how it's drawn where the renderer is running.
+type SvgEdgeData = (
+    Entity,
+    &'static Edge,
+    Option<&'static EdgeDirection>,
+    Option<&'static EdgePath>,
+    Option<&'static EdgeStroke>,
+    Has<Hidden>,
+    Has<OffTree>,
+    Has<Culled>,
+    Has<Subsampled>,
+);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<ExportSvg>().add_systems(Update, export_svg);
+}
+
+/// Exports the graph as drawn: each edge's traced path and stroke, and each
+/// node's shape and color. Edges hidden from view are left out, except for
+/// those only sampled out of a simplified drawing. Paths aren't kept up to
+/// date offscreen, in a simplified drawing or without a renderer, so those
+/// edges are routed afresh.
+fn export_svg(
+    mut events: EventReader<ExportSvg>,
+    nodes: Query<SvgNodeData, With<GraphNode>>,
+    edges: Query<SvgEdgeData>,
+    // Missing in headless apps, whose nodes have no material.
+    materials: Option<Res<Assets<ColorMaterial>>>,
+    clear: Option<Res<ClearColor>>,
+    config: Res<Config>,
+) {
+    let background = clear.map_or(ClearColor::default().0, |c| c.0);
+    for ExportSvg(path) in events.read() {
+        let svg_nodes: Vec<SvgNode> = nodes
+            .iter()
+            .map(|(t, r, shape, m)| SvgNode {
+                position: t.translation.xy(),
+                radius: r.0,
+                shape: shape.copied().unwrap_or_default(),
+                color: m
+                    .zip(materials.as_ref())
+                    .and_then(|(m, materials)| materials.get(&m.0))
+                    .map_or(Color::WHITE, |m| m.color),
+            })
+            .collect();
+        let node = |id| {
+            let (transform, radius, ..) = nodes.get(id).ok()?;
+            Some((transform.translation.xy(), radius.0))
+        };
+        let shown: Vec<_> = edges
+            .iter()
+            .filter(|&(.., hidden, off_tree, _, _)| !hidden && !off_tree)
+            .collect();
+        let stale: Vec<(Entity, Edge)> = shown
+            .iter()
+            .filter(|&&(_, _, _, path, .., culled, sampled_out)| {
+                path.is_none_or(|p| p.0.is_empty()) || culled || sampled_out
+            })
+            .map(|&(id, edge, ..)| (id, *edge))
+            .collect();
+        let mut routed: HashMap<Entity, Vec<Vec2>> = stale
+            .iter()
+            .map(|&(id, _)| id)
+            .zip(route_paths(&stale, node, config.edge_curvature))
+            .collect();
+        let svg_edges: Vec<SvgEdge> = shown
+            .into_iter()
+            .map(|(id, edge, direction, path, stroke, ..)| {
+                let path = routed
+                    .remove(&id)
+                    .unwrap_or_else(|| path.map(|p| p.0.clone()).unwrap_or_default());
+                let radius = |id| node(id).map_or(0., |(_, r)| r);
+                let arrows = arrow_tips(
+                    &path,
+                    direction.copied(),
+                    radius(edge.source),
+                    radius(edge.target),
+                )
+                .into_iter()
+                .flatten()
+                .map(|(tip, dir)| {
+                    let rotation = Rot2::radians(dir.to_angle());
+                    ARROW.map(|c| tip + rotation * (c * config.arrow_size))
+                })
+                .collect();
+                SvgEdge {
+                    path,
+                    width: stroke.map_or(config.edge_width, |s| s.width),
+                    color: stroke.map_or(config.edge_color, |s| s.color),
+                    arrows,
+                }
+            })
+            .collect();
+        match fs::write(path, write(&svg_nodes, &svg_edges, background)) {
+            Ok(()) => info!("exported svg to {}", path.display()),
+            Err(e) => error!("failed to export svg to {}: {e}", path.display()),
+        }
+    }
+}
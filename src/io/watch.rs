@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use super::load;
+use crate::{
+    edit::set_attribute,
+    graph::{Attributes, Edge, EdgeLabel, GraphNode, NodeId},
+    history::History,
+    setup::GraphSpawner,
+    spec::{EdgeSpec, GraphSpec},
+};
+
+/// Seconds between checks of the watched file.
+const POLL_SECS: f32 = 0.5;
+
+/// Reloads a graph file whenever it changes on disk. Rather than respawning
+/// everything, the new contents are diffed against the live graph by
+/// [`NodeId`]: nodes and edges that went away are removed, new ones are
+/// added and the rest keep their positions, with their attributes and
+/// weights updated. [`LoadGraph`](super::LoadGraph) moves the watcher over
+/// to the file it loads.
+#[derive(Resource, Debug)]
+pub struct GraphWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl GraphWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            modified: modified(&path),
+            path,
+            timer: Timer::from_seconds(POLL_SECS, TimerMode::Repeating),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Watches `path` from now on, taking its current contents as seen.
+    pub fn watch(&mut self, path: impl Into<PathBuf>) {
+        *self = Self::new(path);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, reload.run_if(resource_exists::<GraphWatcher>));
+}
+
+fn reload(
+    mut watcher: ResMut<GraphWatcher>,
+    time: Res<Time>,
+    mut spawner: GraphSpawner,
+    mut nodes: Query<(Entity, &NodeId, &mut Attributes), With<GraphNode>>,
+    mut edges: Query<(Entity, &mut Edge, Option<&mut Attributes>), Without<GraphNode>>,
+    mut history: ResMut<History>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified(&watcher.path);
+    if modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+    let spec = match load(&watcher.path) {
+        Ok(spec) => spec,
+        // Likely caught halfway through being written; the next write will
+        // bring it back.
+        Err(e) => {
+            warn!("failed to reload {}: {e}", watcher.path.display());
+            return;
+        }
+    };
+    let (added, removed) = sync(&spec, &mut spawner, &mut nodes, &mut edges);
+    // Undo steps would refer to nodes that may no longer exist.
+    history.clear();
+    info!(
+        "reloaded {}: {added} elements added, {removed} removed",
+        watcher.path.display()
+    );
+}
+
+/// Brings the live graph in line with `spec`, returning how many elements
+/// were added and removed.
+fn sync(
+    spec: &GraphSpec,
+    spawner: &mut GraphSpawner,
+    nodes: &mut Query<(Entity, &NodeId, &mut Attributes), With<GraphNode>>,
+    edges: &mut Query<(Entity, &mut Edge, Option<&mut Attributes>), Without<GraphNode>>,
+) -> (usize, usize) {
+    let (mut added, mut removed) = (0, 0);
+    let wanted: HashSet<&str> = spec.nodes.iter().map(|n| n.id.as_str()).collect();
+    let mut live: HashMap<String, Entity> = HashMap::default();
+    for (id, node_id, _) in &*nodes {
+        if wanted.contains(node_id.0.as_str()) {
+            live.insert(node_id.0.clone(), id);
+        } else {
+            // Takes its edges along.
+            spawner.cmd.entity(id).despawn();
+            removed += 1;
+        }
+    }
+    for node in &spec.nodes {
+        match live.get(&node.id) {
+            Some(&id) => {
+                let Ok((.., mut attributes)) = nodes.get_mut(id) else {
+                    continue;
+                };
+                if attributes.0 == node.attributes {
+                    continue;
+                }
+                update_attributes(&mut spawner.cmd, id, &mut attributes, &node.attributes);
+            }
+            None => {
+                let id = spawner.spawn_node(node);
+                live.insert(node.id.clone(), id);
+                added += 1;
+            }
+        }
+    }
+
+    // Edges are matched up by their endpoints, in order, so parallel edges
+    // pair off one by one.
+    let mut wanted: HashMap<(Entity, Entity), Vec<&EdgeSpec>> = HashMap::default();
+    for edge in spec.edges.iter().rev() {
+        if let (Some(&source), Some(&target)) = (live.get(&edge.source), live.get(&edge.target)) {
+            wanted.entry((source, target)).or_default().push(edge);
+        }
+    }
+    for (id, mut edge, attributes) in edges {
+        let Some(spec) = wanted
+            .get_mut(&(edge.source, edge.target))
+            .and_then(Vec::pop)
+        else {
+            if spawner.graph().endpoints(id).is_some() {
+                // May already be going with one of its nodes.
+                spawner.cmd.entity(id).try_despawn();
+                removed += 1;
+            }
+            continue;
+        };
+        if edge.weight != spec.weight {
+            edge.weight = spec.weight;
+        }
+        let current = attributes.as_deref().map(|a| &a.0);
+        if current.is_some_and(|a| *a == spec.attributes)
+            || current.is_none() && spec.attributes.is_empty()
+        {
+            continue;
+        }
+        match spec.attributes.get("label") {
+            Some(label) => spawner.cmd.entity(id).insert(EdgeLabel(label.clone())),
+            None => spawner.cmd.entity(id).remove::<EdgeLabel>(),
+        };
+        spawner
+            .cmd
+            .entity(id)
+            .insert(Attributes(spec.attributes.clone()));
+    }
+    for ((source, target), specs) in wanted {
+        for spec in specs {
+            spawner.spawn_edge_spec(spec, source, target);
+            added += 1;
+        }
+    }
+    (added, removed)
+}
+
+/// Applies the difference to `new` key by key, so only what changed is
+/// touched and labels stay in step.
+fn update_attributes(
+    cmd: &mut Commands,
+    node: Entity,
+    attributes: &mut Attributes,
+    new: &BTreeMap<String, String>,
+) {
+    let gone: Vec<String> = attributes
+        .0
+        .keys()
+        .filter(|k| !new.contains_key(*k))
+        .cloned()
+        .collect();
+    for key in gone {
+        set_attribute(cmd, node, attributes, &key, None);
+    }
+    for (key, value) in new {
+        if attributes.0.get(key) != Some(value) {
+            set_attribute(cmd, node, attributes, key, Some(value.clone()));
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use quick_xml::events::BytesStart;
+
+use super::GraphIoError;
+
+pub(super) fn attr(e: &BytesStart, name: &[u8]) -> Result<Option<String>, GraphIoError> {
+    Ok(match e.try_get_attribute(name)? {
+        Some(a) => Some(a.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+pub(super) fn required(e: &BytesStart, name: &[u8]) -> Result<String, GraphIoError> {
+    attr(e, name)?.ok_or_else(|| {
+        GraphIoError::Parse(format!(
+            "<{}> is missing `{}`",
+            String::from_utf8_lossy(e.local_name().as_ref()),
+            String::from_utf8_lossy(name)
+        ))
+    })
+}
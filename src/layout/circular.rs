@@ -0,0 +1,97 @@
+use std::f32::consts::TAU;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use super::{
+    PlacedNodes, place, register,
+    registry::{OneShotLayout, drive_layout},
+};
+use crate::{config::Config, graph::Graph};
+
+/// Settings for the circular layout, which spaces every node evenly on one
+/// ring. It is instant and deterministic, so besides being a layout of its own
+/// it can warm-start the other algorithms through [`RunCircularLayout`].
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct CircularLayout {
+    /// Ring radius; `None` leaves `Config::ideal_length` of arc per node.
+    pub radius: Option<f32>,
+    /// Order nodes by a depth-first walk from the best-connected node so
+    /// that neighbours sit next to each other and fewer chords cross.
+    pub reduce_crossings: bool,
+}
+
+impl Default for CircularLayout {
+    fn default() -> Self {
+        Self {
+            radius: None,
+            reduce_crossings: true,
+        }
+    }
+}
+
+/// Arranges the current nodes on a circle, whatever the active layout.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct RunCircularLayout;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<CircularLayout>()
+        .add_event::<RunCircularLayout>()
+        .add_systems(Update, run_circular_layout.after(drive_layout));
+    register(
+        app,
+        OneShotLayout::<CircularLayout>::new("Circular", |world| {
+            world.send_event(RunCircularLayout);
+        }),
+    );
+}
+
+fn run_circular_layout(
+    mut ev_r: EventReader<RunCircularLayout>,
+    mut nodes: PlacedNodes,
+    graph: Res<Graph>,
+    settings: Res<CircularLayout>,
+    config: Res<Config>,
+) {
+    if ev_r.read().count() == 0 {
+        return;
+    }
+    let mut order: Vec<_> = graph.nodes().collect();
+    order.sort();
+    if settings.reduce_crossings {
+        order = depth_first_order(&graph, order);
+    }
+    let n = order.len() as f32;
+    let radius = settings.radius.unwrap_or(n * config.ideal_length / TAU);
+    let positions = order
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| (id, Vec2::from_angle(i as f32 / n * TAU) * radius));
+    place(&mut nodes, positions);
+}
+
+/// Depth-first visiting order over every component, starting each one from
+/// its highest-degree node and taking neighbours in entity order.
+fn depth_first_order(graph: &Graph, nodes: Vec<Entity>) -> Vec<Entity> {
+    let mut roots = nodes;
+    roots.sort_by_key(|&id| std::cmp::Reverse(graph.degree(id)));
+    let mut seen = HashSet::new();
+    let mut order = Vec::with_capacity(roots.len());
+    for root in roots {
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            order.push(id);
+            let mut next: Vec<_> = graph
+                .neighbors(id)
+                .map(|(n, _)| n)
+                .filter(|n| !seen.contains(n))
+                .collect();
+            // Reversed so the stack pops them in ascending order.
+            next.sort_by(|a, b| b.cmp(a));
+            stack.extend(next);
+        }
+    }
+    order
+}
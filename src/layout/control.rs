@@ -0,0 +1,127 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::registry::LayoutRegistry;
+use crate::forces::Iterations;
+
+/// How far a single step has got: the algorithm steps in `Update`, then the
+/// physics clock advances by one fixed timestep in the next fixed update.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Step {
+    #[default]
+    None,
+    Layout,
+    Physics,
+}
+
+/// Pauses, single-steps and restarts the layout, both the active algorithm
+/// and avian's physics clock. `Space` pauses or resumes, `.` takes a single
+/// step and `R` restarts.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LayoutControl {
+    paused: bool,
+    step: Step,
+    restart: bool,
+}
+
+impl LayoutControl {
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.step = Step::None;
+    }
+
+    pub fn toggle(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Pauses the layout and advances it by one step.
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step = Step::Layout;
+    }
+
+    /// Starts the active algorithm over, see [`LayoutRegistry::restart`],
+    /// and resets [`Iterations`].
+    pub fn restart(&mut self) {
+        self.restart = true;
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<LayoutControl>()
+        .add_systems(
+            Update,
+            (
+                hotkeys,
+                apply_control.run_if(resource_changed::<LayoutControl>),
+            )
+                .chain(),
+        )
+        .add_systems(FixedUpdate, step_physics);
+}
+
+/// Whether the active algorithm should step this frame.
+pub(crate) fn layout_running(control: Res<LayoutControl>) -> bool {
+    !control.paused || control.step == Step::Layout
+}
+
+/// Hands a single step over to physics once the algorithm has taken it.
+pub(crate) fn finish_layout_step(mut control: ResMut<LayoutControl>) {
+    if control.step == Step::Layout {
+        control.step = Step::Physics;
+    }
+}
+
+fn hotkeys(keys: Res<ButtonInput<KeyCode>>, mut control: ResMut<LayoutControl>) {
+    if keys.just_pressed(KeyCode::Space) {
+        control.toggle();
+    }
+    if keys.just_pressed(KeyCode::Period) {
+        control.step();
+    }
+    if keys.just_pressed(KeyCode::KeyR) {
+        control.restart();
+    }
+}
+
+fn apply_control(
+    mut control: ResMut<LayoutControl>,
+    mut time: ResMut<Time<Physics>>,
+    mut registry: ResMut<LayoutRegistry>,
+    mut iterations: ResMut<Iterations>,
+) {
+    match (control.paused, time.is_paused()) {
+        (true, false) => time.pause(),
+        (false, true) => time.unpause(),
+        _ => {}
+    }
+    if control.restart {
+        control.bypass_change_detection().restart = false;
+        registry.restart();
+        iterations.0 = 0;
+    }
+}
+
+/// Avian still runs a paused schedule when its clock is advanced by hand.
+fn step_physics(
+    mut control: ResMut<LayoutControl>,
+    mut time: ResMut<Time<Physics>>,
+    fixed: Res<Time<Fixed>>,
+) {
+    if control.step == Step::Physics {
+        time.advance_by(fixed.timestep());
+        control.step = Step::None;
+    }
+}
@@ -0,0 +1,183 @@
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    register,
+    registry::{FnLayout, run_system},
+};
+use crate::{
+    config::Config,
+    forces::{Iterations, QuadTree, par_accumulate, par_map_chunks},
+    graph::{Edge, Graph, GraphNode, NodeRadius},
+    spatial::SpatialGrid,
+};
+
+/// How much harder overlapping nodes push each other apart than nodes that
+/// just touch.
+const OVERLAP_REPULSION: f32 = 10.;
+
+/// Settings for the ForceAtlas2 layout (Jacomy et al., 2014). Repulsion
+/// scales with the degree of both nodes, so hubs push each other away and
+/// take their neighbourhoods with them, which separates communities well.
+#[derive(Resource, Clone, Debug)]
+pub struct ForceAtlas2 {
+    /// Repulsion strength, in units of `Config::ideal_length²`.
+    pub scaling_ratio: f32,
+    /// Pull of every node toward the origin, proportional to its degree.
+    pub gravity: f32,
+    /// Gravity grows with distance from the origin instead of staying constant.
+    pub strong_gravity: bool,
+    /// Logarithmic attraction, which gives tighter clusters. Usually wants a
+    /// smaller `scaling_ratio`.
+    pub lin_log: bool,
+    /// Attract by the gap between node rims rather than centres, and push
+    /// overlapping nodes apart hard.
+    pub prevent_overlap: bool,
+    /// Multiplier on the total force acting on every node.
+    pub speed: f32,
+}
+
+impl Default for ForceAtlas2 {
+    fn default() -> Self {
+        Self {
+            scaling_ratio: 1.,
+            gravity: 1.,
+            strong_gravity: false,
+            lin_log: false,
+            prevent_overlap: true,
+            speed: 10.,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ForceAtlas2>();
+    register(
+        app,
+        FnLayout::new("ForceAtlas2", |world| run_system(world, apply_forces)),
+    );
+}
+
+struct Body {
+    id: Entity,
+    pos: Vec2,
+    radius: f32,
+    /// Degree + 1, the FA2 "mass" that scales repulsion and gravity.
+    mass: f32,
+}
+
+fn apply_forces(
+    mut nodes: Query<(Entity, &Transform, &NodeRadius, &mut ExternalForce), With<GraphNode>>,
+    edges: Query<&Edge>,
+    graph: Res<Graph>,
+    grid: Res<SpatialGrid>,
+    settings: Res<ForceAtlas2>,
+    config: Res<Config>,
+    mut i: ResMut<Iterations>,
+) {
+    i.0 += 1;
+    let bodies: Vec<_> = nodes
+        .iter()
+        .map(|(id, t, r, _)| Body {
+            id,
+            pos: t.translation.xy(),
+            radius: r.0,
+            mass: graph.degree(id) as f32 + 1.,
+        })
+        .collect();
+    let index: HashMap<_, _> = bodies.iter().enumerate().map(|(i, b)| (b.id, i)).collect();
+    let tree = QuadTree::build(bodies.iter().map(|b| (b.pos, b.mass)).collect());
+    let kr = settings.scaling_ratio * config.ideal_length.powi(2);
+
+    let max_radius = bodies.iter().map(|b| b.radius).fold(0., f32::max);
+
+    let repulsion = par_map_chunks(&bodies, |start, chunk| {
+        (start..)
+            .zip(chunk)
+            .map(|(i, body)| {
+                let repulsion = tree.accumulate(i, config.barnes_hut_theta, |offset, m| {
+                    let dist_sq = offset.length_squared();
+                    if dist_sq == 0. {
+                        return Vec2::ZERO;
+                    }
+                    offset * (kr * body.mass * m / dist_sq)
+                });
+                let gravity = if settings.strong_gravity {
+                    -body.pos * settings.gravity * body.mass
+                } else {
+                    -body.pos.normalize_or_zero()
+                        * settings.gravity
+                        * body.mass
+                        * config.ideal_length
+                };
+                let overlap = if settings.prevent_overlap {
+                    overlap_push(i, body, &bodies, &index, &grid, max_radius, kr)
+                } else {
+                    Vec2::ZERO
+                };
+                repulsion + gravity + overlap
+            })
+            .collect()
+    });
+
+    let ends: Vec<_> = edges
+        .iter()
+        .filter_map(|edge| {
+            let (s, t) = (*index.get(&edge.source)?, *index.get(&edge.target)?);
+            Some((s, t, edge.weight))
+        })
+        .collect();
+    let attraction = par_accumulate(&ends, bodies.len(), |&(s, t, weight), totals| {
+        let offset = bodies[t].pos - bodies[s].pos;
+        let dist = offset.length();
+        if dist == 0. {
+            return;
+        }
+        let gap = if settings.prevent_overlap {
+            dist - bodies[s].radius - bodies[t].radius
+        } else {
+            dist
+        };
+        if gap <= 0. {
+            return;
+        }
+        let magnitude = if settings.lin_log {
+            (1. + gap).ln()
+        } else {
+            gap
+        };
+        let pull = offset / dist * magnitude * weight;
+        totals[s] += pull;
+        totals[t] -= pull;
+    });
+
+    for ((.., mut force), (r, a)) in nodes.iter_mut().zip(repulsion.into_iter().zip(attraction)) {
+        force.apply_force((r + a) * settings.speed);
+    }
+}
+
+/// Hard push on body `i` away from every body it overlaps.
+fn overlap_push(
+    i: usize,
+    body: &Body,
+    bodies: &[Body],
+    index: &HashMap<Entity, usize>,
+    grid: &SpatialGrid,
+    max_radius: f32,
+    kr: f32,
+) -> Vec2 {
+    let mut push = Vec2::ZERO;
+    for (other, pos) in grid.within(body.pos, body.radius + max_radius) {
+        let Some(&j) = index.get(&other) else {
+            continue;
+        };
+        let reach = body.radius + bodies[j].radius;
+        let offset = body.pos - pos;
+        let dist = offset.length();
+        if i == j || dist == 0. || dist >= reach {
+            continue;
+        }
+        push += offset / dist * (OVERLAP_REPULSION * kr * body.mass * bodies[j].mass / reach);
+    }
+    push
+}
@@ -0,0 +1,127 @@
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    register,
+    registry::{FnLayout, run_system},
+};
+use crate::{
+    config::Config,
+    forces::{Iterations, QuadTree, par_accumulate, par_map_chunks},
+    graph::{Edge, GraphNode, Pinned},
+};
+
+/// Settings and state for the Fruchterman–Reingold layout, the classic
+/// 1991 algorithm: every node moves by its net displacement, capped by a
+/// temperature that cools each iteration until the layout freezes.
+#[derive(Resource, Clone, Debug)]
+pub struct FruchtermanReingold {
+    /// Area of the square frame nodes are kept in. `None` gives every node
+    /// `Config::ideal_length²`, which makes the optimal distance `k` equal to
+    /// the ideal length.
+    pub area: Option<f32>,
+    /// Starting temperature as a fraction of the frame width.
+    pub initial_temperature: f32,
+    /// Factor the temperature is multiplied by after each iteration.
+    pub decay: f32,
+    /// The layout counts as converged once the temperature drops below this.
+    pub min_temperature: f32,
+    temperature: Option<f32>,
+}
+
+impl Default for FruchtermanReingold {
+    fn default() -> Self {
+        Self {
+            area: None,
+            initial_temperature: 0.1,
+            decay: 0.99,
+            min_temperature: 0.5,
+            temperature: None,
+        }
+    }
+}
+
+impl FruchtermanReingold {
+    /// Current displacement cap, or `None` before the first iteration.
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.temperature.is_some_and(|t| t < self.min_temperature)
+    }
+
+    /// Reheats the layout so it runs again from the initial temperature.
+    pub fn restart(&mut self) {
+        self.temperature = None;
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<FruchtermanReingold>();
+    register(
+        app,
+        FnLayout::new("Fruchterman-Reingold", |world| run_system(world, step))
+            .on_init(|world| world.resource_mut::<FruchtermanReingold>().restart())
+            .converged_when(|world| world.resource::<FruchtermanReingold>().is_converged()),
+    );
+}
+
+fn step(
+    mut nodes: Query<(Entity, &mut Transform, &mut LinearVelocity), With<GraphNode>>,
+    edges: Query<&Edge>,
+    pinned: Query<(), With<Pinned>>,
+    mut fr: ResMut<FruchtermanReingold>,
+    config: Res<Config>,
+    mut i: ResMut<Iterations>,
+) {
+    if fr.is_converged() || nodes.is_empty() {
+        return;
+    }
+    i.0 += 1;
+
+    let n = nodes.iter().len() as f32;
+    let area = fr.area.unwrap_or(n * config.ideal_length.powi(2));
+    let width = area.sqrt();
+    let k = width / n.sqrt();
+    let temperature = fr.temperature.unwrap_or(fr.initial_temperature * width);
+
+    let (ids, positions): (Vec<_>, Vec<_>) = nodes
+        .iter()
+        .map(|(id, t, _)| (id, t.translation.xy()))
+        .unzip();
+    let index: HashMap<_, _> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let tree = QuadTree::build(positions.iter().map(|&p| (p, 1.)).collect());
+    let repulsion = par_map_chunks(&positions, |start, chunk| {
+        (start..start + chunk.len())
+            .map(|i| {
+                tree.accumulate(i, config.barnes_hut_theta, |offset, m| {
+                    let dist_sq = offset.length_squared().max(f32::EPSILON);
+                    offset * (k * k * m / dist_sq)
+                })
+            })
+            .collect()
+    });
+    let ends: Vec<_> = edges
+        .iter()
+        .filter_map(|edge| Some((*index.get(&edge.source)?, *index.get(&edge.target)?)))
+        .collect();
+    let attraction = par_accumulate(&ends, ids.len(), |&(s, t), disp| {
+        let offset = positions[t] - positions[s];
+        let pull = offset * offset.length() / k;
+        disp[s] += pull;
+        disp[t] -= pull;
+    });
+    let disp = repulsion.into_iter().zip(attraction).map(|(r, a)| r + a);
+
+    let half = Vec2::splat(width / 2.);
+    for (((id, mut transform, mut velocity), pos), d) in nodes.iter_mut().zip(positions).zip(disp) {
+        if pinned.contains(id) {
+            continue;
+        }
+        let moved = (pos + d.clamp_length_max(temperature)).clamp(-half, half);
+        transform.translation = moved.extend(transform.translation.z);
+        velocity.0 = Vec2::ZERO;
+    }
+    fr.temperature = Some(temperature * fr.decay);
+}
@@ -0,0 +1,183 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    hop_distances, register,
+    registry::{FnLayout, run_system},
+};
+use crate::{
+    config::Config,
+    forces::Iterations,
+    graph::{Graph, GraphNode, Pinned},
+};
+
+/// Settings and state for the Kamada–Kawai layout. Every pair of nodes
+/// is tied by a spring whose rest length is their graph distance times
+/// `Config::ideal_length`, and nodes are moved one at a time by Newton steps
+/// until no node feels a gradient above `tolerance`.
+#[derive(Resource, Clone, Debug)]
+pub struct KamadaKawai {
+    /// Largest remaining energy gradient at which the layout counts as
+    /// converged.
+    pub tolerance: f32,
+    /// Newton steps taken per frame.
+    pub steps_per_frame: usize,
+    solver: Option<Solver>,
+}
+
+impl Default for KamadaKawai {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.1,
+            steps_per_frame: 20,
+            solver: None,
+        }
+    }
+}
+
+impl KamadaKawai {
+    pub fn is_converged(&self) -> bool {
+        self.solver.as_ref().is_some_and(|s| s.converged)
+    }
+
+    /// Recomputes graph distances and runs again from the current positions.
+    pub fn restart(&mut self) {
+        self.solver = None;
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Solver {
+    nodes: Vec<Entity>,
+    /// Spring rest length `l_ij` for every pair.
+    length: Vec<Vec<f32>>,
+    /// Spring stiffness `k_ij` for every pair.
+    stiffness: Vec<Vec<f32>>,
+    edge_count: usize,
+    converged: bool,
+}
+
+impl Solver {
+    fn new(graph: &Graph, ideal_length: f32) -> Self {
+        let nodes: Vec<_> = graph.nodes().collect();
+        let dist = hop_distances(graph, &nodes);
+        let length = dist
+            .iter()
+            .map(|row| row.iter().map(|d| d * ideal_length).collect())
+            .collect();
+        let stiffness = dist
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&d| if d > 0. { 1. / (d * d) } else { 0. })
+                    .collect()
+            })
+            .collect();
+        Self {
+            nodes,
+            length,
+            stiffness,
+            edge_count: graph.edge_count(),
+            converged: false,
+        }
+    }
+
+    fn is_stale(&self, graph: &Graph) -> bool {
+        self.nodes.len() != graph.node_count() || self.edge_count != graph.edge_count()
+    }
+
+    /// First and second partial derivatives of the energy with respect to
+    /// node `m`'s position: `(gradient, (∂²/∂x², ∂²/∂x∂y, ∂²/∂y²))`.
+    fn derivatives(&self, pos: &[Vec2], m: usize) -> (Vec2, Vec3) {
+        let mut gradient = Vec2::ZERO;
+        let mut hessian = Vec3::ZERO;
+        for i in 0..pos.len() {
+            if i == m {
+                continue;
+            }
+            let d = pos[m] - pos[i];
+            let dist = d.length().max(1e-3);
+            let (k, l) = (self.stiffness[m][i], self.length[m][i]);
+            gradient += k * (d - l * d / dist);
+            let cube = dist.powi(3);
+            hessian += k * Vec3::new(
+                1. - l * d.y * d.y / cube,
+                l * d.x * d.y / cube,
+                1. - l * d.x * d.x / cube,
+            );
+        }
+        (gradient, hessian)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<KamadaKawai>();
+    register(
+        app,
+        FnLayout::new("Kamada-Kawai", |world| run_system(world, step))
+            .on_init(|world| world.resource_mut::<KamadaKawai>().restart())
+            .converged_when(|world| world.resource::<KamadaKawai>().is_converged()),
+    );
+}
+
+fn step(
+    mut nodes: Query<(&mut Transform, &mut LinearVelocity), With<GraphNode>>,
+    pinned: Query<(), With<Pinned>>,
+    mut kk: ResMut<KamadaKawai>,
+    graph: Res<Graph>,
+    config: Res<Config>,
+    mut i: ResMut<Iterations>,
+) {
+    if kk.solver.as_ref().is_none_or(|s| s.is_stale(&graph)) {
+        kk.solver = Some(Solver::new(&graph, config.ideal_length));
+    }
+    let (steps, tolerance) = (kk.steps_per_frame, kk.tolerance);
+    let Some(solver) = kk.solver.as_mut().filter(|s| !s.converged) else {
+        return;
+    };
+    i.0 += 1;
+
+    let mut pos: Vec<_> = solver
+        .nodes
+        .iter()
+        .map(|&id| {
+            nodes
+                .get(id)
+                .map_or(Vec2::ZERO, |(t, _)| t.translation.xy())
+        })
+        .collect();
+    for _ in 0..steps {
+        let Some((m, gradient, hessian)) = (0..pos.len())
+            .filter(|&m| !pinned.contains(solver.nodes[m]))
+            .map(|m| {
+                let (g, h) = solver.derivatives(&pos, m);
+                (m, g, h)
+            })
+            .max_by(|a, b| a.1.length_squared().total_cmp(&b.1.length_squared()))
+        else {
+            break;
+        };
+        if gradient.length() < tolerance {
+            solver.converged = true;
+            break;
+        }
+        let det = hessian.x * hessian.z - hessian.y * hessian.y;
+        if det.abs() < f32::EPSILON {
+            break;
+        }
+        pos[m] += Vec2::new(
+            (-gradient.x * hessian.z + gradient.y * hessian.y) / det,
+            (gradient.x * hessian.y - gradient.y * hessian.x) / det,
+        );
+    }
+
+    for (&id, p) in solver.nodes.iter().zip(pos) {
+        if pinned.contains(id) {
+            continue;
+        }
+        if let Ok((mut transform, mut velocity)) = nodes.get_mut(id) {
+            transform.translation = p.extend(transform.translation.z);
+            velocity.0 = Vec2::ZERO;
+        }
+    }
+}
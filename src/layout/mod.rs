@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+
+use avian2d::prelude::*;
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    config::Config,
+    forces::{below_cutoff, clear_forces, physics_step, reheat},
+    graph::{Edge, EdgeCompliance, EdgeLength, Graph, GraphNode, IncludeDisabled, Pinned},
+    render::transitions::{MoveTo, Transitions},
+};
+
+pub mod circular;
+pub mod control;
+pub mod force_atlas2;
+pub mod fruchterman_reingold;
+pub mod kamada_kawai;
+pub mod radial;
+pub mod registry;
+pub mod spectral;
+pub mod stress;
+pub mod sugiyama;
+#[cfg(feature = "layout-3d")]
+pub mod three_d;
+pub mod tree;
+
+use control::{finish_layout_step, layout_running};
+use registry::{FnLayout, LayoutAlgorithm, LayoutConverged, LayoutRegistry, drive_layout};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<LayoutRegistry>()
+        .add_event::<LayoutConverged>();
+    // Registered first so that it's the default.
+    register(
+        app,
+        FnLayout::new("Physics", physics_step)
+            .with_joints()
+            .on_init(reheat)
+            .converged_when(below_cutoff),
+    );
+    app.add_plugins((
+        control::plugin,
+        force_atlas2::plugin,
+        fruchterman_reingold::plugin,
+        kamada_kawai::plugin,
+        stress::plugin,
+        sugiyama::plugin,
+        circular::plugin,
+        radial::plugin,
+        tree::plugin,
+        #[cfg(feature = "layout-3d")]
+        three_d::plugin,
+    ))
+    .add_systems(
+        Update,
+        (
+            (drive_layout.run_if(layout_running), finish_layout_step)
+                .chain()
+                .after(clear_forces),
+            sync_joints,
+            fit_joints.run_if(
+                resource_changed::<Config>
+                    .or(any_match_filter::<JointChanged>)
+                    .or(any_component_removed::<Edge>)
+                    .or(any_component_removed::<EdgeLength>)
+                    .or(any_component_removed::<EdgeCompliance>),
+            ),
+            fit_damping.run_if(resource_changed::<Config>),
+            sleep_on_converge.run_if(on_event::<LayoutConverged>),
+        ),
+    );
+}
+
+/// Adds a built-in algorithm from inside a layout sub-plugin.
+fn register(app: &mut App, algorithm: impl LayoutAlgorithm) {
+    app.world_mut()
+        .resource_mut::<LayoutRegistry>()
+        .register(algorithm);
+}
+
+type Placed = (&'static mut Transform, &'static mut LinearVelocity);
+
+/// Nodes a computed layout may move.
+#[derive(SystemParam)]
+pub(crate) struct PlacedNodes<'w, 's> {
+    cmd: Commands<'w, 's>,
+    nodes: Query<'w, 's, Placed, (With<GraphNode>, Without<Pinned>)>,
+    // Only there in apps that draw the graph.
+    transitions: Option<Res<'w, Transitions>>,
+}
+
+impl PlacedNodes<'_, '_> {
+    pub fn position(&self, node: Entity) -> Option<Vec2> {
+        self.nodes.get(node).ok().map(|(t, _)| t.translation.xy())
+    }
+}
+
+/// Moves nodes to `positions` and stops them there, gliding them over if
+/// [`Transitions`] are on. Pinned nodes stay put.
+pub(crate) fn place(nodes: &mut PlacedNodes, positions: impl IntoIterator<Item = (Entity, Vec2)>) {
+    let animate = nodes.transitions.as_ref().is_some_and(|t| t.is_enabled());
+    for (id, pos) in positions {
+        let Ok((mut transform, mut velocity)) = nodes.nodes.get_mut(id) else {
+            continue;
+        };
+        velocity.0 = Vec2::ZERO;
+        if animate {
+            let from = transform.translation.xy();
+            nodes.cmd.entity(id).insert(MoveTo::new(from, pos));
+        } else {
+            transform.translation = pos.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Edge joints only pull for algorithms that ask for them; the others bring
+/// their own attraction, so the joints are disabled while they run.
+fn sync_joints(
+    mut cmd: Commands,
+    registry: Res<LayoutRegistry>,
+    edges: Query<(Entity, Has<JointDisabled>), With<DistanceJoint>>,
+) {
+    let enabled = registry.active().is_some_and(|a| a.uses_joints());
+    for (id, disabled) in &edges {
+        match (enabled, disabled) {
+            (true, true) => {
+                cmd.entity(id).remove::<JointDisabled>();
+            }
+            (false, false) => {
+                cmd.entity(id).insert(JointDisabled);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Edges whose joint needs refitting.
+type JointChanged = Or<(Changed<Edge>, Changed<EdgeLength>, Changed<EdgeCompliance>)>;
+
+type FittedJoints<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Edge,
+        Option<&'static EdgeLength>,
+        Option<&'static EdgeCompliance>,
+        &'static mut DistanceJoint,
+    ),
+    IncludeDisabled,
+>;
+
+/// Keeps every joint's rest length and compliance in line with its edge's
+/// weight, see [`Config::joint`], or with its own overrides. Parallel edges
+/// between the same two nodes pull together like one edge of their total
+/// weight: they share its rest length, and its stiffness is split between
+/// them by weight.
+fn fit_joints(mut edges: FittedJoints, config: Res<Config>) {
+    let pair = |edge: &Edge| (edge.source.min(edge.target), edge.source.max(edge.target));
+    // Floored like `Config::joint` does, so shares add up.
+    let share = |edge: &Edge| edge.weight.max(0.1);
+    let mut totals: HashMap<(Entity, Entity), f32> = HashMap::default();
+    for (edge, ..) in &edges {
+        *totals.entry(pair(edge)).or_default() += share(edge);
+    }
+    for (edge, length_override, compliance_override, mut joint) in &mut edges {
+        let total = totals.get(&pair(edge)).copied().unwrap_or(share(edge));
+        let (length, compliance) = config.joint(total);
+        let compliance = compliance * total / share(edge);
+        let length = length_override.map_or(length, |l| l.0);
+        let compliance = compliance_override.map_or(compliance, |c| c.0);
+        if joint.rest_length != length || joint.compliance != compliance {
+            joint.rest_length = length;
+            joint.compliance = compliance;
+        }
+    }
+}
+
+/// Keeps node damping in line with [`Config::cooling_factor`].
+fn fit_damping(mut nodes: Query<&mut LinearDamping, With<GraphNode>>, config: Res<Config>) {
+    let damping = 1. / config.cooling_factor;
+    for mut node in &mut nodes {
+        node.set_if_neq(LinearDamping(damping));
+    }
+}
+
+/// Puts every moving node to sleep once the layout is done with them, see
+/// [`Config::sleep_when_converged`].
+fn sleep_on_converge(
+    mut cmd: Commands,
+    nodes: Query<(Entity, &RigidBody), With<GraphNode>>,
+    config: Res<Config>,
+) {
+    if !config.sleep_when_converged {
+        return;
+    }
+    for (id, body) in &nodes {
+        if body.is_dynamic() {
+            cmd.entity(id).insert((Sleeping, LinearVelocity::ZERO));
+        }
+    }
+}
+
+/// Hop counts between every pair of `nodes`, ignoring edge direction. Pairs in
+/// different components get one more than the longest finite distance so
+/// that components still end up near each other.
+pub(crate) fn hop_distances(graph: &Graph, nodes: &[Entity]) -> Vec<Vec<f32>> {
+    let index: HashMap<_, _> = nodes.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut dist = vec![vec![f32::INFINITY; nodes.len()]; nodes.len()];
+    let mut queue = VecDeque::new();
+    for (source, row) in dist.iter_mut().enumerate() {
+        row[source] = 0.;
+        queue.push_back(source);
+        while let Some(i) = queue.pop_front() {
+            for (neighbor, _) in graph.neighbors(nodes[i]) {
+                let Some(&j) = index.get(&neighbor) else {
+                    continue;
+                };
+                if row[j].is_infinite() {
+                    row[j] = row[i] + 1.;
+                    queue.push_back(j);
+                }
+            }
+        }
+    }
+    let longest = dist
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|d| d.is_finite())
+        .fold(0., f32::max);
+    for d in dist.iter_mut().flatten() {
+        if d.is_infinite() {
+            *d = longest + 1.;
+        }
+    }
+    dist
+}
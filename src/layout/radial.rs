@@ -0,0 +1,108 @@
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    PlacedNodes, place, register,
+    registry::{OneShotLayout, run_system},
+};
+use crate::graph::Graph;
+
+/// Settings for the radial layout: `root` sits in the centre and every
+/// other node on the ring matching its BFS depth, inside the wedge of its
+/// parent so subtrees stay together. Changing the settings re-runs the layout.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct RadialLayout {
+    /// Centre node; `None` picks the highest-degree node.
+    pub root: Option<Entity>,
+    /// Distance between consecutive rings.
+    pub ring_spacing: f32,
+}
+
+impl Default for RadialLayout {
+    fn default() -> Self {
+        Self {
+            root: None,
+            ring_spacing: 80.,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<RadialLayout>();
+    register(
+        app,
+        OneShotLayout::<RadialLayout>::new("Radial", |world| run_system(world, apply_layout)),
+    );
+}
+
+fn apply_layout(mut nodes: PlacedNodes, graph: Res<Graph>, settings: Res<RadialLayout>) {
+    let mut all: Vec<_> = graph.nodes().collect();
+    all.sort();
+    let Some(root) = settings
+        .root
+        .filter(|&r| graph.contains_node(r))
+        .or_else(|| all.iter().copied().max_by_key(|&id| graph.degree(id)))
+    else {
+        return;
+    };
+
+    // BFS tree: children in visiting order, depth of every reached node.
+    let mut children: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    let mut depth: HashMap<_, usize> = [(root, 0)].into_iter().collect();
+    let mut order = vec![root];
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        let mut next: Vec<_> = graph.neighbors(id).map(|(n, _)| n).collect();
+        next.sort();
+        for n in next {
+            if depth.contains_key(&n) {
+                continue;
+            }
+            depth.insert(n, depth[&id] + 1);
+            children.entry(id).or_default().push(n);
+            order.push(n);
+            queue.push_back(n);
+        }
+    }
+
+    // Each subtree gets a wedge proportional to its number of leaves.
+    let mut leaves: HashMap<Entity, usize> = HashMap::default();
+    for &id in order.iter().rev() {
+        let count = children
+            .get(&id)
+            .map_or(1, |c| c.iter().map(|c| leaves[c]).sum());
+        leaves.insert(id, count);
+    }
+    let mut positions = vec![(root, Vec2::ZERO)];
+    let mut wedges = vec![(root, 0., TAU)];
+    while let Some((id, start, span)) = wedges.pop() {
+        let Some(kids) = children.get(&id) else {
+            continue;
+        };
+        let total = leaves[&id] as f32;
+        let mut angle = start;
+        for &kid in kids {
+            let share = span * leaves[&kid] as f32 / total;
+            let radius = depth[&kid] as f32 * settings.ring_spacing;
+            positions.push((kid, Vec2::from_angle(angle + share / 2.) * radius));
+            wedges.push((kid, angle, share));
+            angle += share;
+        }
+    }
+
+    // Nodes outside the root's component go on one more ring.
+    let outside: Vec<_> = all
+        .into_iter()
+        .filter(|id| !depth.contains_key(id))
+        .collect();
+    let outer = (depth.values().max().copied().unwrap_or(0) + 1) as f32 * settings.ring_spacing;
+    let n = outside.len() as f32;
+    positions.extend(
+        outside
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, Vec2::from_angle(i as f32 / n * TAU) * outer)),
+    );
+    place(&mut nodes, positions);
+}
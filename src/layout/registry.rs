@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+
+use crate::graph::Graph;
+
+/// A layout algorithm that can be registered with [`LayoutRegistry`] and
+/// swapped in while the app runs. Algorithms get the whole world, so they can
+/// move nodes directly or apply forces and leave the integration to physics.
+pub trait LayoutAlgorithm: Send + Sync + 'static {
+    /// Name shown in the UI and accepted by [`LayoutRegistry::select`].
+    fn name(&self) -> &str;
+
+    /// Called when the algorithm becomes active and again whenever nodes or
+    /// edges are added or removed.
+    fn init(&mut self, world: &mut World);
+
+    /// Advances the layout by one frame.
+    fn step(&mut self, world: &mut World);
+
+    /// Once this returns `true`, `step` isn't called again until the next
+    /// `init`.
+    fn is_converged(&self, world: &World) -> bool;
+
+    /// Whether the edge joints should pull while this algorithm is active.
+    /// Algorithms that bring their own attraction leave them disabled.
+    fn uses_joints(&self) -> bool {
+        false
+    }
+}
+
+/// [`LayoutAlgorithm`] made of plain functions, for algorithms that keep their
+/// state in resources and run as systems.
+pub struct FnLayout {
+    name: &'static str,
+    init: fn(&mut World),
+    step: fn(&mut World),
+    converged: fn(&World) -> bool,
+    joints: bool,
+}
+
+impl FnLayout {
+    /// An algorithm that runs `step` every frame and never converges.
+    pub fn new(name: &'static str, step: fn(&mut World)) -> Self {
+        Self {
+            name,
+            init: |_| {},
+            step,
+            converged: |_| false,
+            joints: false,
+        }
+    }
+
+    pub fn on_init(mut self, init: fn(&mut World)) -> Self {
+        self.init = init;
+        self
+    }
+
+    pub fn converged_when(mut self, converged: fn(&World) -> bool) -> Self {
+        self.converged = converged;
+        self
+    }
+
+    pub fn with_joints(mut self) -> Self {
+        self.joints = true;
+        self
+    }
+}
+
+impl LayoutAlgorithm for FnLayout {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn init(&mut self, world: &mut World) {
+        (self.init)(world);
+    }
+
+    fn step(&mut self, world: &mut World) {
+        (self.step)(world);
+    }
+
+    fn is_converged(&self, world: &World) -> bool {
+        (self.converged)(world)
+    }
+
+    fn uses_joints(&self) -> bool {
+        self.joints
+    }
+}
+
+/// [`LayoutAlgorithm`] that places every node in a single pass and then holds
+/// still until the graph or its settings resource `S` changes.
+pub struct OneShotLayout<S> {
+    name: &'static str,
+    run: fn(&mut World),
+    applied: Option<S>,
+}
+
+impl<S> OneShotLayout<S> {
+    pub fn new(name: &'static str, run: fn(&mut World)) -> Self {
+        Self {
+            name,
+            run,
+            applied: None,
+        }
+    }
+}
+
+impl<S: Resource + Clone + PartialEq> LayoutAlgorithm for OneShotLayout<S> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn init(&mut self, _: &mut World) {
+        self.applied = None;
+    }
+
+    fn step(&mut self, world: &mut World) {
+        (self.run)(world);
+        self.applied = world.get_resource::<S>().cloned();
+    }
+
+    fn is_converged(&self, world: &World) -> bool {
+        self.applied.is_some() && self.applied.as_ref() == world.get_resource::<S>()
+    }
+}
+
+/// Sent when the active algorithm converges. Algorithms that can come out of
+/// convergence, like the physics layout after a node is dragged, send it
+/// again when they settle once more.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct LayoutConverged;
+
+/// Every available layout algorithm and which one is driving the nodes.
+/// The first registered algorithm starts out active.
+#[derive(Resource, Default)]
+pub struct LayoutRegistry {
+    algorithms: Vec<Box<dyn LayoutAlgorithm>>,
+    active: usize,
+    initialized: bool,
+    converged: bool,
+}
+
+impl LayoutRegistry {
+    /// Adds `algorithm`, replacing any registered one with the same name.
+    pub fn register(&mut self, algorithm: impl LayoutAlgorithm) -> &mut Self {
+        let algorithm = Box::new(algorithm);
+        match self.position(algorithm.name()) {
+            Some(i) => {
+                self.algorithms[i] = algorithm;
+                if i == self.active {
+                    self.initialized = false;
+                }
+            }
+            None => self.algorithms.push(algorithm),
+        }
+        self
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.algorithms.iter().map(|a| a.name())
+    }
+
+    pub fn active(&self) -> Option<&dyn LayoutAlgorithm> {
+        self.algorithms.get(self.active).map(|a| &**a)
+    }
+
+    pub fn active_name(&self) -> Option<&str> {
+        self.active().map(|a| a.name())
+    }
+
+    /// Makes the algorithm called `name` drive the layout from the next frame
+    /// on. Returns `false` if no such algorithm is registered.
+    pub fn select(&mut self, name: &str) -> bool {
+        let Some(i) = self.position(name) else {
+            return false;
+        };
+        if i != self.active {
+            self.active = i;
+            self.initialized = false;
+        }
+        true
+    }
+
+    /// Whether the active algorithm had converged as of its last frame.
+    pub fn is_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Re-initializes the active algorithm, e.g. to reheat a converged layout.
+    pub fn restart(&mut self) {
+        self.initialized = false;
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.algorithms.iter().position(|a| a.name() == name)
+    }
+}
+
+/// Runs a system once, for algorithms built from existing systems.
+pub(crate) fn run_system<M>(world: &mut World, system: impl IntoSystem<(), (), M> + 'static) {
+    if let Err(e) = world.run_system_cached(system) {
+        warn!("layout system failed: {e}");
+    }
+}
+
+/// Steps the active algorithm, initializing it first if it was just selected
+/// or the graph changed shape.
+pub(crate) fn drive_layout(world: &mut World, mut last: Local<Option<(usize, usize)>>) {
+    let graph = world.resource::<Graph>();
+    let size = Some((graph.node_count(), graph.edge_count()));
+    world.resource_scope(|world, mut registry: Mut<LayoutRegistry>| {
+        let registry = &mut *registry;
+        if *last != size {
+            *last = size;
+            registry.initialized = false;
+        }
+        let Some(algorithm) = registry.algorithms.get_mut(registry.active) else {
+            return;
+        };
+        if !registry.initialized {
+            algorithm.init(world);
+            registry.initialized = true;
+            registry.converged = false;
+        }
+        let converged = algorithm.is_converged(world);
+        if !converged {
+            algorithm.step(world);
+        } else if !registry.converged {
+            world.send_event(LayoutConverged);
+        }
+        registry.converged = converged;
+    });
+}
@@ -0,0 +1,146 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::spec::GraphSpec;
+
+/// Power-iteration rounds spent on each component's eigenvectors.
+const ITERATIONS: usize = 300;
+
+/// Where nodes without a stored position start out.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Scattered at random around the origin.
+    #[default]
+    Random,
+    /// Coordinates from the two smallest non-trivial eigenvectors of the graph
+    /// Laplacian. Connected nodes start close together, so relaxation from
+    /// here converges much faster than from a random scatter.
+    Spectral,
+}
+
+/// Spectral coordinates for every node of `spec`, in `spec.nodes` order.
+/// Components are laid out separately and then lined up left to right, with
+/// about `spacing` between neighbours.
+pub fn spectral_layout(spec: &GraphSpec, spacing: f32) -> Vec<Vec2> {
+    let index: HashMap<_, _> = spec
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let mut adjacency = vec![Vec::new(); spec.nodes.len()];
+    for edge in &spec.edges {
+        let (Some(&s), Some(&t)) = (
+            index.get(edge.source.as_str()),
+            index.get(edge.target.as_str()),
+        ) else {
+            continue;
+        };
+        if s != t {
+            adjacency[s].push(t);
+            adjacency[t].push(s);
+        }
+    }
+
+    let mut positions = vec![Vec2::ZERO; spec.nodes.len()];
+    let mut cursor = 0.;
+    for component in components(&adjacency) {
+        let coords = component_layout(&adjacency, &component, spacing);
+        let (min, max) = coords.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &p| (min.min(p), max.max(p)),
+        );
+        let offset = Vec2::new(cursor - min.x, -(min.y + max.y) / 2.);
+        for (&v, p) in component.iter().zip(coords) {
+            positions[v] = p + offset;
+        }
+        cursor += max.x - min.x + spacing;
+    }
+    let centre = (cursor - spacing).max(0.) / 2.;
+    positions
+        .into_iter()
+        .map(|p| p - Vec2::X * centre)
+        .collect()
+}
+
+fn components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; adjacency.len()];
+    let mut out = Vec::new();
+    for root in 0..adjacency.len() {
+        if seen[root] {
+            continue;
+        }
+        seen[root] = true;
+        let mut component = vec![root];
+        let mut i = 0;
+        while let Some(&v) = component.get(i) {
+            for &n in &adjacency[v] {
+                if !seen[n] {
+                    seen[n] = true;
+                    component.push(n);
+                }
+            }
+            i += 1;
+        }
+        out.push(component);
+    }
+    out
+}
+
+/// Orthogonal iteration on `cI - L`, whose largest eigenvectors orthogonal to
+/// the constant vector are the Laplacian's smallest non-trivial ones.
+fn component_layout(adjacency: &[Vec<usize>], component: &[usize], spacing: f32) -> Vec<Vec2> {
+    let n = component.len();
+    if n < 3 {
+        return (0..n).map(|i| Vec2::X * i as f32 * spacing).collect();
+    }
+    let local: HashMap<_, _> = component.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let degree: Vec<f32> = component
+        .iter()
+        .map(|&v| adjacency[v].len() as f32)
+        .collect();
+    let shift = 2. * degree.iter().copied().fold(0., f32::max);
+    let apply = |x: &[f32]| -> Vec<f32> {
+        component
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let neighbours: f32 = adjacency[v].iter().map(|n| x[local[n]]).sum();
+                (shift - degree[i]) * x[i] + neighbours
+            })
+            .collect()
+    };
+
+    // Deterministic, non-degenerate starting vectors.
+    let mut a: Vec<f32> = (0..n).map(|i| (i as f32 * 1.7).sin()).collect();
+    let mut b: Vec<f32> = (0..n).map(|i| (i as f32 * 2.3 + 1.).cos()).collect();
+    for _ in 0..ITERATIONS {
+        a = apply(&a);
+        b = apply(&b);
+        center(&mut a);
+        center(&mut b);
+        normalize(&mut a);
+        let dot: f32 = a.iter().zip(&b).map(|(x, y)| x * y).sum();
+        b.iter_mut().zip(&a).for_each(|(y, x)| *y -= dot * x);
+        normalize(&mut b);
+    }
+    // Unit eigenvectors have entries around 1/√n; scale so neighbours end up
+    // roughly `spacing` apart.
+    let scale = spacing * (n as f32).sqrt();
+    a.into_iter()
+        .zip(b)
+        .map(|(x, y)| Vec2::new(x, y) * scale)
+        .collect()
+}
+
+fn center(x: &mut [f32]) {
+    let mean = x.iter().sum::<f32>() / x.len() as f32;
+    x.iter_mut().for_each(|v| *v -= mean);
+}
+
+fn normalize(x: &mut [f32]) {
+    let norm = x.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0. {
+        x.iter_mut().for_each(|v| *v /= norm);
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use super::{
+    PlacedNodes, hop_distances, place, register,
+    registry::{OneShotLayout, drive_layout},
+};
+use crate::{config::Config, graph::Graph};
+
+/// Settings for stress majorization (Gansner, Koren & North, 2004), which
+/// places nodes so that their distances match graph distances times
+/// `Config::ideal_length` as closely as possible.
+///
+/// It runs as a single offline pass: as a registered layout it runs when
+/// selected and then holds the result, while [`RunStressMajorization`]
+/// applies it once under any layout so the active simulation starts from the
+/// result.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct StressMajorization {
+    /// Upper bound on majorization sweeps over all nodes.
+    pub sweeps: usize,
+    /// Stop once a sweep lowers the stress by less than this fraction.
+    pub tolerance: f32,
+}
+
+impl Default for StressMajorization {
+    fn default() -> Self {
+        Self {
+            sweeps: 300,
+            tolerance: 1e-4,
+        }
+    }
+}
+
+/// Runs a stress majorization pass from the current positions.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct RunStressMajorization;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<StressMajorization>()
+        .add_event::<RunStressMajorization>()
+        .add_systems(Update, run_stress_majorization.after(drive_layout));
+    register(
+        app,
+        OneShotLayout::<StressMajorization>::new("Stress majorization", |world| {
+            world.send_event(RunStressMajorization);
+        }),
+    );
+}
+
+fn run_stress_majorization(
+    mut ev_r: EventReader<RunStressMajorization>,
+    mut nodes: PlacedNodes,
+    graph: Res<Graph>,
+    settings: Res<StressMajorization>,
+    config: Res<Config>,
+) {
+    if ev_r.read().count() == 0 {
+        return;
+    }
+    let ids: Vec<_> = graph.nodes().collect();
+    let mut pos: Vec<_> = ids
+        .iter()
+        .map(|&id| nodes.position(id).unwrap_or(Vec2::ZERO))
+        .collect();
+    let length: Vec<Vec<f32>> = hop_distances(&graph, &ids)
+        .into_iter()
+        .map(|row| row.into_iter().map(|d| d * config.ideal_length).collect())
+        .collect();
+    let sweeps = majorize(&length, &mut pos, settings.sweeps, settings.tolerance);
+    debug!("stress majorization finished after {sweeps} sweeps");
+    place(&mut nodes, ids.into_iter().zip(pos));
+}
+
+fn stress(length: &[Vec<f32>], pos: &[Vec2]) -> f32 {
+    let mut total = 0.;
+    for i in 0..pos.len() {
+        for j in i + 1..pos.len() {
+            let l = length[i][j];
+            total += (pos[i].distance(pos[j]) - l).powi(2) / (l * l);
+        }
+    }
+    total
+}
+
+/// Localized SMACOF: each node in turn moves to the weighted average of where
+/// every other node says it should be. Returns the number of sweeps taken.
+fn majorize(length: &[Vec<f32>], pos: &mut [Vec2], sweeps: usize, tolerance: f32) -> usize {
+    let mut last = stress(length, pos);
+    for sweep in 1..=sweeps {
+        for i in 0..pos.len() {
+            let (mut sum, mut weights) = (Vec2::ZERO, 0.);
+            for j in 0..pos.len() {
+                if i == j {
+                    continue;
+                }
+                let l = length[i][j];
+                let w = 1. / (l * l);
+                let d = pos[i] - pos[j];
+                let dist = d.length().max(1e-3);
+                sum += w * (pos[j] + l * d / dist);
+                weights += w;
+            }
+            if weights > 0. {
+                pos[i] = sum / weights;
+            }
+        }
+        let current = stress(length, pos);
+        if last - current <= tolerance * last {
+            return sweep;
+        }
+        last = current;
+    }
+    sweeps
+}
@@ -0,0 +1,242 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    PlacedNodes, place, register,
+    registry::{OneShotLayout, run_system},
+};
+use crate::graph::Graph;
+
+/// Settings for the layered layout, a Sugiyama-style hierarchical layout
+/// for DAG-like graphs: edges are made acyclic, nodes are assigned to layers
+/// so edges point downwards, layers are reordered to reduce crossings and
+/// nodes are finally spread out on their layer.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Sugiyama {
+    /// Vertical distance between consecutive layers.
+    pub layer_spacing: f32,
+    /// Horizontal distance between neighbours on a layer.
+    pub node_spacing: f32,
+    /// Barycenter sweeps (down and back up) spent on crossing reduction.
+    pub sweeps: usize,
+}
+
+impl Default for Sugiyama {
+    fn default() -> Self {
+        Self {
+            layer_spacing: 80.,
+            node_spacing: 50.,
+            sweeps: 8,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Sugiyama>();
+    register(
+        app,
+        OneShotLayout::<Sugiyama>::new("Layered", |world| run_system(world, apply_layout)),
+    );
+}
+
+fn apply_layout(mut nodes: PlacedNodes, graph: Res<Graph>, settings: Res<Sugiyama>) {
+    let ids: Vec<_> = graph.nodes().collect();
+    let index: HashMap<_, _> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let edges: Vec<_> = graph
+        .edges()
+        .filter(|&(_, s, t)| s != t)
+        .filter_map(|(_, s, t)| Some((*index.get(&s)?, *index.get(&t)?)))
+        .collect();
+    let edges = remove_cycles(ids.len(), &edges);
+    let layer = assign_layers(ids.len(), &edges);
+    let mut layering = Layering::new(&layer, &edges);
+    layering.reduce_crossings(settings.sweeps);
+
+    let ids = &ids;
+    let Sugiyama {
+        layer_spacing,
+        node_spacing,
+        ..
+    } = *settings;
+    let height = layering.layers.len().saturating_sub(1) as f32 * layer_spacing;
+    let positions = layering.layers.iter().enumerate().flat_map(|(l, order)| {
+        let width = order.len().saturating_sub(1) as f32 * node_spacing;
+        let y = height / 2. - l as f32 * layer_spacing;
+        order.iter().enumerate().filter_map(move |(x, &v)| {
+            let pos = Vec2::new(x as f32 * node_spacing - width / 2., y);
+            ids.get(v).map(|&id| (id, pos))
+        })
+    });
+    place(&mut nodes, positions);
+}
+
+/// Reverses every edge that closes a cycle in a depth-first search, leaving a
+/// DAG with the same underlying connections.
+fn remove_cycles(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut out = vec![Vec::new(); n];
+    for (e, &(s, _)) in edges.iter().enumerate() {
+        out[s].push(e);
+    }
+    // 0 = unvisited, 1 = on the DFS stack, 2 = done.
+    let mut state = vec![0u8; n];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..n {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some(top) = stack.last_mut() {
+            let (v, next) = *top;
+            let Some(&e) = out[v].get(next) else {
+                state[v] = 2;
+                stack.pop();
+                continue;
+            };
+            top.1 += 1;
+            let t = edges[e].1;
+            match state[t] {
+                0 => {
+                    state[t] = 1;
+                    stack.push((t, 0));
+                }
+                1 => reversed[e] = true,
+                _ => {}
+            }
+        }
+    }
+    edges
+        .iter()
+        .zip(reversed)
+        .map(|(&(s, t), r)| if r { (t, s) } else { (s, t) })
+        .collect()
+}
+
+/// Longest-path layering: sources sit on layer 0 and every other node one
+/// layer below its deepest predecessor.
+fn assign_layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut indegree = vec![0; n];
+    let mut out = vec![Vec::new(); n];
+    for &(s, t) in edges {
+        out[s].push(t);
+        indegree[t] += 1;
+    }
+    let mut layer = vec![0; n];
+    let mut ready: Vec<_> = (0..n).filter(|&v| indegree[v] == 0).collect();
+    while let Some(v) = ready.pop() {
+        for &t in &out[v] {
+            layer[t] = layer[t].max(layer[v] + 1);
+            indegree[t] -= 1;
+            if indegree[t] == 0 {
+                ready.push(t);
+            }
+        }
+    }
+    layer
+}
+
+/// Node order on every layer. Edges spanning several layers are split by
+/// dummy vertices (indices past the real nodes) so that every link joins
+/// adjacent layers.
+struct Layering {
+    layers: Vec<Vec<usize>>,
+    /// Neighbours of each vertex on the layer above.
+    up: Vec<Vec<usize>>,
+    /// Neighbours of each vertex on the layer below.
+    down: Vec<Vec<usize>>,
+}
+
+impl Layering {
+    fn new(layer: &[usize], edges: &[(usize, usize)]) -> Self {
+        let depth = layer.iter().max().map_or(0, |&l| l + 1);
+        let mut layers = vec![Vec::new(); depth];
+        for (v, &l) in layer.iter().enumerate() {
+            layers[l].push(v);
+        }
+        let mut up = vec![Vec::new(); layer.len()];
+        let mut down = vec![Vec::new(); layer.len()];
+        for &(s, t) in edges {
+            let mut prev = s;
+            for between in &mut layers[layer[s] + 1..layer[t]] {
+                let dummy = up.len();
+                up.push(Vec::new());
+                down.push(Vec::new());
+                between.push(dummy);
+                down[prev].push(dummy);
+                up[dummy].push(prev);
+                prev = dummy;
+            }
+            down[prev].push(t);
+            up[t].push(prev);
+        }
+        Self { layers, up, down }
+    }
+
+    /// Alternating barycenter sweeps, keeping the ordering with the fewest
+    /// crossings seen.
+    fn reduce_crossings(&mut self, sweeps: usize) {
+        let mut best = self.layers.clone();
+        let mut best_crossings = self.crossings();
+        for _ in 0..sweeps {
+            for l in 1..self.layers.len() {
+                self.sort_by_barycenter(l, l - 1, true);
+            }
+            for l in (0..self.layers.len().saturating_sub(1)).rev() {
+                self.sort_by_barycenter(l, l + 1, false);
+            }
+            let crossings = self.crossings();
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = self.layers.clone();
+            }
+        }
+        self.layers = best;
+    }
+
+    fn sort_by_barycenter(&mut self, layer: usize, fixed: usize, from_above: bool) {
+        let rank: HashMap<_, _> = self.layers[fixed]
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i as f32))
+            .collect();
+        let neighbors = if from_above { &self.up } else { &self.down };
+        let barycenter: Vec<_> = self.layers[layer]
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let ranks: Vec<_> = neighbors[v].iter().filter_map(|n| rank.get(n)).collect();
+                if ranks.is_empty() {
+                    // Unconnected vertices keep their current slot.
+                    i as f32
+                } else {
+                    ranks.iter().copied().sum::<f32>() / ranks.len() as f32
+                }
+            })
+            .collect();
+        let mut order: Vec<_> = (0..barycenter.len()).collect();
+        order.sort_by(|&a, &b| barycenter[a].total_cmp(&barycenter[b]));
+        self.layers[layer] = order.into_iter().map(|i| self.layers[layer][i]).collect();
+    }
+
+    fn crossings(&self) -> usize {
+        self.layers
+            .windows(2)
+            .map(|pair| {
+                let rank: HashMap<_, _> =
+                    pair[1].iter().enumerate().map(|(i, &v)| (v, i)).collect();
+                let mut links = Vec::new();
+                for (i, &v) in pair[0].iter().enumerate() {
+                    links.extend(self.down[v].iter().map(|t| (i, rank[t])));
+                }
+                let mut count = 0;
+                for (a, &(s1, t1)) in links.iter().enumerate() {
+                    for &(s2, t2) in &links[a + 1..] {
+                        if (s1 < s2 && t1 > t2) || (s1 > s2 && t1 < t2) {
+                            count += 1;
+                        }
+                    }
+                }
+                count
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,442 @@
+use avian2d::{prelude as two_d, schedule::PhysicsTime as _};
+use avian3d::prelude::*;
+use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    platform::collections::HashMap,
+    prelude::*,
+    render::RenderApp,
+};
+use rand::Rng;
+use std::time::Duration;
+
+use super::{
+    register,
+    registry::{FnLayout, LayoutRegistry, run_system},
+};
+use crate::{
+    config::{Config, LayoutRng},
+    forces::{Iterations, SETTLE_FRAMES},
+    graph::{Charge, Edge, GraphNode, NodeRadius},
+    render::edges::EdgeStroke,
+};
+
+const NAME: &str = "3D";
+
+/// Settings and state for the 3D layout. Nodes get a depth coordinate and
+/// become avian3d spheres, which edges pull together through 3D distance
+/// joints and nearby nodes push apart, giving dense graphs room to untangle;
+/// the scene is shown through an [`OrbitCamera`]. Like the physics layout,
+/// it has converged once the nodes' kinetic energy stays under
+/// [`Config::convergence_energy`].
+///
+/// The nodes' 2D bodies are switched off while it runs, and nodes are
+/// flattened back onto the plane when another algorithm is selected.
+#[derive(Resource, Clone, Debug)]
+pub struct Layout3d {
+    /// How far repulsion reaches, in multiples of [`Config::ideal_length`].
+    /// Neighbors in range are found through avian3d's spatial queries, so
+    /// nodes don't have to be checked against every other.
+    pub repulsion_range: f32,
+    calm: usize,
+}
+
+impl Default for Layout3d {
+    fn default() -> Self {
+        Self {
+            repulsion_range: 4.,
+            calm: 0,
+        }
+    }
+}
+
+impl Layout3d {
+    pub fn is_converged(&self) -> bool {
+        self.calm >= SETTLE_FRAMES
+    }
+
+    /// Reheats the layout so it runs again until the nodes settle.
+    pub fn restart(&mut self) {
+        self.calm = 0;
+    }
+}
+
+/// Camera circling `focus` at `distance`; drag with the right mouse button
+/// to orbit and scroll to zoom.
+#[derive(Component, Clone, Debug)]
+pub struct OrbitCamera {
+    pub focus: Vec3,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            focus: Vec3::ZERO,
+            distance: 800.,
+            yaw: 0.,
+            pitch: 0.,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    // Spheres and the orbit camera need a renderer.
+    if app.get_sub_app(RenderApp).is_none() {
+        return;
+    }
+    // Both engines bring transform interpolation; avian2d's already covers
+    // every body.
+    app.add_plugins(
+        PhysicsPlugins::default()
+            .build()
+            .disable::<PhysicsInterpolationPlugin>(),
+    )
+    .insert_resource(Gravity::ZERO)
+    .init_resource::<Layout3d>()
+    .init_resource::<SphereHandles>()
+    .add_systems(
+        Update,
+        (
+            sync_view,
+            sync_joints,
+            (orbit, draw_edges).run_if(is_active),
+        ),
+    )
+    .add_systems(FixedUpdate, follow_clock);
+    register(
+        app,
+        FnLayout::new(NAME, |world| run_system(world, step))
+            .on_init(|world| {
+                world.resource_mut::<Layout3d>().restart();
+                run_system(world, lift_nodes);
+            })
+            .converged_when(|world| world.resource::<Layout3d>().is_converged()),
+    );
+}
+
+fn is_active(registry: Res<LayoutRegistry>) -> bool {
+    registry.active_name() == Some(NAME)
+}
+
+/// Nodes still lying flat get a random depth so the layout can unfold them.
+fn lift_nodes(
+    mut nodes: Query<&mut Transform, With<GraphNode>>,
+    config: Res<Config>,
+    mut rng: ResMut<LayoutRng>,
+) {
+    for mut transform in &mut nodes {
+        if transform.translation.z == 0. {
+            transform.translation.z = rng.gen_range(-1. ..=1.) * config.ideal_length;
+        }
+    }
+}
+
+type Bodies<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Position,
+        &'static Mass,
+        &'static LinearVelocity,
+        Option<&'static Charge>,
+        &'static mut ExternalForce,
+    ),
+    With<GraphNode>,
+>;
+
+/// Pushes every node away from the others within
+/// [`Layout3d::repulsion_range`], with the same `k q_i q_j / d` as the 2D
+/// physics layout. Edge joints and damping do the rest.
+fn step(
+    mut nodes: Bodies,
+    spatial: SpatialQuery,
+    mut settings: ResMut<Layout3d>,
+    config: Res<Config>,
+    mut i: ResMut<Iterations>,
+) {
+    if settings.is_converged() || nodes.is_empty() {
+        return;
+    }
+    i.0 += 1;
+
+    let k = config.repulsion_strength * config.ideal_length.powi(2);
+    let range = Collider::sphere(settings.repulsion_range * config.ideal_length);
+    let filter = SpatialQueryFilter::default();
+    let pushes: Vec<_> = nodes
+        .iter()
+        .map(|(id, pos, .., charge, _)| {
+            let q = charge.map_or(1., |c| c.0);
+            let push = spatial
+                .shape_intersections(&range, pos.0, Quat::IDENTITY, &filter)
+                .into_iter()
+                .filter(|&other| other != id)
+                .filter_map(|other| nodes.get(other).ok())
+                .map(|(_, other, .., charge, _)| {
+                    let offset = pos.0 - other.0;
+                    let dist_sq = offset.length_squared();
+                    if dist_sq == 0. {
+                        Vec3::ZERO
+                    } else {
+                        offset * (k * charge.map_or(1., |c| c.0) / dist_sq)
+                    }
+                })
+                .sum::<Vec3>();
+            (id, push * q)
+        })
+        .collect();
+    for (id, push) in pushes {
+        if let Ok((.., mut force)) = nodes.get_mut(id) {
+            force.set_force(push);
+        }
+    }
+
+    let n = nodes.iter().len() as f32;
+    let energy: f32 = nodes
+        .iter()
+        .map(|(_, _, mass, velocity, ..)| 0.5 * mass.0 * velocity.length_squared())
+        .sum();
+    if energy / n < config.convergence_energy {
+        settings.calm += 1;
+    } else {
+        settings.calm = 0;
+    }
+}
+
+/// The unit sphere every node is drawn with in 3D, scaled to its radius,
+/// and an unlit material per node color, so all nodes share a handful of
+/// assets like they do in 2D.
+#[derive(Resource, Default)]
+struct SphereHandles {
+    mesh: Option<Handle<Mesh>>,
+    materials: HashMap<[u8; 4], Handle<StandardMaterial>>,
+}
+
+/// [`SphereHandles`] with the assets they go in.
+#[derive(SystemParam)]
+struct SphereAssets<'w> {
+    handles: ResMut<'w, SphereHandles>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    colors: Res<'w, Assets<ColorMaterial>>,
+}
+
+impl SphereAssets<'_> {
+    fn mesh(&mut self) -> Handle<Mesh> {
+        let meshes = &mut self.meshes;
+        self.handles
+            .mesh
+            .get_or_insert_with(|| meshes.add(Sphere::new(1.)))
+            .clone()
+    }
+
+    /// Material in the color of the node's 2D `material`, to 8 bits a
+    /// channel.
+    fn material(
+        &mut self,
+        material: Option<&MeshMaterial2d<ColorMaterial>>,
+    ) -> Handle<StandardMaterial> {
+        let color = material
+            .and_then(|m| self.colors.get(&m.0))
+            .map_or(Color::WHITE, |m| m.color);
+        let rgba = color.to_srgba().to_u8_array();
+        let materials = &mut self.materials;
+        self.handles
+            .materials
+            .entry(rgba)
+            .or_insert_with(|| {
+                materials.add(StandardMaterial {
+                    base_color: Color::from(Srgba::from_u8_array(rgba)),
+                    unlit: true,
+                    // Dimmed nodes are see-through.
+                    alpha_mode: if rgba[3] < u8::MAX {
+                        AlphaMode::Blend
+                    } else {
+                        AlphaMode::Opaque
+                    },
+                    ..default()
+                })
+            })
+            .clone()
+    }
+}
+
+type ViewNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static NodeRadius,
+        Option<Ref<'static, MeshMaterial2d<ColorMaterial>>>,
+        Option<&'static MeshMaterial3d<StandardMaterial>>,
+        Has<RigidBody>,
+        Has<Pickable>,
+    ),
+    With<GraphNode>,
+>;
+
+/// Swaps between the 2D cameras and the orbit camera as the 3D layout is
+/// selected or left. While it's selected, nodes are drawn as spheres that
+/// follow their size and color, and simulated as 3D bodies in place of their
+/// 2D ones; leaving takes the spheres and bodies away again and flattens the
+/// nodes.
+fn sync_view(
+    mut cmd: Commands,
+    registry: Res<LayoutRegistry>,
+    config: Res<Config>,
+    mut cameras: Query<(&mut Camera, Has<OrbitCamera>)>,
+    mut nodes: ViewNodes,
+    mut spheres: SphereAssets,
+) {
+    let active = registry.active_name() == Some(NAME);
+    if active && !cameras.iter().any(|(_, orbit)| orbit) {
+        cmd.spawn((
+            Camera3d::default(),
+            Camera {
+                order: 1,
+                ..default()
+            },
+            OrbitCamera::default(),
+            MeshPickingCamera,
+        ));
+    }
+    for (mut camera, orbit) in &mut cameras {
+        if camera.is_active != (orbit == active) {
+            camera.is_active = orbit == active;
+        }
+    }
+
+    for (id, mut transform, radius, material, sphere, body, pickable) in &mut nodes {
+        if active {
+            let scale = Vec3::splat(radius.0);
+            if transform.scale != scale {
+                transform.scale = scale;
+            }
+            if sphere.is_none() || material.as_ref().is_some_and(Ref::is_changed) {
+                let handle = spheres.material(material.as_deref());
+                if sphere.is_none_or(|s| s.0 != handle) {
+                    cmd.entity(id)
+                        .insert((Mesh3d(spheres.mesh()), MeshMaterial3d(handle)));
+                }
+            }
+            if !body {
+                cmd.entity(id).insert((
+                    RigidBody::Dynamic,
+                    // Scaled to the node's radius along with its sphere.
+                    Collider::sphere(1.),
+                    LockedAxes::ROTATION_LOCKED,
+                    Mass(config.node_mass),
+                    LinearDamping(1. / config.cooling_factor),
+                    two_d::RigidBodyDisabled,
+                ));
+            }
+            // Spheres are picked by mesh, which wants them marked.
+            if !pickable {
+                cmd.entity(id).insert(Pickable::default());
+            }
+        } else if sphere.is_some() || body {
+            cmd.entity(id)
+                .remove::<(
+                    Mesh3d,
+                    MeshMaterial3d<StandardMaterial>,
+                    LockedAxes,
+                    Mass,
+                    LinearDamping,
+                    two_d::RigidBodyDisabled,
+                    Pickable,
+                )>()
+                .remove_with_requires::<(RigidBody, Collider)>();
+            transform.translation.z = 0.;
+            transform.scale = Vec3::ONE;
+        }
+    }
+}
+
+/// Gives every edge a 3D joint while the layout is selected, with the rest
+/// length and compliance its 2D joint was fitted to.
+fn sync_joints(
+    mut cmd: Commands,
+    registry: Res<LayoutRegistry>,
+    mut edges: Query<(Entity, &two_d::DistanceJoint, Option<&mut DistanceJoint>)>,
+) {
+    let active = registry.active_name() == Some(NAME);
+    for (id, fitted, joint) in &mut edges {
+        match (active, joint) {
+            (true, Some(mut joint)) => {
+                if joint.rest_length != fitted.rest_length || joint.compliance != fitted.compliance
+                {
+                    joint.rest_length = fitted.rest_length;
+                    joint.compliance = fitted.compliance;
+                }
+            }
+            (true, None) => {
+                cmd.entity(id).insert(
+                    DistanceJoint::new(fitted.entity1, fitted.entity2)
+                        .with_rest_length(fitted.rest_length)
+                        .with_compliance(fitted.compliance),
+                );
+            }
+            (false, Some(_)) => {
+                cmd.entity(id).remove_with_requires::<DistanceJoint>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+/// Keeps avian3d's clock in step with avian2d's, which
+/// [`LayoutControl`](super::control::LayoutControl) pauses and single-steps.
+fn follow_clock(
+    clock: Res<Time<two_d::Physics>>,
+    mut time: ResMut<Time<Physics>>,
+    mut last: Local<Duration>,
+) {
+    match (clock.is_paused(), time.is_paused()) {
+        (true, false) => time.pause(),
+        (false, true) => time.unpause(),
+        _ => {}
+    }
+    if clock.is_paused() && clock.elapsed() > *last {
+        time.advance_by(clock.elapsed() - *last);
+    }
+    *last = clock.elapsed();
+}
+
+fn orbit(
+    mut cameras: Query<(&mut OrbitCamera, &mut Transform)>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+) {
+    for (mut orbit, mut transform) in &mut cameras {
+        if buttons.pressed(MouseButton::Right) {
+            orbit.yaw -= motion.delta.x * 0.005;
+            orbit.pitch = (orbit.pitch - motion.delta.y * 0.005).clamp(-1.5, 1.5);
+        }
+        orbit.distance = (orbit.distance * (1. - scroll.delta.y * 0.1)).max(10.);
+        let rotation = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.);
+        *transform = Transform::from_translation(orbit.focus + rotation * Vec3::Z * orbit.distance)
+            .looking_at(orbit.focus, Vec3::Y);
+    }
+}
+
+/// Draws edges as lines in the colors the 2D renderer gives them, so themes,
+/// weights and emphasis carry over.
+fn draw_edges(
+    mut gizmos: Gizmos,
+    edges: Query<(&Edge, &EdgeStroke, &Visibility)>,
+    nodes: Query<&Transform, With<GraphNode>>,
+) {
+    for (edge, stroke, visibility) in &edges {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        if let Ok([s, t]) = nodes.get_many([edge.source, edge.target]) {
+            gizmos.line(s.translation, t.translation, stroke.color);
+        }
+    }
+}
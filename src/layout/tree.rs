@@ -0,0 +1,160 @@
+use std::{cmp::Reverse, collections::VecDeque};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    PlacedNodes, place, register,
+    registry::{OneShotLayout, run_system},
+};
+use crate::graph::Graph;
+
+/// Settings for the tree layout, a Reingold–Tilford tidy tree: parents
+/// are centred over their children and sibling subtrees are packed as close
+/// as their contours allow. Graphs that aren't trees are laid out along a BFS
+/// spanning tree, and each component becomes its own tree.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct TreeLayout {
+    /// Root of the first tree; `None` prefers a node without incoming edges
+    /// and then the highest degree.
+    pub root: Option<Entity>,
+    /// Vertical distance between depths.
+    pub level_spacing: f32,
+    /// Minimum horizontal gap between neighbouring subtrees.
+    pub sibling_spacing: f32,
+}
+
+impl Default for TreeLayout {
+    fn default() -> Self {
+        Self {
+            root: None,
+            level_spacing: 80.,
+            sibling_spacing: 40.,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<TreeLayout>();
+    register(
+        app,
+        OneShotLayout::<TreeLayout>::new("Tree", |world| run_system(world, apply_layout)),
+    );
+}
+
+/// Horizontal extent `(left, right)` of a subtree at every depth below its
+/// root, relative to the root.
+type Contour = Vec<(f32, f32)>;
+
+fn apply_layout(mut nodes: PlacedNodes, graph: Res<Graph>, settings: Res<TreeLayout>) {
+    let mut candidates: Vec<_> = graph.nodes().collect();
+    candidates.sort();
+    candidates.sort_by_key(|&id| {
+        let is_source = graph.predecessors(id).next().is_none() || !graph.is_directed();
+        (Reverse(is_source), Reverse(graph.degree(id)))
+    });
+    if let Some(root) = settings.root.filter(|&r| graph.contains_node(r)) {
+        candidates.insert(0, root);
+    }
+
+    // BFS spanning forest; `order` lists parents before their children.
+    let mut children: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    let mut depth: HashMap<Entity, usize> = HashMap::default();
+    let mut roots = Vec::new();
+    let mut order = Vec::new();
+    for root in candidates {
+        if depth.contains_key(&root) {
+            continue;
+        }
+        roots.push(root);
+        depth.insert(root, 0);
+        let mut queue = VecDeque::from([root]);
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            let mut next: Vec<_> = graph.neighbors(id).map(|(n, _)| n).collect();
+            next.sort();
+            for n in next {
+                if !depth.contains_key(&n) {
+                    depth.insert(n, depth[&id] + 1);
+                    children.entry(id).or_default().push(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    // Bottom-up: pack each node's child subtrees and centre it above them.
+    let spacing = settings.sibling_spacing;
+    let mut contours: HashMap<Entity, Contour> = HashMap::default();
+    let mut offset: HashMap<Entity, f32> = HashMap::default();
+    for &id in order.iter().rev() {
+        let kids = children.get(&id).map_or(&[][..], Vec::as_slice);
+        let (packed, shifts) = pack(
+            kids.iter().map(|k| contours.remove(k).unwrap_or_default()),
+            spacing,
+        );
+        let centre = match (shifts.first(), shifts.last()) {
+            (Some(first), Some(last)) => (first + last) / 2.,
+            _ => 0.,
+        };
+        for (&kid, shift) in kids.iter().zip(shifts) {
+            offset.insert(kid, shift - centre);
+        }
+        let mut contour = vec![(0., 0.)];
+        contour.extend(packed.into_iter().map(|(l, r)| (l - centre, r - centre)));
+        contours.insert(id, contour);
+    }
+
+    // The trees of the forest are packed the same way, twice as far apart.
+    let (forest, shifts) = pack(
+        roots.iter().map(|r| contours.remove(r).unwrap_or_default()),
+        2. * spacing,
+    );
+    let width = forest
+        .iter()
+        .fold((0f32, 0f32), |(l, r), &(a, b)| (l.min(a), r.max(b)));
+    for (&root, shift) in roots.iter().zip(shifts) {
+        offset.insert(root, shift - (width.0 + width.1) / 2.);
+    }
+    let height = depth.values().max().copied().unwrap_or(0) as f32 * settings.level_spacing;
+
+    // Top-down: offsets are relative to the parent, so accumulate them.
+    let mut x: HashMap<Entity, f32> = HashMap::default();
+    let mut positions = Vec::with_capacity(order.len());
+    for &id in &order {
+        let own = x.get(&id).copied().unwrap_or(0.) + offset.get(&id).copied().unwrap_or(0.);
+        for kid in children.get(&id).into_iter().flatten() {
+            x.insert(*kid, own);
+        }
+        let y = height / 2. - depth[&id] as f32 * settings.level_spacing;
+        positions.push((id, Vec2::new(own, y)));
+    }
+    place(&mut nodes, positions);
+}
+
+/// Places subtrees left to right, each shifted just far enough that it keeps
+/// `spacing` from everything before it at every depth. Returns the merged
+/// contour and the shift of each subtree.
+fn pack(subtrees: impl Iterator<Item = Contour>, spacing: f32) -> (Contour, Vec<f32>) {
+    let mut merged: Contour = Vec::new();
+    let mut shifts = Vec::new();
+    for contour in subtrees {
+        let shift = if shifts.is_empty() {
+            0.
+        } else {
+            merged
+                .iter()
+                .zip(&contour)
+                .map(|(&(_, right), &(left, _))| right - left + spacing)
+                .fold(f32::MIN, f32::max)
+        };
+        for (d, &(l, r)) in contour.iter().enumerate() {
+            let (l, r) = (l + shift, r + shift);
+            match merged.get_mut(d) {
+                Some(m) => *m = (m.0.min(l), m.1.max(r)),
+                None => merged.push((l, r)),
+            }
+        }
+        shifts.push(shift);
+    }
+    (merged, shifts)
+}
@@ -0,0 +1,181 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_cursor::TrackCursorPlugin;
+
+mod analysis;
+mod camera;
+mod collapse;
+mod commands;
+mod config;
+mod drag;
+mod edit;
+mod ego;
+mod filter;
+mod forces;
+mod graph;
+mod headless;
+mod hidden;
+mod history;
+#[cfg(feature = "petgraph")]
+mod interop;
+pub mod io;
+pub mod layout;
+mod navigation;
+mod picking;
+pub mod render;
+mod sample;
+mod screenshot;
+mod search;
+mod selection;
+mod setup;
+mod spatial;
+mod spec;
+mod stats;
+mod timeline;
+#[cfg(feature = "egui")]
+mod ui;
+
+pub use analysis::{
+    centrality::{BetweennessProgress, ComputeBetweenness, ComputePageRank, PageRank},
+    community::{Community, DetectCommunities},
+    components::{ComponentSettings, ConnectedComponent},
+    cycles::Cycles,
+    path::ShortestPath,
+    spanning::{SpanningTree, SpanningTreeMode},
+    traversal::{StartTraversal, Traversal, TraversalOrder},
+};
+pub use camera::{FitView, FocusNode};
+pub use collapse::{CollapseNodes, CollapsedInto, ExpandNode, MetaNode};
+pub use commands::GraphCommands;
+pub use config::{Config, LayoutRng};
+pub use drag::DragMode;
+pub use edit::{AddEdge, AddNode, DeleteElements, SetAttribute, SetPinned};
+pub use ego::EgoFocus;
+pub use filter::{AttributeFilter, Comparison, FilteredOut, NodeFilter};
+pub use forces::{Cooling, CoolingSchedule, DeltaV, GravityCenter, Iterations, QuadTree};
+pub use graph::{
+    Attributes, Charge, Edge, EdgeCompliance, EdgeDirection, EdgeLabel, EdgeLength, Graph,
+    GraphNode, LabelPriority, NodeId, NodeLabel, NodeRadius, Pinned,
+};
+pub use headless::{Headless, HeadlessLayoutPlugin};
+pub use hidden::Hidden;
+pub use history::{History, Redo, Undo};
+#[cfg(feature = "petgraph")]
+pub use interop::PetgraphWeight;
+#[cfg(feature = "websocket")]
+pub use io::stream::{GraphStream, StreamMessage};
+pub use io::{
+    GraphIoError, GraphSnapshot, LoadGraph, LoadProgress, LoadStage, SaveGraph, SpawnGraph,
+    layout::{ExportLayout, ImportLayout, LayoutFile},
+    scene::{LoadScene, SaveScene, SceneFile},
+    svg::ExportSvg,
+    watch::GraphWatcher,
+};
+#[cfg(feature = "layout-3d")]
+pub use layout::three_d::{Layout3d, OrbitCamera};
+pub use layout::{
+    circular::{CircularLayout, RunCircularLayout},
+    control::LayoutControl,
+    force_atlas2::ForceAtlas2,
+    fruchterman_reingold::FruchtermanReingold,
+    kamada_kawai::KamadaKawai,
+    radial::RadialLayout,
+    registry::{FnLayout, LayoutAlgorithm, LayoutConverged, LayoutRegistry, OneShotLayout},
+    spectral::{Placement, spectral_layout},
+    stress::{RunStressMajorization, StressMajorization},
+    sugiyama::Sugiyama,
+    tree::TreeLayout,
+};
+pub use render::{
+    bundling::EdgeBundling,
+    colors::{ColorMapping, Colormap},
+    culling::ViewCulling,
+    emphasis::{Dimmed, Highlighted},
+    hulls::{CommunityHull, CommunityHulls, NodeGroup},
+    lod::LevelOfDetail,
+    shapes::{NodeShape, ShapeMapping},
+    sizes::{NodeSizing, SizeBy},
+    theme::Theme,
+    transitions::Transitions,
+    weights::EdgeWeightStyle,
+};
+pub use sample::{GraphSample, SampleMethod};
+pub use screenshot::TakeScreenshot;
+pub use search::NodeSearch;
+pub use selection::{Selected, Selection};
+pub use spatial::SpatialGrid;
+pub use spec::{EdgeSpec, GraphSpec, NodeSpec};
+pub use stats::GraphStats;
+pub use timeline::{Lifetime, Timeline};
+#[cfg(feature = "egui")]
+pub use ui::GraphUiPlugin;
+
+/// Force-directed layout for every [`GraphNode`] in the world.
+///
+/// Adds avian physics, mesh picking and cursor tracking unless the app already
+/// has them, so embedding apps only need a camera, a [`GraphSpec`] and,
+/// optionally, their own [`Config`] inserted before this plugin. Nodes and
+/// edges are picked through the [`SpatialGrid`] rather than by mesh, so
+/// mesh picking is added opt-in: only cameras with a `MeshPickingCamera` and
+/// meshes with a `Pickable` take part.
+pub struct GraphLayoutPlugin;
+
+impl Plugin for GraphLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PhysicsSchedulePlugin>() {
+            app.add_plugins(PhysicsPlugins::default());
+        }
+        if !app.is_plugin_added::<MeshPickingPlugin>() {
+            app.add_plugins(MeshPickingPlugin)
+                .insert_resource(MeshPickingSettings {
+                    require_markers: true,
+                    ..default()
+                });
+        }
+        if !app.is_plugin_added::<TrackCursorPlugin>() {
+            app.add_plugins(TrackCursorPlugin);
+        }
+        simulation(app);
+        app.add_plugins((
+            camera::plugin,
+            collapse::plugin,
+            drag::plugin,
+            ego::plugin,
+            filter::plugin,
+            hidden::plugin,
+            navigation::plugin,
+            picking::plugin,
+            render::plugin,
+            sample::plugin,
+            screenshot::plugin,
+            search::plugin,
+            selection::plugin,
+            timeline::plugin,
+        ));
+    }
+}
+
+/// Everything but input handling and drawing, shared with
+/// [`HeadlessLayoutPlugin`].
+fn simulation(app: &mut App) {
+    app.add_plugins((
+        analysis::plugin,
+        edit::plugin,
+        graph::plugin,
+        history::plugin,
+        forces::plugin,
+        io::plugin,
+        layout::plugin,
+        spatial::plugin,
+        stats::plugin,
+    ))
+    .insert_resource(Gravity(Vec2::splat(0.)))
+    .init_resource::<Config>()
+    .init_resource::<LayoutRng>()
+    .init_resource::<GraphSpec>()
+    .add_systems(Startup, setup::setup)
+    .add_systems(
+        Update,
+        config::reseed.run_if(resource_changed::<config::Config>),
+    );
+}
@@ -1,184 +1,256 @@
-use avian2d::{math::PI, prelude::*};
-use bevy::prelude::*;
-use bevy_cursor::{CursorLocation, TrackCursorPlugin};
-use bevy_egui::EguiPlugin;
-use rand::Rng;
-fn main() {
-    App::new()
-        .add_plugins((
-            DefaultPlugins,
-            PhysicsPlugins::default(),
-            PhysicsDebugPlugin::default(),
-            MeshPickingPlugin,
-            TrackCursorPlugin,
-        ))
-        .insert_gizmo_config(
-            PhysicsGizmos {
-                joint_separation_color: Some(Color::WHITE),
-                collider_color: None,
-                ..default()
-            },
-            GizmoConfig::default(),
-        )
-        .add_plugins(EguiPlugin::default())
-        .insert_resource(Gravity(Vec2::splat(0.)))
-        .insert_resource(Iterations(0))
-        .insert_resource(Config::default())
-        .add_systems(Startup, setup)
-        .add_systems(Update, update) //.run_if(below_cutoff))
-        .add_systems(PostUpdate, process_delta_v) //.run_if(below_cutoff))
-        .add_event::<DeltaV>()
-        .run();
-}
+use avian2d::prelude::*;
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_graph::{
+    Config, GraphIoError, GraphLayoutPlugin, GraphSpec, GraphUiPlugin, GraphWatcher,
+    HeadlessLayoutPlugin, LayoutControl, LayoutRegistry, LoadGraph, io,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Frames an `--out` run may take to converge unless `--iterations` says
+/// otherwise.
+const HEADLESS_ITERATIONS: usize = 5000;
+
+const USAGE: &str = "\
+usage: bevy-graph [GRAPH] [OPTIONS]
 
-const IDEAL_LENGTH: f32 = 50.;
-const COOLING_FACTOR: f32 = 0.2;
-const NODE_TOTAL: usize = 50;
-const NODE_MASS: f32 = 5.;
-const COMPLIANCE: f32 = 0.001;
-const COLLIDER_RADIUS: f32 = 49.;
+Lays out GRAPH, or a random demo graph, in an interactive window.
 
-#[derive(Resource)]
-struct Config {
-    ideal_length: f32,
-    cooling_factor: f32,
-    node_mass: f32,
-    compliance: f32,
-    node_total: usize,
-    collider_radius: f32,
+options:
+  --layout NAME       algorithm to run, e.g. physics, fa2, fr, kk, stress, tree
+  --seed N            seed for the random initial placement
+  --out FILE          lay out without a window and write the positions to FILE
+                      (layout JSON, or the whole graph as .gexf / .dot)
+  --iterations N      frames an --out run may take to converge (default 5000)
+  --open              after an --out run, show the result in the window
+  --watch             reload GRAPH whenever it changes on disk
+  -h, --help          print this help";
+
+/// Command-line options, see [`USAGE`].
+#[derive(Default)]
+struct Cli {
+    graph: Option<String>,
+    layout: Option<String>,
+    seed: Option<u64>,
+    out: Option<PathBuf>,
+    iterations: Option<usize>,
+    open: bool,
+    watch: bool,
+    help: bool,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            ideal_length: IDEAL_LENGTH,
-            cooling_factor: COOLING_FACTOR,
-            node_mass: NODE_MASS,
-            compliance: COMPLIANCE,
-            node_total: NODE_TOTAL,
-            collider_radius: COLLIDER_RADIUS,
+impl Cli {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--layout" => cli.layout = Some(value()?),
+                "--seed" => cli.seed = Some(parse_number(&arg, value()?)?),
+                "--out" => cli.out = Some(value()?.into()),
+                "--iterations" => cli.iterations = Some(parse_number(&arg, value()?)?),
+                "--open" => cli.open = true,
+                "--watch" => cli.watch = true,
+                "-h" | "--help" => cli.help = true,
+                _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+                _ if cli.graph.is_some() => return Err(format!("unexpected argument {arg}")),
+                _ => cli.graph = Some(arg),
+            }
         }
+        Ok(cli)
     }
 }
 
-#[derive(Event)]
-struct DeltaV(Entity, Vec2);
+fn parse_number<T: std::str::FromStr>(option: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{option} expects a number, got {value}"))
+}
 
-#[derive(Resource)]
-struct Iterations(usize);
+/// Finds the registered algorithm `name` refers to, ignoring case and
+/// punctuation and accepting a prefix or the usual abbreviations.
+fn layout_name(registry: &LayoutRegistry, name: &str) -> Option<String> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let wanted = match normalize(name).as_str() {
+        "fa2" => "forceatlas2".to_string(),
+        "fr" => "fruchtermanreingold".to_string(),
+        "kk" => "kamadakawai".to_string(),
+        other => other.to_string(),
+    };
+    let names = || registry.names().map(|n| (n, normalize(n)));
+    names()
+        .find(|(_, n)| *n == wanted)
+        .or_else(|| names().find(|(_, n)| n.starts_with(&wanted)))
+        .map(|(n, _)| n.to_string())
+}
 
-#[derive(Component)]
-struct Node;
+/// Makes `--layout` the active algorithm.
+fn select_layout(app: &mut App, layout: Option<&str>) -> Result<(), String> {
+    let Some(layout) = layout else {
+        return Ok(());
+    };
+    let mut registry = app.world_mut().resource_mut::<LayoutRegistry>();
+    let Some(name) = layout_name(&registry, layout) else {
+        let names: Vec<_> = registry.names().collect();
+        return Err(format!(
+            "unknown layout {layout}, expected one of: {}",
+            names.join(", ")
+        ));
+    };
+    registry.select(&name);
+    Ok(())
+}
 
-fn setup(
-    mut cmd: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    config: Res<Config>,
-) {
-    let damping = 1. / config.cooling_factor;
-    cmd.spawn(Camera2d);
-    let mut ids = Vec::new();
-    let mut observer = Observer::new(move_on_drag);
-    for _ in 0..config.node_total {
-        let angle = rand::thread_rng().gen_range(-PI..=PI);
-        // get screen size, max space btwn
-        let distance = rand::thread_rng().gen_range(0. ..500.);
-        let pos = Vec2::from_angle(angle) * distance;
-        let id = cmd
-            .spawn((
-                Node,
-                Mesh2d(meshes.add(Circle::new(5.))),
-                MeshMaterial2d(materials.add(Color::hsl(1., 1., 1.))),
-                Transform::from_translation(Vec3::new(pos.x, pos.y, 0.)),
-                RigidBody::Dynamic,
-                Collider::circle(config.collider_radius),
-                Mass(config.node_mass),
-                Sensor,
-                LinearVelocity::default(),
-                LinearDamping(damping),
-                CollisionEventsEnabled,
+fn main() -> AppExit {
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) if cli.help => {
+            println!("{USAGE}");
+            return AppExit::Success;
+        }
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return AppExit::error();
+        }
+    };
+    let mut config = Config::default();
+    if let Some(seed) = cli.seed {
+        config.seed = seed;
+    }
+    // A window loads its graph file in the background, so big ones don't
+    // hold it up.
+    let progressive = cli.graph.as_ref().filter(|_| cli.out.is_none());
+    let initial = match progressive {
+        Some(_) => Ok(GraphSpec::default()),
+        None => initial_graph(cli.graph.as_deref(), &config),
+    };
+    let mut spec = match initial {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("{e}");
+            return AppExit::error();
+        }
+    };
+    if let Some(out) = &cli.out {
+        let mut app = App::new();
+        // Before the plugins, so the seed is in place for the first scatter.
+        app.insert_resource(config.clone())
+            .add_plugins((
+                MinimalPlugins,
+                LogPlugin::default(),
+                HeadlessLayoutPlugin {
+                    max_iterations: cli.iterations.unwrap_or(HEADLESS_ITERATIONS),
+                    output: out.clone(),
+                },
             ))
-            .id();
-        observer.watch_entity(id);
-        ids.push(id);
+            .insert_resource(spec.clone());
+        if let Err(e) = select_layout(&mut app, cli.layout.as_deref()) {
+            eprintln!("{e}");
+            return AppExit::error();
+        }
+        let exit = app.run();
+        if exit.is_error() || !cli.open {
+            return exit;
+        }
+        if let Err(e) = read_result(out, &mut spec) {
+            eprintln!("failed to read back {}: {e}", out.display());
+            return AppExit::error();
+        }
+    }
+    let mut defaults = DefaultPlugins.build();
+    if cli.out.is_some() {
+        // The headless run already set up logging.
+        defaults = defaults.disable::<LogPlugin>();
+    }
+    let mut app = App::new();
+    app.insert_resource(config).add_plugins((
+        defaults,
+        PhysicsPlugins::default(),
+        PhysicsDebugPlugin::default(),
+        GraphLayoutPlugin,
+        GraphUiPlugin,
+    ));
+    if let Err(e) = select_layout(&mut app, cli.layout.as_deref()) {
+        eprintln!("{e}");
+        return AppExit::error();
     }
-    cmd.spawn(observer);
-    let mut ids_iter = ids.iter();
-    while let Some(x) = ids_iter.next() {
-        let Some(n1) = ids_iter.next() else {
-            return;
-        };
-        let Some(n2) = ids_iter.next() else {
-            return;
-        };
-        cmd.spawn(
-            DistanceJoint::new(*x, *n1)
-                .with_rest_length(config.ideal_length)
-                .with_compliance(config.compliance),
-        );
-        cmd.spawn(
-            DistanceJoint::new(*x, *n2)
-                .with_rest_length(config.ideal_length)
-                .with_compliance(config.compliance),
-        );
+    if cli.watch
+        && let Some(graph) = &cli.graph
+    {
+        app.insert_resource(GraphWatcher::new(graph));
     }
+    if cli.out.is_some() {
+        // Show the result as computed; `Space` carries on from there.
+        let mut control = LayoutControl::default();
+        control.pause();
+        app.insert_resource(control);
+    }
+    if let Some(graph) = progressive {
+        app.world_mut().send_event(LoadGraph(graph.into()));
+    }
+    app.insert_gizmo_config(
+        PhysicsGizmos {
+            joint_separation_color: Some(Color::WHITE),
+            collider_color: None,
+            ..default()
+        },
+        GizmoConfig::default(),
+    )
+    .insert_resource(spec)
+    .add_systems(Startup, spawn_camera)
+    .run()
 }
 
-fn update(
-    mut ev_w: EventWriter<DeltaV>,
-    mut coll_reader: EventReader<CollisionStarted>,
-    query: Query<&Transform>,
-    mut i: ResMut<Iterations>,
-) {
-    i.0 += 1;
-    for &CollisionStarted(a_id, b_id) in coll_reader.read() {
-        dbg!(&a_id, &b_id);
-        let a = query.get(a_id).expect("entity A to exist").translation.xy();
-        let b = query.get(b_id).expect("entity B to exist").translation.xy();
-        let (rep_a, rep_b) = repulsive_force(a, b);
-        ev_w.write_batch([DeltaV(a_id, rep_a), DeltaV(b_id, rep_b)]);
-    }
+fn spawn_camera(mut cmd: Commands) {
+    cmd.spawn(Camera2d);
 }
 
-fn process_delta_v(
-    mut ev_r: EventReader<DeltaV>,
-    mut query: Query<&mut LinearVelocity>,
-    mut i: ResMut<Iterations>,
-) {
-    i.0 += 1;
-    for DeltaV(id, dv) in ev_r.read() {
-        let mut v = query.get_mut(*id).unwrap();
-        v.0 += dv;
-    }
+fn initial_graph(path: Option<&str>, config: &Config) -> Result<GraphSpec, String> {
+    let Some(path) = path else {
+        return Ok(demo_graph(config.node_total, config.seed));
+    };
+    GraphSpec::load(path).map_err(|e| format!("failed to load {path}: {e}"))
 }
 
-fn repulsive_force(a: Vec2, b: Vec2) -> (Vec2, Vec2) {
-    if a == b {
-        let force = IDEAL_LENGTH.powi(2);
-        let angle = rand::thread_rng().gen_range(-PI..=PI);
-        return (
-            Vec2::from_angle(angle) * force,
-            Vec2::from_angle(angle + PI) * force,
-        );
+/// Positions an `--out` run wrote, moved onto `spec`'s nodes.
+fn read_result(out: &Path, spec: &mut GraphSpec) -> Result<(), GraphIoError> {
+    if !out
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+    {
+        *spec = GraphSpec::load(out)?;
+        return Ok(());
     }
-    let diff = a - b;
-    let angle = diff.to_angle();
-    let force = IDEAL_LENGTH.powi(2) / diff.length();
-    (
-        Vec2::from_angle(angle) * force,
-        Vec2::from_angle(angle + PI) * force,
-    )
+    let positions = io::layout::read(&fs::read_to_string(out)?)?;
+    for node in &mut spec.nodes {
+        node.position = positions.get(&node.id).copied().or(node.position);
+    }
+    Ok(())
 }
 
-fn move_on_drag(
-    trigger: Trigger<Pointer<Drag>>,
-    mut transforms: Query<&mut Transform>,
-    cursor: Res<CursorLocation>,
-) {
-    let mut transform = transforms.get_mut(trigger.target).unwrap();
-    let Some(pos) = cursor.world_position() else {
-        return;
-    };
-    transform.translation = Vec3::new(pos.x, pos.y, 0.);
+/// Random tree with a few extra cross links.
+fn demo_graph(n: usize, seed: u64) -> GraphSpec {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut spec = GraphSpec::new();
+    for i in 0..n {
+        spec = spec.node(i.to_string());
+        if i > 0 {
+            spec = spec.edge(rng.gen_range(0..i).to_string(), i.to_string());
+        }
+    }
+    for _ in 0..n / 10 {
+        let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        if a != b {
+            spec = spec.edge(a.to_string(), b.to_string());
+        }
+    }
+    spec
 }
@@ -0,0 +1,178 @@
+use std::f32::consts::FRAC_PI_3;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    camera::FocusNode,
+    graph::{Graph, GraphNode},
+    selection::{Selected, Selection},
+};
+
+/// Widest angle off an arrow key's direction a node may lie at to be stepped
+/// to.
+const ARROW_CONE: f32 = FRAC_PI_3;
+
+/// Node whose neighbours `Tab` is cycling through, and the neighbour it
+/// selected last.
+#[derive(Resource)]
+struct TabCycle {
+    hub: Entity,
+    current: Entity,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Update, (step_with_arrows, cycle_neighbors, focus_on_enter));
+}
+
+/// What the navigation keys read to find their way around.
+#[derive(SystemParam)]
+struct Navigator<'w, 's> {
+    selection: Res<'w, Selection>,
+    selected: Query<'w, 's, Entity, With<Selected>>,
+    nodes: Query<'w, 's, (Entity, &'static Transform, &'static Visibility), With<GraphNode>>,
+    cameras: Query<'w, 's, &'static Transform, (With<Camera2d>, Without<GraphNode>)>,
+    graph: Res<'w, Graph>,
+}
+
+impl Navigator<'_, '_> {
+    /// Position of `node` if it can be navigated to.
+    fn position(&self, node: Entity) -> Option<Vec2> {
+        self.nodes
+            .get(node)
+            .ok()
+            .filter(|(.., visibility)| **visibility != Visibility::Hidden)
+            .map(|(_, transform, _)| transform.translation.xy())
+    }
+
+    /// The last selected node, or with nothing selected the node nearest the
+    /// centre of the view.
+    fn current(&self) -> Option<Entity> {
+        if let Some(node) = self
+            .selection
+            .last()
+            .filter(|&n| self.position(n).is_some())
+        {
+            return Some(node);
+        }
+        let centre = self.cameras.single().ok()?.translation.xy();
+        self.nodes
+            .iter()
+            .filter(|(.., visibility)| **visibility != Visibility::Hidden)
+            .min_by(|a, b| {
+                let distance = |t: &Transform| t.translation.xy().distance_squared(centre);
+                distance(a.1).total_cmp(&distance(b.1))
+            })
+            .map(|(id, ..)| id)
+    }
+
+    fn select_only(&self, cmd: &mut Commands, node: Entity) {
+        for id in self.selected.iter().filter(|&id| id != node) {
+            cmd.entity(id).remove::<Selected>();
+        }
+        cmd.entity(node).insert(Selected);
+    }
+}
+
+/// Arrow keys select the nearest neighbour lying that way, or failing that
+/// the nearest node of all. With nothing selected they start at the node
+/// nearest the centre of the view.
+fn step_with_arrows(mut cmd: Commands, keys: Res<ButtonInput<KeyCode>>, nav: Navigator) {
+    let Some(dir) = [
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+    ]
+    .into_iter()
+    .find_map(|(key, dir)| keys.just_pressed(key).then_some(dir)) else {
+        return;
+    };
+    let Some(current) = nav.current() else {
+        return;
+    };
+    if !nav.selection.contains(current) {
+        nav.select_only(&mut cmd, current);
+        return;
+    }
+    let Some(from) = nav.position(current) else {
+        return;
+    };
+    // Distance, stretched the further the node lies off the key's direction.
+    let best = |candidates: &mut dyn Iterator<Item = Entity>| {
+        candidates
+            .filter(|&n| n != current)
+            .filter_map(|n| {
+                let offset = nav.position(n)? - from;
+                let angle = offset.angle_to(dir).abs();
+                (offset != Vec2::ZERO && angle <= ARROW_CONE)
+                    .then(|| (n, offset.length() * (1. + angle)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+    };
+    let next = best(&mut nav.graph.neighbors(current).map(|(n, _)| n))
+        .or_else(|| best(&mut nav.nodes.iter().map(|(id, ..)| id)));
+    if let Some(next) = next {
+        nav.select_only(&mut cmd, next);
+    }
+}
+
+/// `Tab` walks around the selected node's neighbours counterclockwise and
+/// `Shift+Tab` clockwise, coming back to the same neighbours for as long as
+/// the selection isn't moved some other way.
+fn cycle_neighbors(
+    mut cmd: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    nav: Navigator,
+    cycle: Option<Res<TabCycle>>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let Some(current) = nav.current() else {
+        return;
+    };
+    if !nav.selection.contains(current) {
+        nav.select_only(&mut cmd, current);
+        return;
+    }
+    let hub = cycle
+        .filter(|c| c.current == current && nav.position(c.hub).is_some())
+        .map_or(current, |c| c.hub);
+    let Some(centre) = nav.position(hub) else {
+        return;
+    };
+    let mut around: Vec<(Entity, f32)> = nav
+        .graph
+        .neighbors(hub)
+        .filter_map(|(n, _)| Some((n, (nav.position(n)? - centre).to_angle())))
+        .collect();
+    around.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    around.dedup_by_key(|(n, _)| *n);
+    if around.is_empty() {
+        return;
+    }
+    let back = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let next = match around.iter().position(|&(n, _)| n == current) {
+        Some(i) if back => (i + around.len() - 1) % around.len(),
+        Some(i) => (i + 1) % around.len(),
+        None if back => around.len() - 1,
+        None => 0,
+    };
+    let next = around[next].0;
+    nav.select_only(&mut cmd, next);
+    cmd.insert_resource(TabCycle { hub, current: next });
+}
+
+/// `Enter` flies the camera to the last selected node.
+fn focus_on_enter(
+    keys: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    mut ev_w: EventWriter<FocusNode>,
+) {
+    if keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        && let Some(node) = selection.last()
+    {
+        ev_w.write(FocusNode(node));
+    }
+}
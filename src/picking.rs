@@ -0,0 +1,148 @@
+use bevy::{picking::backend::prelude::*, prelude::*};
+
+use crate::{
+    graph::{Edge, GraphNode, NodeRadius},
+    render::{
+        culling::InView,
+        edges::{EDGE_Z, EdgePath, EdgeStroke},
+    },
+    spatial::{SpatialGrid, update_grid},
+};
+
+/// Pixels either side of an edge's line that still count as on it.
+const EDGE_SLOP: f32 = 4.;
+
+/// Nodes smaller than this many pixels across pick as if they were this big,
+/// so they can still be hit when zoomed out.
+const NODE_MIN_PIXELS: f32 = 6.;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        PreUpdate,
+        (pick_nodes.after(update_grid), pick_edges).in_set(PickSet::Backend),
+    );
+}
+
+type ActiveCamera<'w, 's> =
+    Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform), With<Camera2d>>;
+
+type Pointers<'w, 's> = Query<'w, 's, (&'static PointerId, &'static PointerLocation)>;
+
+/// Where each pointer is in the world, seen by the active 2D camera, and how
+/// wide a pixel is there.
+fn pointers_in_world<'a>(
+    camera: &'a Camera,
+    transform: &'a GlobalTransform,
+    pointers: &'a Pointers,
+) -> impl Iterator<Item = (PointerId, Vec3, f32)> + 'a {
+    pointers.iter().filter_map(|(&pointer, location)| {
+        let position = location.location.as_ref()?.position;
+        let ray = camera.viewport_to_world(transform, position).ok()?;
+        let beside = camera
+            .viewport_to_world_2d(transform, position + Vec2::X)
+            .ok()?;
+        Some((pointer, ray.origin, ray.origin.xy().distance(beside)))
+    })
+}
+
+/// Picking backend for nodes, which looks them up in the [`SpatialGrid`]
+/// instead of ray casting against every node's mesh: reports the node under
+/// each pointer, the one whose centre is closest where nodes overlap.
+fn pick_nodes(
+    pointers: Pointers,
+    cameras: ActiveCamera,
+    grid: Res<SpatialGrid>,
+    nodes: Query<
+        (
+            &NodeRadius,
+            &GlobalTransform,
+            &ViewVisibility,
+            Option<&Pickable>,
+        ),
+        With<GraphNode>,
+    >,
+    mut output: EventWriter<PointerHits>,
+) {
+    let Some((camera_id, camera, transform)) = cameras.iter().find(|(_, c, _)| c.is_active) else {
+        return;
+    };
+    for (pointer, origin, pixel) in pointers_in_world(camera, transform, &pointers) {
+        let point = origin.xy();
+        let min_radius = NODE_MIN_PIXELS / 2. * pixel;
+        let nearest = grid
+            .within(point, grid.max_radius().max(min_radius))
+            .filter_map(|(id, center)| {
+                let (radius, node, visibility, pickable) = nodes.get(id).ok()?;
+                let distance = center.distance(point);
+                (visibility.get()
+                    && pickable.is_none_or(|p| p.is_hoverable)
+                    && distance <= radius.0.max(min_radius))
+                .then_some((id, distance, node.translation().z))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((node, _, z)) = nearest else {
+            continue;
+        };
+        let hit = HitData::new(camera_id, origin.z - z, Some(point.extend(z)), None);
+        output.write(PointerHits::new(
+            pointer,
+            vec![(node, hit)],
+            camera.order as f32,
+        ));
+    }
+}
+
+/// Picking backend for edges, whose lines are drawn in shared batches that
+/// mesh picking can't tell apart: reports the edge nearest each pointer, if
+/// the pointer is on its line, behind the nodes.
+fn pick_edges(
+    pointers: Pointers,
+    cameras: ActiveCamera,
+    edges: Query<(Entity, &EdgePath, &EdgeStroke, &Visibility), InView<Edge>>,
+    mut output: EventWriter<PointerHits>,
+) {
+    let Some((camera_id, camera, transform)) = cameras.iter().find(|(_, c, _)| c.is_active) else {
+        return;
+    };
+    for (pointer, origin, pixel) in pointers_in_world(camera, transform, &pointers) {
+        let point = origin.xy();
+        let nearest = edges
+            .iter()
+            .filter(|(.., visibility)| **visibility != Visibility::Hidden)
+            .filter_map(|(id, path, stroke, _)| {
+                let distance = distance_to(&path.0, point)?;
+                (distance <= stroke.width / 2. + EDGE_SLOP * pixel).then_some((id, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((edge, _)) = nearest else {
+            continue;
+        };
+        let hit = HitData::new(
+            camera_id,
+            origin.z - EDGE_Z,
+            Some(point.extend(EDGE_Z)),
+            None,
+        );
+        output.write(PointerHits::new(
+            pointer,
+            vec![(edge, hit)],
+            camera.order as f32,
+        ));
+    }
+}
+
+/// Distance from `point` to the polyline `path`.
+fn distance_to(path: &[Vec2], point: Vec2) -> Option<f32> {
+    path.windows(2)
+        .map(|w| {
+            let segment = w[1] - w[0];
+            let length = segment.length_squared();
+            let t = if length > 0. {
+                ((point - w[0]).dot(segment) / length).clamp(0., 1.)
+            } else {
+                0.
+            };
+            point.distance(w[0] + segment * t)
+        })
+        .reduce(f32::min)
+}
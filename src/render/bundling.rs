@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use super::edges::{EdgePath, EdgeSystems};
+
+/// Settings for force-directed edge bundling (Holten & van Wijk, 2009), a
+/// post-process over the rendered edge paths. Every edge is subdivided and its
+/// points are attracted by the matching points of compatible edges, those of
+/// similar length, angle and position, so that edges running the same way
+/// merge into bundles. Node positions are not affected.
+///
+/// The pass costs O(E²) per frame, so it is meant for moderately sized
+/// graphs and is off by default.
+#[derive(Resource, Clone, Debug)]
+pub struct EdgeBundling {
+    /// Blend between the unbundled paths at `0` and fully bundled ones at
+    /// `1`. `0` skips the pass entirely.
+    pub strength: f32,
+    /// Subdivision cycles; each one doubles the points per edge.
+    pub cycles: usize,
+    /// Iterations in the first cycle, shrinking by a third every cycle.
+    pub iterations: usize,
+    /// Spring constant keeping each edge's subdivision points together.
+    pub stiffness: f32,
+    /// Edges less compatible than this don't attract each other.
+    pub compatibility: f32,
+}
+
+impl Default for EdgeBundling {
+    fn default() -> Self {
+        Self {
+            strength: 0.,
+            cycles: 5,
+            iterations: 40,
+            stiffness: 0.1,
+            compatibility: 0.6,
+        }
+    }
+}
+
+/// Distance each point may move per iteration in the first cycle; halved
+/// every cycle.
+const INITIAL_STEP: f32 = 0.1;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<EdgeBundling>().add_systems(
+        PostUpdate,
+        bundle
+            .after(EdgeSystems::Trace)
+            .before(EdgeSystems::Draw)
+            .run_if(|settings: Res<EdgeBundling>| settings.strength > 0.),
+    );
+}
+
+struct Segment {
+    from: Vec2,
+    to: Vec2,
+}
+
+impl Segment {
+    fn vector(&self) -> Vec2 {
+        self.to - self.from
+    }
+
+    fn midpoint(&self) -> Vec2 {
+        (self.from + self.to) / 2.
+    }
+
+    fn project(&self, p: Vec2) -> Vec2 {
+        let v = self.vector();
+        self.from + v * (p - self.from).dot(v) / v.length_squared()
+    }
+
+    /// How much `other` sits alongside this segment rather than off its end.
+    fn visibility(&self, other: &Segment) -> f32 {
+        let (i0, i1) = (self.project(other.from), self.project(other.to));
+        let span = i0.distance(i1);
+        if span == 0. {
+            return 0.;
+        }
+        let offset = self.midpoint().distance((i0 + i1) / 2.);
+        (1. - 2. * offset / span).max(0.)
+    }
+
+    /// Product of the angle, scale, position and visibility compatibilities.
+    fn compatibility(&self, other: &Segment) -> f32 {
+        let (p, q) = (self.vector(), other.vector());
+        let (lp, lq) = (p.length(), q.length());
+        let avg = (lp + lq) / 2.;
+        let angle = (p.dot(q) / (lp * lq)).abs();
+        let scale = 2. / (avg / lp.min(lq) + lp.max(lq) / avg);
+        let position = avg / (avg + self.midpoint().distance(other.midpoint()));
+        let visibility = self.visibility(other).min(other.visibility(self));
+        angle * scale * position * visibility
+    }
+}
+
+fn bundle(mut edges: Query<&mut EdgePath>, settings: Res<EdgeBundling>) {
+    let mut paths: Vec<_> = edges
+        .iter_mut()
+        .filter(|p| p.0.len() >= 2 && p.0[0] != p.0[p.0.len() - 1])
+        .collect();
+    let segments: Vec<_> = paths
+        .iter()
+        .map(|p| Segment {
+            from: p.0[0],
+            to: p.0[p.0.len() - 1],
+        })
+        .collect();
+
+    // Compatible pairs only depend on the endpoints, so find them once. Edges
+    // running the opposite way are matched up point for point in reverse.
+    let mut compatible = vec![Vec::new(); segments.len()];
+    for i in 0..segments.len() {
+        for j in i + 1..segments.len() {
+            let (a, b) = (&segments[i], &segments[j]);
+            if a.compatibility(b) >= settings.compatibility {
+                let reversed = a.vector().dot(b.vector()) < 0.;
+                compatible[i].push((j, reversed));
+                compatible[j].push((i, reversed));
+            }
+        }
+    }
+
+    let mut points: Vec<Vec<Vec2>> = segments.iter().map(|s| vec![s.from, s.to]).collect();
+    let mut step = INITIAL_STEP;
+    let mut iterations = settings.iterations as f32;
+    for _ in 0..settings.cycles {
+        for edge in &mut points {
+            subdivide(edge);
+        }
+        for _ in 0..iterations.round() as usize {
+            let forces: Vec<Vec<Vec2>> = (0..points.len())
+                .map(|e| edge_forces(&points, e, &compatible[e], &segments[e], settings.stiffness))
+                .collect();
+            for (edge, force) in points.iter_mut().zip(forces) {
+                for (p, f) in edge.iter_mut().zip(force) {
+                    *p += f * step;
+                }
+            }
+        }
+        step /= 2.;
+        iterations *= 2. / 3.;
+    }
+
+    for ((path, bundled), segment) in paths.iter_mut().zip(points).zip(&segments) {
+        let n = bundled.len() - 1;
+        path.0 = bundled
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let straight = segment.from.lerp(segment.to, i as f32 / n as f32);
+                straight.lerp(p, settings.strength)
+            })
+            .collect();
+    }
+}
+
+/// Spring pull from the neighbouring points plus attraction to the matching
+/// point of every compatible edge. Endpoints stay put.
+fn edge_forces(
+    points: &[Vec<Vec2>],
+    e: usize,
+    compatible: &[(usize, bool)],
+    segment: &Segment,
+    stiffness: f32,
+) -> Vec<Vec2> {
+    let edge = &points[e];
+    let n = edge.len();
+    let k = stiffness / (segment.vector().length() * (n - 1) as f32);
+    let mut forces = vec![Vec2::ZERO; n];
+    for i in 1..n - 1 {
+        let p = edge[i];
+        let mut force = k * (edge[i - 1] - p + edge[i + 1] - p);
+        for &(other, reversed) in compatible {
+            let matching = if reversed { n - 1 - i } else { i };
+            let offset = points[other][matching] - p;
+            let dist = offset.length();
+            if dist > f32::EPSILON {
+                force += offset / dist;
+            }
+        }
+        forces[i] = force;
+    }
+    forces
+}
+
+/// Inserts a point halfway along every segment of the polyline.
+fn subdivide(edge: &mut Vec<Vec2>) {
+    let mut refined = Vec::with_capacity(edge.len() * 2 - 1);
+    for pair in edge.windows(2) {
+        refined.push(pair[0]);
+        refined.push((pair[0] + pair[1]) / 2.);
+    }
+    refined.extend(edge.last());
+    *edge = refined;
+}
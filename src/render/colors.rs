@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use super::{
+    emphasis::{DIMMED_ALPHA, Dimmed},
+    shared::NodeAssets,
+};
+use crate::graph::{Attributes, GraphNode};
+
+/// How nodes are colored. With `attribute` set, every node is colored by its
+/// value of that attribute: if every value is a number they are spread over
+/// `colormap` from the smallest to the largest, otherwise, or if `categorical`
+/// is set, each distinct value takes the next `palette` entry in sorted order.
+/// Without it, nodes use their own `color` attribute. Nodes without a usable
+/// value get `default_color`.
+#[derive(Resource, Clone, Debug)]
+pub struct ColorMapping {
+    pub attribute: Option<String>,
+    /// Treat numbers as categories too, such as community IDs.
+    pub categorical: bool,
+    /// Colors for categorical values, reused from the start when there are
+    /// more categories than colors.
+    pub palette: Vec<Color>,
+    pub colormap: Colormap,
+    pub default_color: Color,
+}
+
+impl Default for ColorMapping {
+    fn default() -> Self {
+        Self {
+            attribute: None,
+            categorical: false,
+            palette: TABLEAU_10.iter().map(|&hex| Color::Srgba(hex)).collect(),
+            colormap: Colormap::Viridis,
+            default_color: Color::WHITE,
+        }
+    }
+}
+
+impl ColorMapping {
+    /// Colors by `attribute` with the default palette and colormap.
+    pub fn by(attribute: impl Into<String>) -> Self {
+        Self {
+            attribute: Some(attribute.into()),
+            ..default()
+        }
+    }
+}
+
+pub(crate) const TABLEAU_10: [Srgba; 10] = [
+    Srgba::rgb(0.306, 0.475, 0.655),
+    Srgba::rgb(0.949, 0.557, 0.169),
+    Srgba::rgb(0.882, 0.341, 0.349),
+    Srgba::rgb(0.463, 0.718, 0.698),
+    Srgba::rgb(0.349, 0.631, 0.310),
+    Srgba::rgb(0.929, 0.788, 0.282),
+    Srgba::rgb(0.690, 0.478, 0.631),
+    Srgba::rgb(1.000, 0.616, 0.655),
+    Srgba::rgb(0.612, 0.459, 0.373),
+    Srgba::rgb(0.729, 0.690, 0.675),
+];
+
+const VIRIDIS: [Srgba; 5] = [
+    Srgba::rgb(0.267, 0.005, 0.329),
+    Srgba::rgb(0.231, 0.322, 0.545),
+    Srgba::rgb(0.129, 0.569, 0.549),
+    Srgba::rgb(0.369, 0.788, 0.384),
+    Srgba::rgb(0.993, 0.906, 0.144),
+];
+const PLASMA: [Srgba; 5] = [
+    Srgba::rgb(0.050, 0.030, 0.528),
+    Srgba::rgb(0.494, 0.012, 0.658),
+    Srgba::rgb(0.798, 0.280, 0.470),
+    Srgba::rgb(0.973, 0.585, 0.252),
+    Srgba::rgb(0.940, 0.975, 0.131),
+];
+const DIVERGING: [Srgba; 3] = [
+    Srgba::rgb(0.230, 0.299, 0.754),
+    Srgba::rgb(0.865, 0.865, 0.865),
+    Srgba::rgb(0.706, 0.016, 0.150),
+];
+const GREYS: [Srgba; 2] = [Srgba::rgb(0.15, 0.15, 0.15), Srgba::rgb(0.95, 0.95, 0.95)];
+
+/// Continuous color scale for numeric attributes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Colormap {
+    /// Perceptually uniform dark blue to yellow.
+    #[default]
+    Viridis,
+    /// Perceptually uniform dark blue to orange to yellow.
+    Plasma,
+    /// Blue through white to red, for values around a midpoint.
+    Diverging,
+    Greys,
+}
+
+impl Colormap {
+    fn stops(self) -> &'static [Srgba] {
+        match self {
+            Self::Viridis => &VIRIDIS,
+            Self::Plasma => &PLASMA,
+            Self::Diverging => &DIVERGING,
+            Self::Greys => &GREYS,
+        }
+    }
+
+    /// Color at `t` in `[0, 1]`, interpolated between the map's stops.
+    pub fn sample(self, t: f32) -> Color {
+        let stops = self.stops();
+        let scaled = t.clamp(0., 1.) * (stops.len() - 1) as f32;
+        let i = (scaled as usize).min(stops.len() - 2);
+        Color::Srgba(stops[i].mix(&stops[i + 1], scaled - i as f32))
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ColorMapping>()
+        .add_systems(PostUpdate, apply_mapping);
+}
+
+/// Nodes that were just spawned or dimmed, or whose attributes changed.
+type NeedsColor = Or<(
+    Added<MeshMaterial2d<ColorMaterial>>,
+    Changed<Attributes>,
+    Added<Dimmed>,
+)>;
+
+/// A node's attributes, material and whether it's dimmed.
+type Colored = (
+    &'static Attributes,
+    &'static mut MeshMaterial2d<ColorMaterial>,
+    Has<Dimmed>,
+);
+
+/// Recolors every node whenever the mapping, a node's attributes or the set
+/// of nodes changes, since numeric ranges depend on all of them.
+pub(super) fn apply_mapping(
+    mut nodes: Query<Colored, With<GraphNode>>,
+    changed: Query<(), (With<GraphNode>, NeedsColor)>,
+    mut undimmed: RemovedComponents<Dimmed>,
+    mapping: Res<ColorMapping>,
+    mut assets: NodeAssets,
+) {
+    if !mapping.is_changed() && changed.is_empty() && undimmed.read().count() == 0 {
+        return;
+    }
+    let colors = node_colors(nodes.iter().map(|(attributes, ..)| attributes), &mapping);
+    for ((_, mut material, dimmed), color) in nodes.iter_mut().zip(colors) {
+        let color = if dimmed {
+            color.with_alpha(color.alpha() * DIMMED_ALPHA)
+        } else {
+            color
+        };
+        let shared = assets.material(color);
+        if material.0 != shared {
+            material.0 = shared;
+        }
+    }
+}
+
+/// Color of each node in `nodes` order.
+fn node_colors<'a>(
+    nodes: impl Iterator<Item = &'a Attributes> + Clone,
+    mapping: &ColorMapping,
+) -> Vec<Color> {
+    let Some(key) = &mapping.attribute else {
+        return nodes
+            .map(|a| {
+                a.get("color")
+                    .and_then(|c| Srgba::hex(c).ok())
+                    .map_or(mapping.default_color, Color::from)
+            })
+            .collect();
+    };
+    let values: Vec<_> = nodes.map(|a| a.get(key)).collect();
+    // Numeric only if every value that is present parses.
+    let numbers: Option<Vec<Option<f32>>> = values
+        .iter()
+        .map(|v| {
+            v.map(|v| v.trim().parse().ok())
+                .map_or(Some(None), |n| n.map(Some))
+        })
+        .collect();
+    if let Some(numbers) = numbers.filter(|n| !mapping.categorical && n.iter().any(Option::is_some))
+    {
+        let (min, max) = numbers
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        let range = (max - min).max(f32::EPSILON);
+        return numbers
+            .into_iter()
+            .map(|v| {
+                v.map_or(mapping.default_color, |v| {
+                    mapping.colormap.sample((v - min) / range)
+                })
+            })
+            .collect();
+    }
+    let mut categories: Vec<_> = values.iter().flatten().copied().collect();
+    categories.sort_unstable_by(|a, b| category_order(a, b));
+    categories.dedup();
+    values
+        .into_iter()
+        .map(|v| {
+            v.and_then(|v| categories.binary_search_by(|c| category_order(c, v)).ok())
+                .and_then(|i| {
+                    let n = mapping.palette.len();
+                    (n > 0).then(|| mapping.palette[i % n])
+                })
+                .unwrap_or(mapping.default_color)
+        })
+        .collect()
+}
+
+/// Numbers first in numeric order, so `2` comes before `10`, then everything
+/// else in lexical order.
+pub(super) fn category_order(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y).then(a.cmp(b)),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
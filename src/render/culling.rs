@@ -0,0 +1,98 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::edges::{EdgePath, EdgeSystems, LOOP_SIZE};
+use crate::graph::{Edge, GraphNode, NodeRadius};
+
+/// How far an edge is allowed to bend out of the box between its nodes, as a
+/// fraction of its length.
+const BEND_ALLOWANCE: f32 = 0.5;
+
+/// Skips work on what the camera can't see. Nodes and edges outside the
+/// view, grown by `margin` of its size on every side, are culled: their edge
+/// meshes aren't rebuilt, their labels aren't laid out and picking passes
+/// them by.
+#[derive(Resource, Clone, Debug)]
+pub struct ViewCulling {
+    pub enabled: bool,
+    pub margin: f32,
+}
+
+impl Default for ViewCulling {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            margin: 0.25,
+        }
+    }
+}
+
+/// Marks a node or edge outside the view.
+#[derive(Component)]
+pub(crate) struct Culled;
+
+/// `T`s in view.
+pub(crate) type InView<T> = (With<T>, Without<Culled>);
+
+/// Nodes and edges in view, plus those that just left it so they get one
+/// last update where they went.
+pub(crate) type OnScreen = Or<(Without<Culled>, Added<Culled>)>;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ViewCulling>().add_systems(
+        PostUpdate,
+        cull.after(PhysicsSet::Sync).before(EdgeSystems::Trace),
+    );
+}
+
+fn cull(
+    mut cmd: Commands,
+    culling: Res<ViewCulling>,
+    cameras: Query<(&Camera, &GlobalTransform, &Projection), With<Camera2d>>,
+    nodes: Query<(Entity, &Transform, &NodeRadius, Has<Culled>), With<GraphNode>>,
+    edges: Query<(Entity, &Edge, Has<Culled>), With<EdgePath>>,
+) {
+    let view = cameras
+        .iter()
+        .filter(|_| culling.enabled)
+        .find(|(camera, ..)| camera.is_active)
+        .and_then(|(_, transform, projection)| match projection {
+            Projection::Orthographic(ortho) => Some(Rect {
+                min: ortho.area.min + transform.translation().xy(),
+                max: ortho.area.max + transform.translation().xy(),
+            }),
+            _ => None,
+        })
+        .map(|view| view.inflate(view.half_size().max_element() * culling.margin));
+    let visible = |bounds: Rect| {
+        view.is_none_or(|view| bounds.min.cmple(view.max).all() && view.min.cmple(bounds.max).all())
+    };
+    let mut mark = |id: Entity, culled: bool, was_culled: bool| {
+        if culled && !was_culled {
+            cmd.entity(id).insert((Culled, Pickable::IGNORE));
+        } else if !culled && was_culled {
+            cmd.entity(id).remove::<(Culled, Pickable)>();
+        }
+    };
+
+    for (id, transform, radius, was_culled) in &nodes {
+        let bounds = Rect::from_center_half_size(transform.translation.xy(), Vec2::splat(radius.0));
+        mark(id, !visible(bounds), was_culled);
+    }
+    for (id, edge, was_culled) in &edges {
+        let (Ok((_, source, radius, _)), Ok((_, target, ..))) =
+            (nodes.get(edge.source), nodes.get(edge.target))
+        else {
+            continue;
+        };
+        let (from, to) = (source.translation.xy(), target.translation.xy());
+        // Self-loops reach out twice their circle's radius.
+        let reach = if edge.source == edge.target {
+            2. * LOOP_SIZE * radius.0
+        } else {
+            from.distance(to) * BEND_ALLOWANCE
+        };
+        let bounds = Rect::from_corners(from, to).inflate(reach);
+        mark(id, !visible(bounds), was_culled);
+    }
+}
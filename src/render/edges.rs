@@ -0,0 +1,489 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use avian2d::prelude::*;
+use bevy::{
+    asset::RenderAssetUsages,
+    ecs::entity_disabling::Disabled,
+    platform::collections::HashMap,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use super::{culling::OnScreen, lod::Subsampled, shared::NodeAssets};
+use crate::{
+    config::Config,
+    graph::{Edge, EdgeDirection, GraphNode, IncludeDisabled, NodeRadius},
+};
+
+/// Draw depth of edges, behind the nodes at `z = 0`.
+pub(crate) const EDGE_Z: f32 = -1.;
+
+/// Edges whose lines share a mesh. Small enough that a moving edge doesn't
+/// rebuild much, big enough to keep draw calls few.
+const BATCH_SIZE: usize = 2048;
+
+/// Points sampled along a curved edge.
+const CURVE_SEGMENTS: usize = 16;
+
+/// Extra bend, as a fraction of edge length, between neighbouring edges that
+/// join the same pair of nodes.
+const PARALLEL_SPACING: f32 = 0.25;
+
+/// Radius of a self-loop's circle as a multiple of its node's radius.
+pub(crate) const LOOP_SIZE: f32 = 1.;
+
+/// Points sampled around a self-loop.
+const LOOP_SEGMENTS: usize = 24;
+
+/// Radians between neighbouring self-loops on the same node.
+const LOOP_SPACING: f32 = 0.8;
+
+/// Arrowhead mesh shared by every edge, a unit-length triangle with its tip
+/// at the origin pointing along `+x`, and the plain white material batches
+/// of lines are drawn with, their colors being in their vertices.
+#[derive(Resource)]
+struct EdgeAssets {
+    arrow: Handle<Mesh>,
+    lines: Handle<ColorMaterial>,
+}
+
+impl FromWorld for EdgeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let arrow = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Triangle2d::new(ARROW[0], ARROW[1], ARROW[2]));
+        let lines = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::WHITE);
+        Self { arrow, lines }
+    }
+}
+
+/// The lines of every edge, drawn [`BATCH_SIZE`] at a time in one mesh each
+/// instead of one apiece. Only batches holding an edge that moved, restyled
+/// or came and went are rebuilt.
+#[derive(Resource, Default)]
+struct EdgeBatches {
+    batches: Vec<Batch>,
+    /// Batch each edge is drawn in.
+    slots: HashMap<Entity, usize>,
+}
+
+struct Batch {
+    mesh: Handle<Mesh>,
+    edges: Vec<Entity>,
+    dirty: bool,
+}
+
+/// World-space polyline an edge is drawn along, from its source node's centre
+/// to its target node's centre.
+#[derive(Component, Clone, Debug, Default)]
+pub(crate) struct EdgePath(pub Vec<Vec2>);
+
+/// Width of an edge's line and the color it and the arrowheads are drawn
+/// in. Restyled by [`EdgeWeightStyle`](super::weights::EdgeWeightStyle).
+#[derive(Component, Clone, Debug, PartialEq)]
+pub(crate) struct EdgeStroke {
+    pub width: f32,
+    pub color: Color,
+}
+
+/// Child entities drawing an edge's arrowheads, positioned in world space.
+/// Its line is drawn with the others in [`EdgeBatches`].
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum EdgePart {
+    /// Arrowhead touching the target node.
+    Head,
+    /// Arrowhead touching the source node.
+    Tail,
+}
+
+/// Stages of edge rendering, so post-processes can rewrite the paths between
+/// tracing and drawing.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum EdgeSystems {
+    Trace,
+    Draw,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<EdgeBatches>()
+        .add_systems(Startup, |mut cmd: Commands| {
+            cmd.init_resource::<EdgeAssets>()
+        })
+        .add_systems(
+            PostUpdate,
+            (
+                attach_parts,
+                assign_batches,
+                trace_paths.in_set(EdgeSystems::Trace),
+                (draw_paths, draw_batches).in_set(EdgeSystems::Draw),
+            )
+                .chain()
+                .after(PhysicsSet::Sync)
+                .before(TransformSystem::TransformPropagate),
+        );
+}
+
+/// Gives new edges their arrowheads, which are their only children.
+fn attach_parts(
+    mut cmd: Commands,
+    edges: Query<Entity, (With<Edge>, Without<Children>)>,
+    assets: Res<EdgeAssets>,
+    mut shared: NodeAssets,
+    config: Res<Config>,
+) {
+    for id in &edges {
+        let material = shared.material(config.edge_color);
+        cmd.entity(id)
+            .insert((
+                EdgePath::default(),
+                EdgeStroke {
+                    width: config.edge_width,
+                    color: config.edge_color,
+                },
+                Transform::from_xyz(0., 0., EDGE_Z),
+                Visibility::default(),
+            ))
+            .with_children(|parts| {
+                for part in [EdgePart::Head, EdgePart::Tail] {
+                    parts.spawn((
+                        part,
+                        Mesh2d(assets.arrow.clone()),
+                        MeshMaterial2d(material.clone()),
+                        Transform::default(),
+                        Visibility::Hidden,
+                    ));
+                }
+            });
+    }
+}
+
+/// Puts new edges in a batch with room, starting a new one when all are
+/// full, and takes removed ones out.
+fn assign_batches(
+    mut cmd: Commands,
+    mut batches: ResMut<EdgeBatches>,
+    added: Query<Entity, Added<EdgePath>>,
+    mut removed: RemovedComponents<EdgePath>,
+    assets: Res<EdgeAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let b = &mut *batches;
+    for id in removed.read() {
+        if let Some(i) = b.slots.remove(&id) {
+            b.batches[i].edges.retain(|&e| e != id);
+            b.batches[i].dirty = true;
+        }
+    }
+    for id in &added {
+        let i = match b.batches.iter().position(|b| b.edges.len() < BATCH_SIZE) {
+            Some(i) => i,
+            None => {
+                let mesh = meshes.add(Mesh::new(
+                    PrimitiveTopology::TriangleList,
+                    RenderAssetUsages::default(),
+                ));
+                cmd.spawn((
+                    Mesh2d(mesh.clone()),
+                    MeshMaterial2d(assets.lines.clone()),
+                    Transform::from_xyz(0., 0., EDGE_Z),
+                    Pickable::IGNORE,
+                ));
+                b.batches.push(Batch {
+                    mesh,
+                    edges: Vec::new(),
+                    dirty: true,
+                });
+                b.batches.len() - 1
+            }
+        };
+        b.batches[i].edges.push(id);
+        b.batches[i].dirty = true;
+        b.slots.insert(id, i);
+    }
+}
+
+/// Routes the edges on screen, see [`route_paths`].
+fn trace_paths(
+    mut edges: Query<(Entity, &Edge, &mut EdgePath), (Without<Subsampled>, OnScreen)>,
+    nodes: Query<(&Transform, &NodeRadius), With<GraphNode>>,
+    config: Res<Config>,
+) {
+    let routed: Vec<(Entity, Edge)> = edges.iter().map(|(id, edge, _)| (id, *edge)).collect();
+    let paths = route_paths(
+        &routed,
+        |id| {
+            let (transform, radius) = nodes.get(id).ok()?;
+            Some((transform.translation.xy(), radius.0))
+        },
+        config.edge_curvature,
+    );
+    for ((.., mut path), points) in edges.iter_mut().zip(paths) {
+        // Untouched while the edge stays put, so its batch isn't rebuilt.
+        if path.0 != points {
+            path.0 = points;
+        }
+    }
+}
+
+/// Routes every edge in `edges` as a quadratic Bézier between its nodes,
+/// found by `node` as their centre and radius, and every self-loop as a
+/// circle out of its node and back. Edges joining the same pair of nodes
+/// are fanned out to alternate sides, and loops on the same node around it,
+/// so none of them hide behind another. Edges whose nodes are missing get
+/// no points.
+pub(crate) fn route_paths(
+    edges: &[(Entity, Edge)],
+    node: impl Fn(Entity) -> Option<(Vec2, f32)>,
+    curvature: f32,
+) -> Vec<Vec<Vec2>> {
+    let mut parallel: HashMap<(Entity, Entity), Vec<Entity>> = HashMap::default();
+    for &(id, edge) in edges {
+        let pair = (edge.source.min(edge.target), edge.source.max(edge.target));
+        parallel.entry(pair).or_default().push(id);
+    }
+    let mut fan: HashMap<Entity, f32> = HashMap::default();
+    for group in parallel.values_mut() {
+        group.sort();
+        let middle = (group.len() - 1) as f32 / 2.;
+        for (i, &id) in group.iter().enumerate() {
+            fan.insert(id, (i as f32 - middle) * PARALLEL_SPACING);
+        }
+    }
+
+    edges
+        .iter()
+        .map(|&(id, edge)| {
+            let mut points = Vec::new();
+            let fan = fan.get(&id).copied().unwrap_or(0.);
+            if let (Some((from, radius)), Some((to, _))) = (node(edge.source), node(edge.target)) {
+                // Fan offsets are measured from the lower entity, so an edge
+                // running the other way bends to the opposite side of its own
+                // direction.
+                let side = if edge.source <= edge.target { 1. } else { -1. };
+                let bend = curvature + side * fan;
+                if edge.source == edge.target {
+                    let angle = fan / PARALLEL_SPACING * LOOP_SPACING;
+                    points.extend(self_loop(from, radius, angle));
+                } else if bend == 0. {
+                    points.extend([from, to]);
+                } else {
+                    let control = (from + to) / 2. + (to - from).perp() * bend;
+                    points.extend((0..=CURVE_SEGMENTS).map(|i| {
+                        let t = i as f32 / CURVE_SEGMENTS as f32;
+                        from.lerp(control, t).lerp(control.lerp(to, t), t)
+                    }));
+                }
+            }
+            points
+        })
+        .collect()
+}
+
+/// Circle [`LOOP_SIZE`] times the node's `radius` that passes through the
+/// node's `centre`, leaning `angle` from straight up, traced from the centre
+/// round to it again.
+fn self_loop(centre: Vec2, radius: f32, angle: f32) -> impl Iterator<Item = Vec2> {
+    let size = radius * LOOP_SIZE;
+    let out = Vec2::from_angle(FRAC_PI_2 + angle);
+    let middle = centre + out * size;
+    let start = (-out).to_angle();
+    (0..=LOOP_SEGMENTS).map(move |i| {
+        let turned = start + TAU * i as f32 / LOOP_SEGMENTS as f32;
+        middle + Vec2::from_angle(turned) * size
+    })
+}
+
+/// Edges that are drawn, which sampled-out and offscreen ones aren't.
+type DrawnEdges<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static EdgePath,
+        &'static EdgeStroke,
+        &'static Edge,
+        Option<&'static EdgeDirection>,
+        &'static Children,
+    ),
+    (Without<Subsampled>, OnScreen),
+>;
+
+type ArrowParts<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static EdgePart,
+        &'static mut MeshMaterial2d<ColorMaterial>,
+        &'static mut Transform,
+        &'static mut Visibility,
+    ),
+>;
+
+/// Puts the arrowheads where each edge's path crosses the node rims, in the
+/// edge's color.
+fn draw_paths(
+    edges: DrawnEdges,
+    mut parts: ArrowParts,
+    nodes: Query<&NodeRadius, With<GraphNode>>,
+    mut shared: NodeAssets,
+    config: Res<Config>,
+) {
+    for (path, style, edge, direction, children) in &edges {
+        let radius = |id| nodes.get(id).map_or(0., |r| r.0);
+        let [head, tail] = arrow_tips(
+            &path.0,
+            direction.copied(),
+            radius(edge.source),
+            radius(edge.target),
+        );
+        for &child in children {
+            let Ok((part, mut material, mut transform, mut visibility)) = parts.get_mut(child)
+            else {
+                continue;
+            };
+            let tip = match part {
+                EdgePart::Head => head,
+                EdgePart::Tail => tail,
+            };
+            let placed = tip.map(|(tip, dir)| arrowhead(tip, dir, config.arrow_size));
+            if let Some(placed) = placed {
+                *transform = placed;
+                let color = shared.material(style.color);
+                if material.0 != color {
+                    material.0 = color;
+                }
+            }
+            visibility.set_if_neq(if placed.is_some() {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+        }
+    }
+}
+
+/// Where the arrowheads of an edge drawn along `path` go, at its target then
+/// its source, as the point on each node's rim and the direction pointing
+/// into the node. Only the ends `direction` points to get one.
+pub(crate) fn arrow_tips(
+    path: &[Vec2],
+    direction: Option<EdgeDirection>,
+    source_radius: f32,
+    target_radius: f32,
+) -> [Option<(Vec2, Vec2)>; 2] {
+    [
+        direction
+            .filter(|d| d.points_to_target())
+            .and_then(|_| rim(path.iter().rev(), target_radius)),
+        direction
+            .filter(|d| d.points_to_source())
+            .and_then(|_| rim(path.iter(), source_radius)),
+    ]
+}
+
+/// Corners of the arrowhead mesh in [`EdgeAssets`], with its tip at the
+/// origin pointing along `+x`.
+pub(crate) const ARROW: [Vec2; 3] = [Vec2::ZERO, Vec2::new(-1., 0.35), Vec2::new(-1., -0.35)];
+
+fn arrowhead(tip: Vec2, dir: Vec2, size: f32) -> Transform {
+    Transform::from_translation(tip.extend(0.5))
+        .with_rotation(Quat::from_rotation_z(dir.to_angle()))
+        .with_scale(Vec3::splat(size))
+}
+
+/// Walks `path` from the node at its start outwards and returns where it
+/// leaves the node's circle, along with the direction pointing back into the
+/// node.
+fn rim<'a>(path: impl Iterator<Item = &'a Vec2>, radius: f32) -> Option<(Vec2, Vec2)> {
+    let mut points = path.copied();
+    let centre = points.next()?;
+    let mut inner = centre;
+    for outer in points {
+        let (d_in, d_out) = (inner.distance(centre), outer.distance(centre));
+        if d_out >= radius {
+            let t = if d_out > d_in {
+                (radius - d_in) / (d_out - d_in)
+            } else {
+                0.
+            };
+            let dir = (inner - outer).try_normalize()?;
+            return Some((inner.lerp(outer, t), dir));
+        }
+        inner = outer;
+    }
+    None
+}
+
+/// Edges that changed how they're drawn, or were just disabled.
+type Redrawn = Or<(
+    Changed<EdgePath>,
+    Changed<EdgeStroke>,
+    Changed<Visibility>,
+    Added<Disabled>,
+)>;
+
+/// Rebuilds the batches holding an edge that changed. Hidden and disabled
+/// edges are left out.
+fn draw_batches(
+    mut batches: ResMut<EdgeBatches>,
+    changed: Query<Entity, (With<EdgePath>, Redrawn, IncludeDisabled)>,
+    mut enabled: RemovedComponents<Disabled>,
+    edges: Query<(&EdgePath, &EdgeStroke, &Visibility)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let b = &mut *batches;
+    for id in changed.iter().chain(enabled.read()) {
+        if let Some(&i) = b.slots.get(&id) {
+            b.batches[i].dirty = true;
+        }
+    }
+    for batch in b.batches.iter_mut().filter(|batch| batch.dirty) {
+        batch.dirty = false;
+        let Some(mesh) = meshes.get_mut(&batch.mesh) else {
+            continue;
+        };
+        let mut strokes = Strokes::default();
+        for (path, stroke, visibility) in edges.iter_many(&batch.edges) {
+            if *visibility != Visibility::Hidden {
+                strokes.add(&path.0, stroke.width, stroke.color);
+            }
+        }
+        strokes.write(mesh);
+    }
+}
+
+/// Triangles for many strokes, to go in one mesh.
+#[derive(Default)]
+struct Strokes {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl Strokes {
+    /// Adds a band of `width` around the polyline `points`.
+    fn add(&mut self, points: &[Vec2], width: f32, color: Color) {
+        let start = self.positions.len() as u32;
+        for (i, &p) in points.iter().enumerate() {
+            let prev = points[i.saturating_sub(1)];
+            let next = points[(i + 1).min(points.len() - 1)];
+            let normal = (next - prev).normalize_or_zero().perp() * width / 2.;
+            self.positions.push((p + normal).extend(0.).to_array());
+            self.positions.push((p - normal).extend(0.).to_array());
+        }
+        for i in 1..points.len() as u32 {
+            let (a, b, c, d) = (2 * i - 2, 2 * i - 1, 2 * i, 2 * i + 1);
+            self.indices
+                .extend([a, b, c, b, d, c].map(|index| start + index));
+        }
+        self.colors
+            .resize(self.positions.len(), color.to_linear().to_f32_array());
+    }
+
+    fn write(self, mesh: &mut Mesh) {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh.insert_indices(Indices::U32(self.indices));
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+/// Alpha dimmed elements are drawn at, relative to their usual color.
+pub(crate) const DIMMED_ALPHA: f32 = 0.15;
+
+/// How many times wider than usual highlighted edges are drawn.
+pub(crate) const HIGHLIGHT_WIDTH: f32 = 2.5;
+
+/// Fades a node or edge into the background, so that the ones singled out
+/// stand out.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Dimmed;
+
+/// Draws an edge wider and in the theme's selection color.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Highlighted;
@@ -0,0 +1,269 @@
+use std::f32::consts::TAU;
+
+use avian2d::prelude::*;
+use bevy::{
+    asset::RenderAssetUsages,
+    platform::collections::HashMap,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+    sprite::Anchor,
+};
+
+use super::colors::apply_mapping;
+use crate::{
+    analysis::community::Community,
+    config::Config,
+    graph::{GraphNode, IncludeDisabled, NodeRadius},
+};
+
+/// Draw depth of hulls, behind the edges.
+const HULL_Z: f32 = -2.;
+
+/// Room left between a hull's rim and the nodes inside it.
+const HULL_PADDING: f32 = 12.;
+
+/// Points sampled around each node for the hull, which round its corners.
+const HULL_SAMPLES: usize = 12;
+
+/// Opacity of the hull's fill.
+pub(crate) const HULL_ALPHA: f32 = 0.18;
+
+/// A named set of nodes, drawn as a soft hull in `color` behind them that
+/// follows them as the layout moves. Spawn it on an entity of its own and
+/// despawn that to ungroup; members that are gone or disabled are left out.
+#[derive(Component, Clone, Debug)]
+pub struct NodeGroup {
+    pub name: String,
+    pub members: Vec<Entity>,
+    pub color: Color,
+}
+
+/// Whether every [`Community`] of two or more nodes is drawn as a hull in
+/// its nodes' color, following them as the layout settles.
+#[derive(Resource, Clone, Debug)]
+pub struct CommunityHulls {
+    pub enabled: bool,
+}
+
+impl Default for CommunityHulls {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Marks the [`NodeGroup`] drawing a [`Community`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CommunityHull(pub usize);
+
+/// Text child naming its [`NodeGroup`] above the hull.
+#[derive(Component)]
+struct GroupName;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<CommunityHulls>().add_systems(
+        PostUpdate,
+        (
+            sync_community_hulls.run_if(
+                resource_changed::<CommunityHulls>
+                    .or(any_match_filter::<Changed<Community>>)
+                    .or(any_component_removed::<Community>),
+            ),
+            color_community_hulls.after(apply_mapping),
+            attach_hulls,
+            draw_hulls,
+        )
+            .chain()
+            .after(PhysicsSet::Sync)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// Respawns the community hulls whenever communities change.
+fn sync_community_hulls(
+    mut cmd: Commands,
+    settings: Res<CommunityHulls>,
+    nodes: Query<(Entity, &Community), (With<GraphNode>, IncludeDisabled)>,
+    hulls: Query<Entity, With<CommunityHull>>,
+) {
+    for id in &hulls {
+        cmd.entity(id).despawn();
+    }
+    if !settings.enabled {
+        return;
+    }
+    let mut communities: HashMap<usize, Vec<Entity>> = HashMap::default();
+    for (id, community) in &nodes {
+        communities.entry(community.0).or_default().push(id);
+    }
+    for (community, members) in communities {
+        if members.len() < 2 {
+            continue;
+        }
+        cmd.spawn((
+            NodeGroup {
+                name: format!("Community {community}"),
+                members,
+                // Taken from the nodes by `color_community_hulls`.
+                color: Color::NONE,
+            },
+            CommunityHull(community),
+        ));
+    }
+}
+
+/// Gives each community hull the color its nodes are drawn in.
+fn color_community_hulls(
+    mut hulls: Query<&mut NodeGroup, With<CommunityHull>>,
+    nodes: Query<&MeshMaterial2d<ColorMaterial>, With<GraphNode>>,
+    materials: Res<Assets<ColorMaterial>>,
+) {
+    for mut group in &mut hulls {
+        let color = nodes
+            .iter_many(&group.members)
+            .find_map(|m| materials.get(&m.0))
+            .map(|m| m.color.with_alpha(1.));
+        if let Some(color) = color
+            && color != group.color
+        {
+            group.color = color;
+        }
+    }
+}
+
+fn attach_hulls(
+    mut cmd: Commands,
+    groups: Query<(Entity, &NodeGroup), Without<Mesh2d>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<Config>,
+) {
+    for (id, group) in &groups {
+        cmd.entity(id)
+            .insert((
+                Mesh2d(meshes.add(empty_mesh())),
+                MeshMaterial2d(materials.add(group.color.with_alpha(HULL_ALPHA))),
+                Transform::from_xyz(0., 0., HULL_Z),
+                Visibility::default(),
+                Pickable::IGNORE,
+            ))
+            .with_child((
+                GroupName,
+                Text2d::new(group.name.clone()),
+                TextFont::from_font_size(config.label_font_size),
+                TextColor(group.color),
+                Anchor::BottomCenter,
+                Pickable::IGNORE,
+            ));
+    }
+}
+
+/// Nodes a hull goes around, as opposed to the names on top.
+type HullMember = (With<GraphNode>, Without<GroupName>);
+
+/// Refits every hull around its members and keeps names and colors in step
+/// with the groups.
+fn draw_hulls(
+    groups: Query<(
+        Ref<NodeGroup>,
+        &Mesh2d,
+        &MeshMaterial2d<ColorMaterial>,
+        &Children,
+    )>,
+    mut names: Query<(&mut Text2d, &mut TextColor, &mut Transform), With<GroupName>>,
+    nodes: Query<(&Transform, &NodeRadius), HullMember>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (group, mesh, material, children) in &groups {
+        let circles = nodes
+            .iter_many(&group.members)
+            .map(|(t, r)| (t.translation.xy(), r.0));
+        let outline = hull(circles, HULL_PADDING);
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            fill(mesh, &outline);
+        }
+        if group.is_changed()
+            && let Some(material) = materials.get_mut(&material.0)
+        {
+            material.color = group.color.with_alpha(HULL_ALPHA);
+        }
+        let top = outline
+            .iter()
+            .copied()
+            .reduce(|a, b| if b.y > a.y { b } else { a });
+        for &child in children {
+            let Ok((mut text, mut color, mut transform)) = names.get_mut(child) else {
+                continue;
+            };
+            if group.is_changed() {
+                text.0.clone_from(&group.name);
+                color.0 = group.color;
+            }
+            if let Some(top) = top {
+                transform.translation = top.extend(0.);
+            }
+        }
+    }
+}
+
+fn empty_mesh() -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Convex hull of `circles`, each grown by `padding`, counter-clockwise.
+/// Sampling the circles rounds the corners, and a lone circle comes out as
+/// a circle.
+pub(crate) fn hull(circles: impl Iterator<Item = (Vec2, f32)>, padding: f32) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = circles
+        .flat_map(|(centre, radius)| {
+            (0..HULL_SAMPLES).map(move |i| {
+                let angle = i as f32 * TAU / HULL_SAMPLES as f32;
+                centre + Vec2::from_angle(angle) * (radius + padding)
+            })
+        })
+        .collect();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    // Andrew's monotone chain: the lower half left to right, then the upper
+    // half back.
+    let turns_left = |h: &[Vec2], p: Vec2| {
+        let (a, b) = (h[h.len() - 2], h[h.len() - 1]);
+        (b - a).perp_dot(p - a) > 0.
+    };
+    let reversed: Vec<Vec2> = points.iter().rev().copied().collect();
+    let mut outline: Vec<Vec2> = Vec::with_capacity(points.len() + 1);
+    for pass in [&points, &reversed] {
+        let start = outline.len();
+        for &p in pass {
+            while outline.len() >= start + 2 && !turns_left(&outline, p) {
+                outline.pop();
+            }
+            outline.push(p);
+        }
+        // The last point starts the other half.
+        outline.pop();
+    }
+    outline
+}
+
+/// Replaces `mesh` with the filled polygon `outline`, as a fan around its
+/// centroid.
+pub(crate) fn fill(mesh: &mut Mesh, outline: &[Vec2]) {
+    let centroid = outline.iter().sum::<Vec2>() / outline.len().max(1) as f32;
+    let mut positions = vec![centroid.extend(0.).to_array()];
+    positions.extend(outline.iter().map(|p| p.extend(0.).to_array()));
+    let n = outline.len() as u32;
+    let indices = if n < 3 {
+        Vec::new()
+    } else {
+        (0..n).flat_map(|i| [0, i + 1, (i + 1) % n + 1]).collect()
+    };
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(Indices::U32(indices));
+}
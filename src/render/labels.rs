@@ -0,0 +1,389 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use avian2d::prelude::*;
+use bevy::{ecs::query::QueryFilter, platform::collections::HashMap, prelude::*, sprite::Anchor};
+
+use super::{
+    culling::InView,
+    edges::{EdgePath, EdgeSystems},
+    lod::detailed,
+    theme::Theme,
+};
+use crate::{
+    config::Config,
+    graph::{Edge, EdgeLabel, Graph, GraphNode, LabelPriority, NodeLabel, NodeRadius},
+    selection::Selected,
+};
+
+/// Rough advance of one character as a fraction of the font size, used to
+/// size labels without waiting for text layout.
+const CHAR_WIDTH: f32 = 0.6;
+
+/// Added to the priority of selected nodes' labels so they win every overlap.
+const SELECTED_PRIORITY: f32 = 1e6;
+
+/// Text child drawing its parent's [`NodeLabel`] or [`EdgeLabel`].
+#[derive(Component)]
+struct LabelText;
+
+/// Label components that get a [`LabelText`] child.
+trait Label: Component {
+    fn text(&self) -> &str;
+
+    fn color(theme: &Theme) -> Color;
+}
+
+impl Label for NodeLabel {
+    fn text(&self) -> &str {
+        &self.0
+    }
+
+    fn color(theme: &Theme) -> Color {
+        theme.node_label
+    }
+}
+
+impl Label for EdgeLabel {
+    fn text(&self) -> &str {
+        &self.0
+    }
+
+    fn color(theme: &Theme) -> Color {
+        theme.edge_label
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        (
+            (
+                spawn_labels::<NodeLabel, With<GraphNode>>,
+                // Edges get their drawing parts first.
+                spawn_labels::<EdgeLabel, With<EdgePath>>,
+                despawn_labels::<NodeLabel>,
+                despawn_labels::<EdgeLabel>,
+            ),
+            (
+                sync_font.run_if(resource_changed::<Config>),
+                sync_color.run_if(resource_changed::<Theme>),
+            ),
+            (follow_nodes, follow_edges, declutter)
+                .chain()
+                .run_if(detailed),
+            hide_labels.run_if(not(detailed)),
+        )
+            .chain()
+            .after(PhysicsSet::Sync)
+            .after(EdgeSystems::Draw)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// Entities matching `F` whose label changed or that just got children.
+type LabelOwners<'w, 's, L, F> = Query<
+    'w,
+    's,
+    (Entity, &'static L, Option<&'static Children>),
+    (F, Or<(Changed<L>, Added<Children>)>),
+>;
+
+fn spawn_labels<L: Label, F: QueryFilter>(
+    mut cmd: Commands,
+    owners: LabelOwners<L, F>,
+    mut texts: Query<&mut Text2d, With<LabelText>>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+) {
+    for (id, label, children) in &owners {
+        let existing = children
+            .into_iter()
+            .flatten()
+            .find(|&&child| texts.contains(child));
+        if let Some(&child) = existing {
+            if let Ok(mut text) = texts.get_mut(child)
+                && text.0 != label.text()
+            {
+                text.0 = label.text().to_string();
+            }
+            continue;
+        }
+        cmd.entity(id).with_child((
+            LabelText,
+            Text2d::new(label.text()),
+            TextFont::from_font_size(config.label_font_size),
+            TextColor(L::color(&theme)),
+            Anchor::BottomCenter,
+        ));
+    }
+}
+
+fn despawn_labels<L: Label>(
+    mut cmd: Commands,
+    mut removed: RemovedComponents<L>,
+    children: Query<&Children>,
+    texts: Query<(), With<LabelText>>,
+) {
+    for id in removed.read() {
+        for &child in children.get(id).into_iter().flatten() {
+            if texts.contains(child) {
+                cmd.entity(child).despawn();
+            }
+        }
+    }
+}
+
+fn sync_font(mut fonts: Query<&mut TextFont, With<LabelText>>, config: Res<Config>) {
+    for mut font in &mut fonts {
+        font.font_size = config.label_font_size;
+    }
+}
+
+fn sync_color(
+    mut labels: Query<(&ChildOf, &mut TextColor), With<LabelText>>,
+    nodes: Query<(), With<GraphNode>>,
+    theme: Res<Theme>,
+) {
+    for (parent, mut color) in &mut labels {
+        color.0 = if nodes.contains(parent.parent()) {
+            NodeLabel::color(&theme)
+        } else {
+            EdgeLabel::color(&theme)
+        };
+    }
+}
+
+/// Drops every label from a simplified drawing.
+fn hide_labels(mut labels: Query<&mut Visibility, With<LabelText>>) {
+    for mut visibility in &mut labels {
+        visibility.set_if_neq(Visibility::Hidden);
+    }
+}
+
+/// Keeps labels upright above their nodes however the node bodies rotate.
+fn follow_nodes(
+    nodes: Query<(&Transform, &NodeRadius, &Children), InView<GraphNode>>,
+    mut labels: Query<&mut Transform, (With<LabelText>, Without<GraphNode>)>,
+    config: Res<Config>,
+) {
+    for (node, radius, children) in &nodes {
+        let upright = node.rotation.inverse();
+        let offset = Vec3::new(0., radius.0 + config.label_offset, 1.);
+        for &child in children {
+            if let Ok(mut label) = labels.get_mut(child) {
+                label.translation = upright * offset;
+                label.rotation = upright;
+            }
+        }
+    }
+}
+
+/// Sits edge labels on the middle of their path, turned along it but never
+/// upside down. Edge parts live in world space, so labels do too.
+fn follow_edges(
+    edges: Query<(&EdgePath, &Children), InView<Edge>>,
+    mut labels: Query<(&mut Transform, &mut Visibility), With<LabelText>>,
+    config: Res<Config>,
+) {
+    for (path, children) in &edges {
+        let middle = halfway(&path.0);
+        for &child in children {
+            let Ok((mut transform, mut visibility)) = labels.get_mut(child) else {
+                continue;
+            };
+            let shown = config.show_edge_labels && middle.is_some();
+            visibility.set_if_neq(if shown {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+            let Some((point, dir)) = middle else {
+                continue;
+            };
+            let mut angle = dir.to_angle();
+            if angle.abs() > FRAC_PI_2 {
+                angle -= angle.signum() * PI;
+            }
+            transform.translation = point.extend(1.5);
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
+
+/// Point halfway along `path` by length, with the direction of the path there.
+fn halfway(path: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let total: f32 = path.windows(2).map(|w| w[0].distance(w[1])).sum();
+    let mut remaining = total / 2.;
+    for w in path.windows(2) {
+        let length = w[0].distance(w[1]);
+        if length > 0. && remaining <= length {
+            let dir = (w[1] - w[0]) / length;
+            return Some((w[0] + dir * remaining, dir));
+        }
+        remaining -= length;
+    }
+    None
+}
+
+type PlacedTexts<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ChildOf,
+        &'static Text2d,
+        &'static mut Transform,
+        &'static mut Visibility,
+    ),
+    (With<LabelText>, Without<GraphNode>),
+>;
+
+type RankedNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static NodeRadius,
+        Option<&'static LabelPriority>,
+        Has<Selected>,
+    ),
+    InView<GraphNode>,
+>;
+
+/// Where a label could go, as the world-space box it would cover and the
+/// local translation that puts it there.
+struct Spot {
+    bounds: Rect,
+    translation: Vec3,
+}
+
+/// Scales labels to a constant size on screen, then places them greedily in
+/// priority order: node labels try the four sides of their node and edge
+/// labels their midpoint, and a label that fits nowhere is hidden. Labels
+/// out of view are left as they are.
+fn declutter(
+    cameras: Query<(&Camera, &Projection), With<Camera2d>>,
+    nodes: RankedNodes,
+    edges: Query<(), InView<Edge>>,
+    mut labels: PlacedTexts,
+    graph: Res<Graph>,
+    config: Res<Config>,
+) {
+    let scale = cameras
+        .iter()
+        .find(|(camera, _)| camera.is_active)
+        .and_then(|(_, projection)| match projection {
+            Projection::Orthographic(ortho) => Some(ortho.scale),
+            _ => None,
+        })
+        .unwrap_or(1.);
+    let height = config.label_font_size * scale;
+
+    let mut queue = Vec::new();
+    for (label, (parent, text, mut transform, mut visibility)) in labels.iter_mut().enumerate() {
+        transform.scale = Vec3::splat(scale);
+        let size = Vec2::new(text.0.chars().count() as f32 * CHAR_WIDTH * height, height);
+        let (priority, spots) = if let Ok((node, radius, priority, selected)) =
+            nodes.get(parent.parent())
+        {
+            visibility.set_if_neq(Visibility::Inherited);
+            let mut priority = graph.degree(parent.parent()) as f32 + priority.map_or(0., |p| p.0);
+            if selected {
+                priority += SELECTED_PRIORITY;
+            }
+            let gap = radius.0 + config.label_offset * scale;
+            let upright = node.rotation.inverse();
+            let centre = node.translation.xy();
+            // Anchored at the bottom centre, so each offset is that point.
+            let offsets = [
+                Vec2::new(0., gap),
+                Vec2::new(0., -gap - size.y),
+                Vec2::new(gap + size.x / 2., -size.y / 2.),
+                Vec2::new(-gap - size.x / 2., -size.y / 2.),
+            ];
+            let spots: Vec<_> = offsets
+                .into_iter()
+                .map(|offset| Spot {
+                    bounds: Rect::from_center_size(centre + offset + Vec2::Y * size.y / 2., size),
+                    translation: upright * offset.extend(1.),
+                })
+                .collect();
+            (priority, spots)
+        } else if edges.contains(parent.parent()) {
+            if *visibility == Visibility::Hidden {
+                continue;
+            }
+            let up = transform.rotation * Vec3::Y * size.y / 2.;
+            let across = transform.rotation * Vec3::X * size.x / 2.;
+            let extent = (up.abs() + across.abs()).xy();
+            let spot = Spot {
+                bounds: Rect::from_center_half_size(transform.translation.xy() + up.xy(), extent),
+                translation: transform.translation,
+            };
+            // Below every node label.
+            (-1., vec![spot])
+        } else {
+            continue;
+        };
+        queue.push((priority, label, spots));
+    }
+    if !config.declutter_labels {
+        return;
+    }
+    queue.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut placed = PlacedLabels::new(4. * height);
+    let mut choices = HashMap::new();
+    for (_, label, spots) in queue {
+        let choice = spots.into_iter().find(|spot| !placed.overlaps(spot.bounds));
+        if let Some(spot) = &choice {
+            placed.insert(spot.bounds);
+        }
+        choices.insert(label, choice.map(|spot| spot.translation));
+    }
+    for (label, (_, _, mut transform, mut visibility)) in labels.iter_mut().enumerate() {
+        let Some(&choice) = choices.get(&label) else {
+            continue;
+        };
+        match choice {
+            Some(translation) => transform.translation = translation,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// Boxes of the labels placed so far, bucketed on a grid.
+struct PlacedLabels {
+    cell: f32,
+    cells: HashMap<IVec2, Vec<Rect>>,
+}
+
+impl PlacedLabels {
+    fn new(cell: f32) -> Self {
+        Self {
+            cell: cell.max(f32::EPSILON),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cells(&self, rect: Rect) -> impl Iterator<Item = IVec2> {
+        let min = (rect.min / self.cell).floor().as_ivec2();
+        let max = (rect.max / self.cell).floor().as_ivec2();
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+    }
+
+    fn overlaps(&self, rect: Rect) -> bool {
+        self.cells(rect).any(|cell| {
+            self.cells
+                .get(&cell)
+                .is_some_and(|rects| rects.iter().any(|r| !r.intersect(rect).is_empty()))
+        })
+    }
+
+    fn insert(&mut self, rect: Rect) {
+        for cell in self.cells(rect).collect::<Vec<_>>() {
+            self.cells.entry(cell).or_default().push(rect);
+        }
+    }
+}
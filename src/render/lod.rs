@@ -0,0 +1,178 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::edges::{EdgePath, EdgeSystems};
+use crate::graph::{Graph, GraphNode, IncludeDisabled};
+
+/// Width of a node drawn as a point, in pixels.
+const POINT_SIZE: f32 = 3.;
+
+/// Simplified drawing for big graphs. Once the camera zooms out past
+/// `zoom_threshold`, or the graph has more than `node_threshold` nodes,
+/// labels are dropped, nodes are drawn as points a few pixels wide and only
+/// an even sample of at most `max_edges` edges is drawn.
+#[derive(Resource, Clone, Debug)]
+pub struct LevelOfDetail {
+    pub enabled: bool,
+    /// Orthographic camera scale, in world units per pixel.
+    pub zoom_threshold: f32,
+    pub node_threshold: usize,
+    pub max_edges: usize,
+    simplified: bool,
+}
+
+impl Default for LevelOfDetail {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            zoom_threshold: 5.,
+            node_threshold: 20_000,
+            max_edges: 50_000,
+            simplified: false,
+        }
+    }
+}
+
+impl LevelOfDetail {
+    /// Whether the graph is drawn simplified right now.
+    pub fn is_simplified(&self) -> bool {
+        self.simplified
+    }
+}
+
+/// Run condition for full detail.
+pub(crate) fn detailed(detail: Res<LevelOfDetail>) -> bool {
+    !detail.is_simplified()
+}
+
+/// Square mesh shared by every node drawn as a point.
+#[derive(Resource)]
+struct PointMesh(Handle<Mesh>);
+
+impl FromWorld for PointMesh {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self(meshes.add(Rectangle::from_length(POINT_SIZE)))
+    }
+}
+
+/// A node's own mesh, put aside while it is drawn as a point.
+#[derive(Component)]
+pub(crate) struct DetailedMesh(pub(crate) Handle<Mesh>);
+
+/// Marks an edge left out of the simplified drawing.
+#[derive(Component)]
+pub(crate) struct Subsampled;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<LevelOfDetail>()
+        .add_systems(Startup, |mut cmd: Commands| {
+            cmd.init_resource::<PointMesh>()
+        })
+        .add_systems(
+            PostUpdate,
+            (
+                decide_detail,
+                (
+                    swap_meshes.run_if(
+                        resource_changed::<LevelOfDetail>.or(any_match_filter::<Added<GraphNode>>),
+                    ),
+                    subsample_edges.run_if(
+                        resource_changed::<LevelOfDetail>
+                            .or(resource_changed::<Graph>)
+                            .or(any_match_filter::<Added<EdgePath>>),
+                    ),
+                ),
+            )
+                .chain()
+                .after(PhysicsSet::Sync)
+                .before(EdgeSystems::Trace),
+        );
+}
+
+/// Switches detail as the zoom or graph size crosses a threshold, and keeps
+/// points the same size on screen.
+fn decide_detail(
+    mut detail: ResMut<LevelOfDetail>,
+    cameras: Query<(&Camera, &Projection), With<Camera2d>>,
+    graph: Res<Graph>,
+    point: Res<PointMesh>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut sized_for: Local<f32>,
+) {
+    let scale = cameras
+        .iter()
+        .find(|(camera, _)| camera.is_active)
+        .and_then(|(_, projection)| match projection {
+            Projection::Orthographic(ortho) => Some(ortho.scale),
+            _ => None,
+        })
+        .unwrap_or(1.);
+    let simplified = detail.enabled
+        && (scale > detail.zoom_threshold || graph.node_count() > detail.node_threshold);
+    if simplified != detail.simplified {
+        detail.simplified = simplified;
+    }
+    if simplified
+        && scale != *sized_for
+        && let Some(mesh) = meshes.get_mut(&point.0)
+    {
+        *mesh = Rectangle::from_length(POINT_SIZE * scale).into();
+        *sized_for = scale;
+    }
+}
+
+type NodeMeshes<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static mut Mesh2d, Option<&'static DetailedMesh>),
+    (With<GraphNode>, IncludeDisabled),
+>;
+
+/// Swaps every node's mesh for the point while simplified, and back after.
+fn swap_meshes(
+    mut cmd: Commands,
+    detail: Res<LevelOfDetail>,
+    mut nodes: NodeMeshes,
+    point: Res<PointMesh>,
+) {
+    for (id, mut mesh, detailed) in &mut nodes {
+        match detailed {
+            None if detail.is_simplified() => {
+                let own = std::mem::replace(&mut mesh.0, point.0.clone());
+                cmd.entity(id).insert(DetailedMesh(own));
+            }
+            Some(own) if !detail.is_simplified() => {
+                mesh.0 = own.0.clone();
+                cmd.entity(id).remove::<DetailedMesh>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Marks all but every so many edges [`Subsampled`] while simplified, in
+/// entity order so the sample is spread evenly and stays put. Whether an edge
+/// is drawn is left to [`Hidden`](crate::Hidden)'s visibility sync, so edges
+/// hidden for other reasons stay hidden when detail returns.
+fn subsample_edges(
+    mut cmd: Commands,
+    detail: Res<LevelOfDetail>,
+    edges: Query<(Entity, Has<Subsampled>), With<EdgePath>>,
+) {
+    let mut edges: Vec<_> = edges.iter().collect();
+    edges.sort_by_key(|&(id, ..)| id);
+    let every = if detail.is_simplified() {
+        edges.len().div_ceil(detail.max_edges.max(1)).max(1)
+    } else {
+        1
+    };
+    for (i, (id, sampled_out)) in edges.into_iter().enumerate() {
+        let drop = i % every != 0;
+        if drop && !sampled_out {
+            cmd.entity(id).insert(Subsampled);
+        } else if !drop && sampled_out {
+            cmd.entity(id).remove::<Subsampled>();
+        }
+    }
+}
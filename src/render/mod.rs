@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+pub mod bundling;
+pub mod colors;
+pub mod culling;
+pub(crate) mod edges;
+pub mod emphasis;
+pub mod hulls;
+mod labels;
+pub mod lod;
+pub mod shapes;
+pub(crate) mod shared;
+pub mod sizes;
+pub mod theme;
+pub mod transitions;
+pub mod weights;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<shared::NodeHandles>().add_plugins((
+        edges::plugin,
+        bundling::plugin,
+        colors::plugin,
+        culling::plugin,
+        hulls::plugin,
+        sizes::plugin,
+        shapes::plugin,
+        theme::plugin,
+        transitions::plugin,
+        weights::plugin,
+        labels::plugin,
+        lod::plugin,
+    ));
+}
@@ -0,0 +1,279 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    colors::category_order, edges::EdgeSystems, lod::DetailedMesh, shared::NodeAssets,
+    sizes::apply_sizing,
+};
+use crate::graph::{Attributes, GraphNode, NodeRadius};
+
+/// Radius of a capsule's round ends, as a fraction of the node's radius.
+const CAPSULE_ROUNDING: f32 = 0.7;
+
+/// Outline of a node, drawn and collided with. Every shape covers the same
+/// area as the node's circle, so changing shape doesn't change how big a
+/// node looks. Spawned from the `shape` attribute of the source data, and
+/// kept up to date by [`ShapeMapping`].
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeShape {
+    #[default]
+    Circle,
+    Square,
+    Diamond,
+    Hexagon,
+    /// A pill, lying flat.
+    Capsule,
+}
+
+impl NodeShape {
+    pub const ALL: [Self; 5] = [
+        Self::Circle,
+        Self::Square,
+        Self::Diamond,
+        Self::Hexagon,
+        Self::Capsule,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Circle => "circle",
+            Self::Square => "square",
+            Self::Diamond => "diamond",
+            Self::Hexagon => "hexagon",
+            Self::Capsule => "capsule",
+        }
+    }
+
+    /// The shape called `name`, in any case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Mesh of this shape for a node of `radius`.
+    pub fn mesh(self, radius: f32) -> Mesh {
+        match self {
+            Self::Circle => Circle::new(radius).into(),
+            Self::Square => Rectangle::from_length(2. * square_half(radius)).into(),
+            Self::Diamond => {
+                let diagonal = 2. * square_half(radius) * 2f32.sqrt();
+                Rhombus::new(diagonal, diagonal).into()
+            }
+            Self::Hexagon => RegularPolygon::new(hexagon_radius(radius), 6).into(),
+            Self::Capsule => {
+                let (round, half) = capsule_size(radius);
+                Mesh::from(Capsule2d::new(round, 2. * half))
+                    .rotated_by(Quat::from_rotation_z(FRAC_PI_2))
+            }
+        }
+    }
+
+    /// Collider of this shape for a node of `radius`.
+    pub fn collider(self, radius: f32) -> Collider {
+        match self {
+            Self::Circle => Collider::circle(radius),
+            Self::Square => Collider::rectangle(2. * square_half(radius), 2. * square_half(radius)),
+            Self::Diamond | Self::Hexagon => self
+                .corners(radius)
+                .and_then(Collider::convex_hull)
+                .unwrap_or_else(|| Collider::circle(radius)),
+            Self::Capsule => {
+                let (round, half) = capsule_size(radius);
+                Collider::capsule_endpoints(round, -half * Vec2::X, half * Vec2::X)
+            }
+        }
+    }
+
+    /// Corners of this shape for a node of `radius`, counterclockwise, for
+    /// the shapes that are polygons.
+    pub fn corners(self, radius: f32) -> Option<Vec<Vec2>> {
+        match self {
+            Self::Square => {
+                let h = square_half(radius);
+                Some(vec![
+                    Vec2::new(h, h),
+                    Vec2::new(-h, h),
+                    Vec2::new(-h, -h),
+                    Vec2::new(h, -h),
+                ])
+            }
+            Self::Diamond => {
+                let d = square_half(radius) * 2f32.sqrt();
+                Some(vec![d * Vec2::X, d * Vec2::Y, -d * Vec2::X, -d * Vec2::Y])
+            }
+            Self::Hexagon => Some(
+                RegularPolygon::new(hexagon_radius(radius), 6)
+                    .vertices(0.)
+                    .into_iter()
+                    .collect(),
+            ),
+            Self::Circle | Self::Capsule => None,
+        }
+    }
+}
+
+/// Half the side of a square as big as a circle of `radius`.
+fn square_half(radius: f32) -> f32 {
+    radius * PI.sqrt() / 2.
+}
+
+/// Circumradius of a hexagon as big as a circle of `radius`.
+fn hexagon_radius(radius: f32) -> f32 {
+    radius * (2. * PI / (3. * 3f32.sqrt())).sqrt()
+}
+
+/// Radius of the round ends and half the length of the straight middle of a
+/// capsule as big as a circle of `radius`.
+pub(crate) fn capsule_size(radius: f32) -> (f32, f32) {
+    let round = radius * CAPSULE_ROUNDING;
+    (round, PI * (radius * radius - round * round) / (4. * round))
+}
+
+/// Which shape nodes take. With `attribute` set, each distinct value of it
+/// takes the next of `shapes` in sorted order, like categorical
+/// [`ColorMapping`](crate::ColorMapping) colors, and nodes without one are
+/// circles. Without it, nodes use their own `shape` attribute if it names a
+/// shape, and are circles otherwise. Inserting a [`NodeShape`] directly
+/// works too, until the mapping or that node's attributes change.
+#[derive(Resource, Clone, Debug)]
+pub struct ShapeMapping {
+    pub attribute: Option<String>,
+    /// Shapes for the values, reused from the start when there are more
+    /// values than shapes.
+    pub shapes: Vec<NodeShape>,
+}
+
+impl Default for ShapeMapping {
+    fn default() -> Self {
+        Self {
+            attribute: None,
+            shapes: NodeShape::ALL.to_vec(),
+        }
+    }
+}
+
+impl ShapeMapping {
+    /// Shapes nodes by `attribute` with every shape.
+    pub fn by(attribute: impl Into<String>) -> Self {
+        Self {
+            attribute: Some(attribute.into()),
+            ..default()
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<NodeShape>()
+        .init_resource::<ShapeMapping>()
+        .add_systems(
+            PostUpdate,
+            (
+                assign_shapes.before(apply_sizing),
+                reshape_nodes.after(apply_sizing),
+            )
+                .after(PhysicsSet::Sync)
+                .before(EdgeSystems::Trace),
+        );
+}
+
+/// Nodes that were just spawned or whose attributes changed.
+type NeedsShape = Or<(Added<GraphNode>, Changed<Attributes>)>;
+
+/// Sets [`NodeShape`]s from the mapping. Mapped by attribute, every node is
+/// reshaped whenever any changes, since the order of values depends on all
+/// of them; otherwise only the nodes that changed are.
+fn assign_shapes(
+    mut nodes: Query<(Entity, &Attributes, Option<&mut NodeShape>), With<GraphNode>>,
+    changed: Query<(), (With<GraphNode>, NeedsShape)>,
+    mapping: Res<ShapeMapping>,
+    mut cmd: Commands,
+) {
+    if !mapping.is_changed() && changed.is_empty() {
+        return;
+    }
+    let all = mapping.is_changed() || mapping.attribute.is_some();
+    let shapes = node_shapes(nodes.iter().map(|(_, a, _)| a), &mapping);
+    for ((id, _, shape), new) in nodes.iter_mut().zip(shapes) {
+        // Shapes set from code last until the mapping changes.
+        let Some(new) = new.or(mapping.is_changed().then_some(NodeShape::Circle)) else {
+            continue;
+        };
+        if !all && !changed.contains(id) {
+            continue;
+        }
+        match shape {
+            Some(mut shape) => {
+                if *shape != new {
+                    *shape = new;
+                }
+            }
+            None => {
+                cmd.entity(id).insert(new);
+            }
+        }
+    }
+}
+
+/// Shape of each node in `nodes` order, or `None` for nodes left as they are.
+fn node_shapes<'a>(
+    nodes: impl Iterator<Item = &'a Attributes>,
+    mapping: &ShapeMapping,
+) -> Vec<Option<NodeShape>> {
+    let Some(key) = &mapping.attribute else {
+        return nodes
+            .map(|a| a.get("shape").and_then(NodeShape::from_name))
+            .collect();
+    };
+    let values: Vec<_> = nodes.map(|a| a.get(key)).collect();
+    let mut categories: Vec<_> = values.iter().flatten().copied().collect();
+    categories.sort_unstable_by(|a, b| category_order(a, b));
+    categories.dedup();
+    values
+        .into_iter()
+        .map(|v| {
+            let shape = v
+                .and_then(|v| categories.binary_search_by(|c| category_order(c, v)).ok())
+                .and_then(|i| {
+                    let n = mapping.shapes.len();
+                    (n > 0).then(|| mapping.shapes[i % n])
+                });
+            Some(shape.unwrap_or_default())
+        })
+        .collect()
+}
+
+type ShapedNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Ref<'static, NodeShape>,
+        &'static NodeRadius,
+        &'static mut Mesh2d,
+        Option<&'static mut DetailedMesh>,
+    ),
+    (With<GraphNode>, Changed<NodeShape>),
+>;
+
+/// Swaps the mesh and collider of nodes whose shape changed.
+fn reshape_nodes(mut cmd: Commands, mut nodes: ShapedNodes, mut assets: NodeAssets) {
+    for (id, shape, radius, mut mesh, detailed) in &mut nodes {
+        // Spawned nodes already have theirs.
+        if shape.is_added() {
+            continue;
+        }
+        let handle = assets.mesh(*shape, radius.0);
+        // A simplified node keeps its point until detail returns.
+        match detailed {
+            Some(mut detailed) => detailed.0 = handle,
+            None => mesh.0 = handle,
+        }
+        cmd.entity(id).insert(shape.collider(radius.0));
+    }
+}
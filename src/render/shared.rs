@@ -0,0 +1,68 @@
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+
+use super::shapes::NodeShape;
+
+/// Mesh radii are rounded to this fraction of a unit, so nodes of nearly the
+/// same size share a mesh.
+const RADIUS_STEPS: f32 = 4.;
+
+/// Meshes and materials shared by nodes of the same shape, size and color, and
+/// by arrowheads of the same color. Bevy draws entities with the same mesh and
+/// material as one instanced batch, so tens of thousands of nodes take a
+/// handful of assets and draw calls.
+#[derive(Resource, Default)]
+pub(crate) struct NodeHandles {
+    meshes: HashMap<(NodeShape, u32), Handle<Mesh>>,
+    materials: HashMap<[u8; 4], Handle<ColorMaterial>>,
+}
+
+impl NodeHandles {
+    /// `shape` for a node of `radius`, rounded to a quarter unit.
+    pub fn mesh(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        shape: NodeShape,
+        radius: f32,
+    ) -> Handle<Mesh> {
+        let steps = (radius * RADIUS_STEPS).round().max(1.) as u32;
+        self.meshes
+            .entry((shape, steps))
+            .or_insert_with(|| meshes.add(shape.mesh(steps as f32 / RADIUS_STEPS)))
+            .clone()
+    }
+
+    /// Flat `color`, to 8 bits a channel.
+    pub fn material(
+        &mut self,
+        materials: &mut Assets<ColorMaterial>,
+        color: Color,
+    ) -> Handle<ColorMaterial> {
+        let rgba = color.to_srgba().to_u8_array();
+        self.materials
+            .entry(rgba)
+            .or_insert_with(|| materials.add(Color::from(Srgba::from_u8_array(rgba))))
+            .clone()
+    }
+}
+
+/// [`NodeHandles`] with the assets they go in, for systems.
+#[derive(SystemParam)]
+pub(crate) struct NodeAssets<'w> {
+    handles: ResMut<'w, NodeHandles>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+}
+
+impl NodeAssets<'_> {
+    pub fn mesh(&mut self, shape: NodeShape, radius: f32) -> Handle<Mesh> {
+        self.handles.mesh(&mut self.meshes, shape, radius)
+    }
+
+    pub fn material(&mut self, color: Color) -> Handle<ColorMaterial> {
+        self.handles.material(&mut self.materials, color)
+    }
+
+    pub fn color(&self, material: &Handle<ColorMaterial>) -> Option<Color> {
+        self.materials.get(material).map(|m| m.color)
+    }
+}
@@ -0,0 +1,160 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{edges::EdgeSystems, lod::DetailedMesh, shapes::NodeShape, shared::NodeAssets};
+use crate::{
+    config::Config,
+    graph::{Attributes, Graph, GraphNode, NodeRadius},
+};
+
+/// How big nodes are drawn and how big their colliders are. With `by` set,
+/// every node is sized by that measure, spread from `min_radius` at the
+/// smallest value to `max_radius` at the largest so that circle *area* grows
+/// linearly with the value. Without it, nodes use their own `size` attribute
+/// or `default_radius`. Sizes follow the graph as nodes, edges and attributes
+/// change.
+#[derive(Resource, Clone, Debug)]
+pub struct NodeSizing {
+    pub by: Option<SizeBy>,
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub default_radius: f32,
+    /// Scale each node's mass with its area, relative to a node of
+    /// `default_radius` weighing [`Config::node_mass`], so big nodes are
+    /// harder to push around.
+    pub scale_mass: bool,
+}
+
+impl Default for NodeSizing {
+    fn default() -> Self {
+        Self {
+            by: None,
+            min_radius: 3.,
+            max_radius: 15.,
+            default_radius: 5.,
+            scale_mass: false,
+        }
+    }
+}
+
+impl NodeSizing {
+    /// Sizes nodes by `by` with the default radii.
+    pub fn by(by: SizeBy) -> Self {
+        Self {
+            by: Some(by),
+            ..default()
+        }
+    }
+}
+
+/// Measure nodes are sized by.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SizeBy {
+    /// Number of edges touching the node.
+    Degree,
+    /// Value of a numeric attribute. Nodes without one get `min_radius`.
+    Attribute(String),
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<NodeSizing>().add_systems(
+        PostUpdate,
+        apply_sizing
+            .after(PhysicsSet::Sync)
+            .before(EdgeSystems::Trace),
+    );
+}
+
+/// Nodes that were just spawned or whose attributes changed.
+type NeedsSize = Or<(Added<GraphNode>, Changed<Attributes>)>;
+
+type SizedNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static Attributes>,
+        &'static mut NodeRadius,
+        &'static mut Mesh2d,
+        Option<&'static mut DetailedMesh>,
+        &'static mut Mass,
+        Option<&'static NodeShape>,
+    ),
+    With<GraphNode>,
+>;
+
+/// Resizes every node whenever the sizing, the graph or a node's attributes
+/// change, since the value range depends on all of them. Masses also follow
+/// [`Config::node_mass`].
+pub(super) fn apply_sizing(
+    mut cmd: Commands,
+    mut nodes: SizedNodes,
+    changed: Query<(), (With<GraphNode>, NeedsSize)>,
+    sizing: Res<NodeSizing>,
+    graph: Res<Graph>,
+    config: Res<Config>,
+    mut assets: NodeAssets,
+) {
+    if !sizing.is_changed() && !graph.is_changed() && !config.is_changed() && changed.is_empty() {
+        return;
+    }
+    let radii = node_radii(
+        nodes.iter().map(|(id, attributes, ..)| (id, attributes)),
+        &sizing,
+        &graph,
+    );
+    for ((id, _, mut radius, mut mesh, detailed, mut mass, shape), r) in nodes.iter_mut().zip(radii)
+    {
+        let node_mass = if sizing.scale_mass {
+            config.node_mass * (r / sizing.default_radius).powi(2)
+        } else {
+            config.node_mass
+        };
+        if mass.0 != node_mass {
+            mass.0 = node_mass;
+        }
+        if radius.0 == r {
+            continue;
+        }
+        radius.0 = r;
+        let shape = shape.copied().unwrap_or_default();
+        // A simplified node keeps its point until detail returns.
+        match detailed {
+            Some(mut detailed) => detailed.0 = assets.mesh(shape, r),
+            None => mesh.0 = assets.mesh(shape, r),
+        }
+        cmd.entity(id).insert(shape.collider(r));
+    }
+}
+
+/// Radius of each node in `nodes` order.
+fn node_radii<'a>(
+    nodes: impl Iterator<Item = (Entity, Option<&'a Attributes>)>,
+    sizing: &NodeSizing,
+    graph: &Graph,
+) -> Vec<f32> {
+    let values: Vec<Option<f32>> = match &sizing.by {
+        None => {
+            return nodes
+                .map(|(_, a)| {
+                    a.and_then(|a| a.get_f32("size"))
+                        .unwrap_or(sizing.default_radius)
+                })
+                .collect();
+        }
+        Some(SizeBy::Degree) => nodes.map(|(id, _)| Some(graph.degree(id) as f32)).collect(),
+        Some(SizeBy::Attribute(key)) => {
+            nodes.map(|(_, a)| a.and_then(|a| a.get_f32(key))).collect()
+        }
+    };
+    let (min, max) = values
+        .iter()
+        .flatten()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let range = (max - min).max(f32::EPSILON);
+    let (lo, hi) = (sizing.min_radius.powi(2), sizing.max_radius.powi(2));
+    values
+        .into_iter()
+        .map(|v| v.map_or(sizing.min_radius, |v| lo.lerp(hi, (v - min) / range).sqrt()))
+        .collect()
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use super::colors::{ColorMapping, TABLEAU_10};
+use crate::config::Config;
+
+/// Every color the graph is drawn with. Whenever the theme changes it is
+/// pushed into [`ClearColor`], [`ColorMapping`], [`Config::edge_color`] and
+/// the labels, overwriting what was set there; tweak the theme rather than
+/// those to restyle the view.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    /// Colors for categorical node attributes, see [`ColorMapping::palette`].
+    pub palette: Vec<Color>,
+    /// Color of nodes that aren't colored by an attribute.
+    pub node: Color,
+    pub edge: Color,
+    /// Highlight for selected nodes and edges.
+    pub selection: Color,
+    pub node_label: Color,
+    pub edge_label: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Light nodes and text on Bevy's default dark grey.
+    pub fn dark() -> Self {
+        Self {
+            background: Color::srgb_u8(43, 44, 47),
+            palette: TABLEAU_10.iter().map(|&c| Color::Srgba(c)).collect(),
+            node: Color::WHITE,
+            edge: Color::srgba(1., 1., 1., 0.5),
+            selection: Color::srgb(1., 0.8, 0.2),
+            node_label: Color::WHITE,
+            edge_label: Color::srgba(1., 1., 1., 0.7),
+        }
+    }
+
+    /// Dark nodes and text on off-white.
+    pub fn light() -> Self {
+        Self {
+            background: Color::srgb(0.96, 0.96, 0.95),
+            palette: TABLEAU_10.iter().map(|&c| Color::Srgba(c)).collect(),
+            node: Color::srgb(0.25, 0.25, 0.25),
+            edge: Color::srgba(0., 0., 0., 0.4),
+            selection: Color::srgb(0.85, 0.35, 0.),
+            node_label: Color::srgb(0.1, 0.1, 0.1),
+            edge_label: Color::srgba(0., 0., 0., 0.7),
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Theme>()
+        .add_systems(PostUpdate, apply_theme.run_if(resource_changed::<Theme>));
+}
+
+fn apply_theme(
+    mut cmd: Commands,
+    theme: Res<Theme>,
+    mut mapping: ResMut<ColorMapping>,
+    mut config: ResMut<Config>,
+) {
+    cmd.insert_resource(ClearColor(theme.background));
+    mapping.palette.clone_from(&theme.palette);
+    mapping.default_color = theme.node;
+    config.edge_color = theme.edge;
+}
@@ -0,0 +1,169 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{colors::apply_mapping, edges::EdgeSystems, shared::NodeAssets};
+use crate::graph::GraphNode;
+
+/// Smallest scale a fading node is drawn at, so its collider never
+/// degenerates.
+const MIN_SCALE: f32 = 0.01;
+
+/// Tweening for structural changes instead of popping: nodes glide to the
+/// positions a computed layout gives them, new nodes grow and fade in, and
+/// removed ones shrink and fade out where they were. A zero `duration`
+/// turns it off.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Transitions {
+    /// Seconds each transition takes.
+    pub duration: f32,
+}
+
+impl Default for Transitions {
+    fn default() -> Self {
+        Self { duration: 0.6 }
+    }
+}
+
+impl Transitions {
+    pub fn is_enabled(&self) -> bool {
+        self.duration > 0.
+    }
+
+    /// Eased progress after `elapsed` seconds, from 0 to 1.
+    fn progress(&self, elapsed: f32) -> f32 {
+        if !self.is_enabled() {
+            return 1.;
+        }
+        let t = (elapsed / self.duration).clamp(0., 1.);
+        t * t * (3. - 2. * t)
+    }
+}
+
+/// Glides a node from `from` to `to`, holding it still on the way.
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct MoveTo {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+}
+
+impl MoveTo {
+    pub fn new(from: Vec2, to: Vec2) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.,
+        }
+    }
+
+    /// Moves the whole glide by `offset`, for nodes shifted along with
+    /// their component.
+    pub fn shift(&mut self, offset: Vec2) {
+        self.from += offset;
+        self.to += offset;
+    }
+}
+
+/// Grows and fades a new node in, or the ghost of a removed one out.
+#[derive(Component, Clone, Copy, Debug)]
+struct Fade {
+    out: bool,
+    elapsed: f32,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Transitions>()
+        .add_observer(fade_in)
+        .add_observer(fade_out)
+        .add_systems(
+            PostUpdate,
+            (move_nodes, fade.after(apply_mapping))
+                .after(PhysicsSet::Sync)
+                .before(EdgeSystems::Trace),
+        );
+}
+
+fn fade_in(trigger: Trigger<OnAdd, GraphNode>, mut cmd: Commands, transitions: Res<Transitions>) {
+    if transitions.is_enabled() {
+        cmd.entity(trigger.target()).insert(Fade {
+            out: false,
+            elapsed: 0.,
+        });
+    }
+}
+
+/// Leaves a ghost of a despawned node behind to fade out, since the node
+/// itself is gone by the next frame.
+fn fade_out(
+    trigger: Trigger<OnRemove, GraphNode>,
+    mut cmd: Commands,
+    transitions: Res<Transitions>,
+    nodes: Query<(&Transform, &Mesh2d, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    if !transitions.is_enabled() {
+        return;
+    }
+    let Ok((transform, mesh, material)) = nodes.get(trigger.target()) else {
+        return;
+    };
+    cmd.spawn((
+        *transform,
+        mesh.clone(),
+        material.clone(),
+        Fade {
+            out: true,
+            elapsed: 0.,
+        },
+    ));
+}
+
+fn move_nodes(
+    mut cmd: Commands,
+    mut nodes: Query<(Entity, &mut MoveTo, &mut Transform, &mut LinearVelocity)>,
+    transitions: Res<Transitions>,
+    time: Res<Time>,
+) {
+    for (id, mut tween, mut transform, mut velocity) in &mut nodes {
+        tween.elapsed += time.delta_secs();
+        let t = transitions.progress(tween.elapsed);
+        let pos = tween.from.lerp(tween.to, t);
+        transform.translation = pos.extend(transform.translation.z);
+        velocity.0 = Vec2::ZERO;
+        if t >= 1. {
+            cmd.entity(id).remove::<MoveTo>();
+        }
+    }
+}
+
+fn fade(
+    mut cmd: Commands,
+    mut faded: Query<(
+        Entity,
+        &mut Fade,
+        &mut Transform,
+        &mut MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut assets: NodeAssets,
+    transitions: Res<Transitions>,
+    time: Res<Time>,
+) {
+    for (id, mut fade, mut transform, mut material) in &mut faded {
+        fade.elapsed += time.delta_secs();
+        let t = transitions.progress(fade.elapsed);
+        let shown = if fade.out { 1. - t } else { t };
+        transform.scale = Vec3::splat(shown.max(MIN_SCALE));
+        // Shared materials come in every alpha, so fading one node leaves
+        // the rest alone.
+        if let Some(color) = assets.color(&material.0) {
+            material.0 = assets.material(color.with_alpha(shown));
+        }
+        if t < 1. {
+            continue;
+        }
+        if fade.out {
+            cmd.entity(id).despawn();
+        } else {
+            cmd.entity(id).remove::<Fade>();
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use super::{
+    edges::{EdgeStroke, EdgeSystems},
+    emphasis::{DIMMED_ALPHA, Dimmed, HIGHLIGHT_WIDTH, Highlighted},
+    theme::Theme,
+};
+use crate::{config::Config, graph::Edge};
+
+/// Draws heavier edges thicker and more opaque. Edge weights are spread from
+/// the lightest edge at `min_width`/`min_alpha` to the heaviest at
+/// `max_width`/`max_alpha`, alpha scaling that of [`Config::edge_color`].
+/// While every edge weighs the same, or with `enabled` off, edges are drawn
+/// with [`Config::edge_width`] and [`Config::edge_color`].
+#[derive(Resource, Clone, Debug)]
+pub struct EdgeWeightStyle {
+    pub enabled: bool,
+    pub min_width: f32,
+    pub max_width: f32,
+    pub min_alpha: f32,
+    pub max_alpha: f32,
+}
+
+impl Default for EdgeWeightStyle {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_width: 0.5,
+            max_width: 6.,
+            min_alpha: 0.2,
+            max_alpha: 1.,
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<EdgeWeightStyle>().add_systems(
+        PostUpdate,
+        apply_style
+            .after(EdgeSystems::Trace)
+            .before(EdgeSystems::Draw),
+    );
+}
+
+/// Edges that just got their parts, whose weight may have changed or that
+/// were just dimmed or highlighted.
+type NeedsStyle = Or<(
+    Added<EdgeStroke>,
+    Changed<Edge>,
+    Added<Dimmed>,
+    Added<Highlighted>,
+)>;
+
+/// An edge's weight, stroke and emphasis.
+type Styled = (
+    &'static Edge,
+    &'static mut EdgeStroke,
+    Has<Dimmed>,
+    Has<Highlighted>,
+);
+
+/// Restyles every edge whenever the style, the config or any edge changes,
+/// since the weight range depends on all of them. [`Highlighted`] edges are
+/// drawn wider in the theme's selection color and [`Dimmed`] ones faded.
+fn apply_style(
+    mut edges: Query<Styled>,
+    changed: Query<(), NeedsStyle>,
+    mut removed: (RemovedComponents<Dimmed>, RemovedComponents<Highlighted>),
+    style: Res<EdgeWeightStyle>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+) {
+    let emphasis_removed = removed.0.read().count() + removed.1.read().count() > 0;
+    if !style.is_changed()
+        && !config.is_changed()
+        && !theme.is_changed()
+        && changed.is_empty()
+        && !emphasis_removed
+    {
+        return;
+    }
+    let (min, max) = edges
+        .iter()
+        .map(|(edge, ..)| edge.weight)
+        .fold((f32::MAX, f32::MIN), |(lo, hi), w| (lo.min(w), hi.max(w)));
+    let weighted = style.enabled && max > min;
+    for (edge, mut stroke, dimmed, highlighted) in &mut edges {
+        let (width, alpha) = if weighted {
+            let t = (edge.weight - min) / (max - min);
+            (
+                style.min_width.lerp(style.max_width, t),
+                style.min_alpha.lerp(style.max_alpha, t),
+            )
+        } else {
+            (config.edge_width, 1.)
+        };
+        let (width, color) = if highlighted {
+            (
+                width.max(config.edge_width) * HIGHLIGHT_WIDTH,
+                theme.selection,
+            )
+        } else {
+            let color = config.edge_color;
+            (width, color.with_alpha(color.alpha() * alpha))
+        };
+        let color = if dimmed {
+            color.with_alpha(color.alpha() * DIMMED_ALPHA)
+        } else {
+            color
+        };
+        // Only touched when the style moves, which rebuilds the edge's batch.
+        stroke.set_if_neq(EdgeStroke { width, color });
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::{config::Config, graph::Graph};
+
+/// Chance a forest fire spreads to one more neighbour of a burning node, so
+/// each node burns `BURN / (1 - BURN)` of its neighbours on average.
+const BURN: f64 = 0.7;
+
+/// How a [`GraphSample`] picks its nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SampleMethod {
+    /// Ends of edges drawn at random.
+    #[default]
+    RandomEdge,
+    /// Fires lit at random nodes that spread along edges, keeping local
+    /// structure such as clusters and paths.
+    ForestFire,
+    /// The `size` nodes of highest degree.
+    TopDegree,
+}
+
+/// Shows a sample of about `size` nodes, and the edges between them, for
+/// graphs too large to lay out whole. Everything else is
+/// [`FilteredOut`](crate::FilteredOut) until `method` is cleared. Samples
+/// are drawn with [`Config::seed`], so the same settings give the same
+/// sample.
+#[derive(Resource, Clone, Debug)]
+pub struct GraphSample {
+    pub method: Option<SampleMethod>,
+    pub size: usize,
+    members: HashSet<Entity>,
+}
+
+impl Default for GraphSample {
+    fn default() -> Self {
+        Self {
+            method: None,
+            size: 1000,
+            members: HashSet::new(),
+        }
+    }
+}
+
+impl GraphSample {
+    pub fn is_active(&self) -> bool {
+        self.method.is_some()
+    }
+
+    /// Whether `node` is shown, which every node is without a `method`.
+    pub fn contains(&self, node: Entity) -> bool {
+        self.method.is_none() || self.members.contains(&node)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<GraphSample>().add_systems(
+        Update,
+        draw_sample.before(crate::filter::apply_filter).run_if(
+            resource_changed::<GraphSample>
+                .or(resource_changed::<Graph>)
+                .or(resource_changed::<Config>),
+        ),
+    );
+}
+
+fn draw_sample(mut sample: ResMut<GraphSample>, graph: Res<Graph>, config: Res<Config>) {
+    let members = match sample.method {
+        Some(method) => {
+            let mut rng = StdRng::seed_from_u64(config.seed);
+            let size = sample.size.min(graph.node_count());
+            match method {
+                SampleMethod::RandomEdge => random_edge(&graph, size, &mut rng),
+                SampleMethod::ForestFire => forest_fire(&graph, size, &mut rng),
+                SampleMethod::TopDegree => top_degree(&graph, size),
+            }
+        }
+        None => HashSet::new(),
+    };
+    if members != sample.members {
+        sample.bypass_change_detection().members = members;
+        sample.set_changed();
+    }
+}
+
+/// Nodes in a fixed order, so a seed always draws the same sample.
+fn sorted_nodes(graph: &Graph) -> Vec<Entity> {
+    let mut nodes: Vec<Entity> = graph.nodes().collect();
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Ends of random edges until there are `size` of them. Nodes without edges
+/// are never drawn.
+fn random_edge(graph: &Graph, size: usize, rng: &mut StdRng) -> HashSet<Entity> {
+    let mut edges: Vec<(Entity, Entity, Entity)> = graph.edges().collect();
+    edges.sort_unstable();
+    edges.shuffle(rng);
+    let mut members = HashSet::new();
+    for (_, source, target) in edges {
+        if members.len() >= size {
+            break;
+        }
+        members.insert(source);
+        members.insert(target);
+    }
+    members
+}
+
+/// Forest fire sampling: each fire starts at a random unburnt node and
+/// spreads to a random few of each burning node's unburnt neighbours, and a
+/// new fire is lit whenever one dies out before `size` nodes have burnt.
+fn forest_fire(graph: &Graph, size: usize, rng: &mut StdRng) -> HashSet<Entity> {
+    let mut unlit = sorted_nodes(graph);
+    unlit.shuffle(rng);
+    let mut burnt = HashSet::new();
+    while burnt.len() < size {
+        let Some(start) = unlit.pop() else {
+            break;
+        };
+        if !burnt.insert(start) {
+            continue;
+        }
+        let mut front = VecDeque::from([start]);
+        while let Some(node) = front.pop_front() {
+            let mut spread = 0;
+            while rng.gen_bool(BURN) {
+                spread += 1;
+            }
+            let mut neighbors: Vec<Entity> = graph
+                .neighbors(node)
+                .map(|(n, _)| n)
+                .filter(|n| !burnt.contains(n))
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors.shuffle(rng);
+            for neighbor in neighbors.into_iter().take(spread) {
+                if burnt.len() >= size {
+                    return burnt;
+                }
+                burnt.insert(neighbor);
+                front.push_back(neighbor);
+            }
+        }
+    }
+    burnt
+}
+
+/// The `size` best connected nodes.
+fn top_degree(graph: &Graph, size: usize) -> HashSet<Entity> {
+    let mut nodes = sorted_nodes(graph);
+    nodes.sort_by_key(|&n| std::cmp::Reverse(graph.degree(n)));
+    nodes.into_iter().take(size).collect()
+}
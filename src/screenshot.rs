@@ -0,0 +1,120 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, save_to_disk},
+    window::PrimaryWindow,
+};
+
+use crate::camera::{FRAME_PADDING, NodeBoundsQuery, framing, node_bounds};
+
+/// Saves the primary window to a PNG. With `fit`, the camera is moved to frame
+/// the whole graph for the capture and restored afterwards.
+#[derive(Event, Clone, Debug)]
+pub struct TakeScreenshot {
+    pub path: PathBuf,
+    pub fit: bool,
+}
+
+impl TakeScreenshot {
+    /// Timestamped file in the working directory.
+    pub fn timestamped(fit: bool) -> Self {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            path: format!("screenshot-{secs}.png").into(),
+            fit,
+        }
+    }
+}
+
+enum Stage {
+    Capture,
+    Restore,
+}
+
+#[derive(Resource)]
+struct PendingScreenshot {
+    path: PathBuf,
+    restore: Option<(Transform, f32)>,
+    stage: Stage,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_event::<TakeScreenshot>().add_systems(
+        Update,
+        (
+            screenshot_hotkeys,
+            start_screenshot.run_if(not(resource_exists::<PendingScreenshot>)),
+            finish_screenshot.run_if(resource_exists::<PendingScreenshot>),
+        )
+            .chain(),
+    );
+}
+
+fn screenshot_hotkeys(keys: Res<ButtonInput<KeyCode>>, mut ev_w: EventWriter<TakeScreenshot>) {
+    if keys.just_pressed(KeyCode::F12) {
+        let current_view = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        ev_w.write(TakeScreenshot::timestamped(!current_view));
+    }
+}
+
+fn start_screenshot(
+    mut cmd: Commands,
+    mut events: EventReader<TakeScreenshot>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    nodes: NodeBoundsQuery,
+) {
+    let Some(ev) = events.read().last() else {
+        return;
+    };
+    let mut restore = None;
+    if ev.fit {
+        let (Ok((mut transform, mut projection)), Ok(window), Some(bounds)) =
+            (cameras.single_mut(), window.single(), node_bounds(nodes))
+        else {
+            return;
+        };
+        if let Projection::Orthographic(ortho) = &mut *projection {
+            restore = Some((*transform, ortho.scale));
+            let (center, scale) = framing(bounds, window.size(), FRAME_PADDING);
+            transform.translation = center.extend(transform.translation.z);
+            ortho.scale = scale;
+        }
+    }
+    cmd.insert_resource(PendingScreenshot {
+        path: ev.path.clone(),
+        restore,
+        stage: Stage::Capture,
+    });
+}
+
+/// Runs a frame after [`start_screenshot`] so the fitted camera has rendered,
+/// then once more after the capture to put the camera back.
+fn finish_screenshot(
+    mut cmd: Commands,
+    mut pending: ResMut<PendingScreenshot>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    match pending.stage {
+        Stage::Capture => {
+            cmd.spawn(Screenshot::primary_window())
+                .observe(save_to_disk(pending.path.clone()));
+            info!("saved screenshot to {}", pending.path.display());
+            pending.stage = Stage::Restore;
+        }
+        Stage::Restore => {
+            if let (Some((saved, scale)), Ok((mut transform, mut projection))) =
+                (pending.restore, cameras.single_mut())
+            {
+                *transform = saved;
+                if let Projection::Orthographic(ortho) = &mut *projection {
+                    ortho.scale = scale;
+                }
+            }
+            cmd.remove_resource::<PendingScreenshot>();
+        }
+    }
+}
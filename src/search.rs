@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::{
+    graph::{GraphNode, NodeId, NodeLabel, NodeRadius},
+    render::theme::Theme,
+};
+
+/// Fuzzy search over node labels and IDs. Set `query` and read back
+/// `matches`, best first; matching nodes are ringed in the theme's
+/// selection color. An empty query matches nothing.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NodeSearch {
+    pub query: String,
+    matches: Vec<Entity>,
+}
+
+impl NodeSearch {
+    pub fn matches(&self) -> &[Entity] {
+        &self.matches
+    }
+
+    pub fn best(&self) -> Option<Entity> {
+        self.matches.first().copied()
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<NodeSearch>().add_systems(
+        Update,
+        (
+            find_matches.run_if(resource_changed::<NodeSearch>.or(any_match_filter::<(
+                With<GraphNode>,
+                Or<(Changed<NodeId>, Changed<NodeLabel>)>,
+            )>)),
+            ring_matches.run_if(resource_exists::<GizmoConfigStore>),
+        )
+            .chain(),
+    );
+}
+
+fn find_matches(
+    nodes: Query<(Entity, &NodeId, Option<&NodeLabel>), With<GraphNode>>,
+    mut search: ResMut<NodeSearch>,
+) {
+    let query = search.query.trim().to_lowercase();
+    let mut scored: Vec<_> = if query.is_empty() {
+        Vec::new()
+    } else {
+        nodes
+            .iter()
+            .filter_map(|(id, node_id, label)| {
+                let by_id = fuzzy_score(&query, &node_id.0);
+                let by_label = label.and_then(|l| fuzzy_score(&query, &l.0));
+                Some((by_id.max(by_label)?, id))
+            })
+            .collect()
+    };
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    // Avoid retriggering this system through change detection.
+    search.bypass_change_detection().matches = scored.into_iter().map(|(_, id)| id).collect();
+}
+
+/// Scores `text` against the lowercase `query` if every query character
+/// appears in it in order. Runs of consecutive characters, matches at word
+/// starts and shorter texts score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous = None;
+    for c in query.chars() {
+        let found = next + text[next..].iter().position(|&t| t == c)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score * 100 - text.len() as i32)
+}
+
+fn ring_matches(
+    mut gizmos: Gizmos,
+    search: Res<NodeSearch>,
+    nodes: Query<(&Transform, &NodeRadius), With<GraphNode>>,
+    theme: Res<Theme>,
+) {
+    for &id in search.matches() {
+        if let Ok((transform, radius)) = nodes.get(id) {
+            gizmos.circle_2d(transform.translation.xy(), radius.0 + 3., theme.selection);
+        }
+    }
+}
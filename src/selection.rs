@@ -0,0 +1,324 @@
+use bevy::{prelude::*, window::Window};
+use bevy_cursor::CursorLocation;
+
+use crate::{
+    graph::{Edge, GraphElement, GraphNode, NodeRadius},
+    render::{
+        edges::{EdgePath, EdgeSystems},
+        theme::Theme,
+    },
+};
+
+/// Width of the ring drawn around selected nodes.
+const RING_WIDTH: f32 = 2.;
+
+/// Marks a selected node or edge. Clicking one selects it alone,
+/// Ctrl-clicking adds it to or removes it from the selection, and clicking
+/// empty space clears it. Insert or remove the component to select from code.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Selected;
+
+/// The [`Selected`] nodes and edges in the order they were selected, kept in
+/// sync with the component.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Selection(Vec<Entity>);
+
+impl Selection {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn contains(&self, node: Entity) -> bool {
+        self.0.contains(&node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The most recently selected node or edge.
+    pub fn last(&self) -> Option<Entity> {
+        self.0.last().copied()
+    }
+}
+
+/// Region being dragged out on empty canvas, as world-space cursor positions.
+/// A box spans the first and last points; a lasso, drawn with Alt held, is
+/// the polygon through all of them. Kept until the end of the frame the drag
+/// ends in, so the click that ends it doesn't clear the selection.
+#[derive(Resource, Clone, Debug)]
+struct Region {
+    points: Vec<Vec2>,
+    lasso: bool,
+    done: bool,
+}
+
+impl Region {
+    fn contains(&self, p: Vec2) -> bool {
+        let (Some(&first), Some(&last)) = (self.points.first(), self.points.last()) else {
+            return false;
+        };
+        if !self.lasso {
+            return Rect::from_corners(first, last).contains(p);
+        }
+        // Even-odd rule: count the edges a ray to the right crosses.
+        let mut inside = false;
+        for (i, &a) in self.points.iter().enumerate() {
+            let b = self.points[(i + 1) % self.points.len()];
+            if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// Highlight ring around a selected node, as its child.
+#[derive(Component)]
+struct SelectionRing;
+
+/// Material shared by every [`SelectionRing`].
+#[derive(Resource)]
+struct RingMaterial(Handle<ColorMaterial>);
+
+impl FromWorld for RingMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let color = world.resource::<Theme>().selection;
+        Self(world.resource_mut::<Assets<ColorMaterial>>().add(color))
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Selection>()
+        .add_systems(Startup, |mut cmd: Commands| {
+            cmd.init_resource::<RingMaterial>()
+        })
+        .add_observer(select_on_click)
+        .add_observer(start_region)
+        .add_observer(extend_region)
+        .add_observer(select_region)
+        .add_observer(on_select)
+        .add_observer(on_deselect)
+        .add_systems(
+            PostUpdate,
+            (
+                fit_rings,
+                sync_ring_color.run_if(resource_changed::<Theme>),
+                draw_selected_edges
+                    .after(EdgeSystems::Draw)
+                    .run_if(resource_exists::<GizmoConfigStore>),
+                draw_region
+                    .run_if(resource_exists::<Region>.and(resource_exists::<GizmoConfigStore>)),
+            ),
+        )
+        .add_systems(
+            Last,
+            (|mut cmd: Commands| cmd.remove_resource::<Region>())
+                .run_if(|region: Option<Res<Region>>| region.is_some_and(|r| r.done)),
+        );
+}
+
+/// Clicking a node or edge selects it alone and Ctrl toggles it instead;
+/// clicking empty space clears the selection unless Ctrl is held. Clicks on
+/// edge parts bubble up to the edge, so only the edge's turn counts.
+fn select_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut cmd: Commands,
+    elements: Query<Has<Selected>, GraphElement>,
+    selected: Query<Entity, With<Selected>>,
+    windows: Query<(), With<Window>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    region: Option<Res<Region>>,
+) {
+    if trigger.button != PointerButton::Primary || region.is_some() {
+        return;
+    }
+    let additive = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let clicked = trigger.target();
+    if !elements.contains(clicked) && !windows.contains(clicked) {
+        return;
+    }
+    match (elements.get(clicked), additive) {
+        (Ok(true), true) => {
+            cmd.entity(clicked).remove::<Selected>();
+        }
+        (Ok(_), true) => {
+            cmd.entity(clicked).insert(Selected);
+        }
+        (Ok(_), false) => {
+            for id in selected.iter().filter(|&id| id != clicked) {
+                cmd.entity(id).remove::<Selected>();
+            }
+            cmd.entity(clicked).insert(Selected);
+        }
+        (Err(_), true) => {}
+        (Err(_), false) => {
+            for id in &selected {
+                cmd.entity(id).remove::<Selected>();
+            }
+        }
+    }
+}
+
+/// Dragging from empty space, which the picking backends report on the
+/// window, starts a box or, with Alt, a lasso.
+fn start_region(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut cmd: Commands,
+    windows: Query<(), With<Window>>,
+    cursor: Res<CursorLocation>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if trigger.button != PointerButton::Primary || !windows.contains(trigger.target) {
+        return;
+    }
+    let Some(start) = cursor.world_position() else {
+        return;
+    };
+    cmd.insert_resource(Region {
+        points: vec![start],
+        lasso: keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+        done: false,
+    });
+}
+
+fn extend_region(
+    _: Trigger<Pointer<Drag>>,
+    region: Option<ResMut<Region>>,
+    cursor: Res<CursorLocation>,
+) {
+    let (Some(mut region), Some(pos)) = (region, cursor.world_position()) else {
+        return;
+    };
+    if region.lasso || region.points.len() < 2 {
+        region.points.push(pos);
+    } else {
+        region.points[1] = pos;
+    }
+}
+
+/// Selects every node whose centre lies in the finished region, replacing
+/// the selection unless Ctrl is held.
+fn select_region(
+    _: Trigger<Pointer<DragEnd>>,
+    mut cmd: Commands,
+    region: Option<ResMut<Region>>,
+    nodes: Query<(Entity, &Transform, Has<Selected>), With<GraphNode>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(mut region) = region.filter(|r| !r.done) else {
+        return;
+    };
+    region.done = true;
+    let additive = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for (id, transform, selected) in &nodes {
+        match (region.contains(transform.translation.xy()), selected) {
+            (true, false) => {
+                cmd.entity(id).insert(Selected);
+            }
+            (false, true) if !additive => {
+                cmd.entity(id).remove::<Selected>();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn draw_region(mut gizmos: Gizmos, region: Res<Region>, theme: Res<Theme>) {
+    let (Some(&first), Some(&last)) = (region.points.first(), region.points.last()) else {
+        return;
+    };
+    if region.done {
+        return;
+    }
+    if region.lasso {
+        gizmos.linestrip_2d(
+            region.points.iter().copied().chain([first]),
+            theme.selection,
+        );
+    } else {
+        let rect = Rect::from_corners(first, last);
+        gizmos.rect_2d(rect.center(), rect.size(), theme.selection);
+    }
+}
+
+fn on_select(
+    trigger: Trigger<OnAdd, Selected>,
+    mut cmd: Commands,
+    mut selection: ResMut<Selection>,
+    nodes: Query<(), With<GraphNode>>,
+    material: Res<RingMaterial>,
+) {
+    let id = trigger.target();
+    selection.0.push(id);
+    if !nodes.contains(id) {
+        return;
+    }
+    cmd.entity(id).with_child((
+        SelectionRing,
+        // Meshed by `fit_rings` once the node's radius is known.
+        Mesh2d::default(),
+        MeshMaterial2d(material.0.clone()),
+        Transform::from_xyz(0., 0., -0.5),
+        Pickable::IGNORE,
+    ));
+}
+
+fn on_deselect(
+    trigger: Trigger<OnRemove, Selected>,
+    mut cmd: Commands,
+    mut selection: ResMut<Selection>,
+    children: Query<&Children>,
+    rings: Query<(), With<SelectionRing>>,
+) {
+    let id = trigger.target();
+    selection.0.retain(|&s| s != id);
+    for &child in children.get(id).into_iter().flatten() {
+        if rings.contains(child) {
+            cmd.entity(child).try_despawn();
+        }
+    }
+}
+
+/// Remeshes rings that are new or whose node changed size.
+fn fit_rings(
+    nodes: Query<(Ref<NodeRadius>, &Children), With<Selected>>,
+    mut rings: Query<(&mut Mesh2d, Ref<SelectionRing>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (radius, children) in &nodes {
+        for &child in children {
+            let Ok((mut mesh, ring)) = rings.get_mut(child) else {
+                continue;
+            };
+            if ring.is_added() || radius.is_changed() {
+                mesh.0 = meshes.add(Annulus::new(radius.0, radius.0 + RING_WIDTH));
+            }
+        }
+    }
+}
+
+/// Traces selected edges over their stroke.
+fn draw_selected_edges(
+    mut gizmos: Gizmos,
+    edges: Query<&EdgePath, (With<Edge>, With<Selected>)>,
+    theme: Res<Theme>,
+) {
+    for path in &edges {
+        gizmos.linestrip_2d(path.0.iter().copied(), theme.selection);
+    }
+}
+
+fn sync_ring_color(
+    theme: Res<Theme>,
+    material: Res<RingMaterial>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if let Some(material) = materials.get_mut(&material.0) {
+        material.color = theme.selection;
+    }
+}
@@ -0,0 +1,230 @@
+use avian2d::{math::PI, prelude::*};
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+use rand::Rng;
+
+use crate::{
+    config::{Config, LayoutRng},
+    graph::{
+        Attributes, Charge, Edge, EdgeDirection, EdgeLabel, Graph, GraphNode, NodeId, NodeLabel,
+        NodeRadius,
+    },
+    layout::spectral::{Placement, spectral_layout},
+    render::{shapes::NodeShape, shared::NodeHandles},
+    spec::{EdgeSpec, GraphSpec, NodeSpec},
+};
+
+pub(crate) fn setup(mut spawner: GraphSpawner, spec: Res<GraphSpec>) {
+    spawner.spawn(&spec);
+}
+
+/// Where [`Placement`] starts the nodes of `spec` without a position of
+/// their own, in order; empty for random placement.
+pub(crate) fn seed_positions(
+    spec: &GraphSpec,
+    placement: Placement,
+    ideal_length: f32,
+) -> Vec<Vec2> {
+    match placement {
+        Placement::Random => Vec::new(),
+        Placement::Spectral => spectral_layout(spec, ideal_length),
+    }
+}
+
+/// Spawns graph nodes and edges with the physics components the layout needs.
+#[derive(SystemParam)]
+pub(crate) struct GraphSpawner<'w, 's> {
+    pub cmd: Commands<'w, 's>,
+    // Missing in headless apps, which spawn nodes without meshes.
+    meshes: Option<ResMut<'w, Assets<Mesh>>>,
+    materials: Option<ResMut<'w, Assets<ColorMaterial>>>,
+    shared: Option<ResMut<'w, NodeHandles>>,
+    config: Res<'w, Config>,
+    graph: ResMut<'w, Graph>,
+    rng: ResMut<'w, LayoutRng>,
+}
+
+impl GraphSpawner<'_, '_> {
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn spawn(&mut self, spec: &GraphSpec) -> HashMap<String, Entity> {
+        self.set_directed(spec.directed);
+        let seeds = seed_positions(spec, self.config.placement, self.config.ideal_length);
+        let mut ids = HashMap::default();
+        self.spawn_nodes(&spec.nodes, &seeds, &mut ids);
+        self.spawn_edges(&spec.edges, &ids);
+        ids
+    }
+
+    pub fn set_directed(&mut self, directed: bool) {
+        self.graph.directed = directed;
+    }
+
+    /// Spawns `nodes`, each without a position of its own at its entry in
+    /// `seeds` if there is one, and records their entities in `ids`.
+    pub fn spawn_nodes(
+        &mut self,
+        nodes: &[NodeSpec],
+        seeds: &[Vec2],
+        ids: &mut HashMap<String, Entity>,
+    ) {
+        for (i, node) in nodes.iter().enumerate() {
+            let id = match (node.position, seeds.get(i)) {
+                (None, Some(&seed)) => self.spawn_node_at(node, seed),
+                _ => self.spawn_node(node),
+            };
+            ids.insert(node.id.clone(), id);
+        }
+    }
+
+    /// Spawns `edges` between the nodes in `ids`.
+    pub fn spawn_edges(&mut self, edges: &[EdgeSpec], ids: &HashMap<String, Entity>) {
+        for edge in edges {
+            let (Some(&source), Some(&target)) = (ids.get(&edge.source), ids.get(&edge.target))
+            else {
+                warn!(
+                    "skipping edge {} -> {}: unknown endpoint",
+                    edge.source, edge.target
+                );
+                continue;
+            };
+            self.spawn_edge_spec(edge, source, target);
+        }
+    }
+
+    /// Adds `spec` to the live graph. Nodes whose ID is taken stand for the
+    /// existing node, so edges can join new nodes to old ones.
+    pub fn merge(&mut self, spec: &GraphSpec) {
+        let mut ids: HashMap<String, Entity> = HashMap::default();
+        for node in &spec.nodes {
+            if self.graph.node_by_id(&node.id).is_none() {
+                let id = self.spawn_node(node);
+                ids.insert(node.id.clone(), id);
+            }
+        }
+        for edge in &spec.edges {
+            let find = |id: &String| ids.get(id).copied().or(self.graph.node_by_id(id));
+            let (Some(source), Some(target)) = (find(&edge.source), find(&edge.target)) else {
+                warn!(
+                    "skipping edge {} -> {}: unknown endpoint",
+                    edge.source, edge.target
+                );
+                continue;
+            };
+            self.spawn_edge_spec(edge, source, target);
+        }
+    }
+
+    pub fn spawn_node(&mut self, node: &NodeSpec) -> Entity {
+        let id = self.cmd.spawn_empty().id();
+        self.insert_node(id, node)
+    }
+
+    /// Like [`spawn_node`](Self::spawn_node), ignoring `node.position`.
+    pub fn spawn_node_at(&mut self, node: &NodeSpec, pos: Vec2) -> Entity {
+        let id = self.cmd.spawn_empty().id();
+        self.insert_node_at(id, node, pos)
+    }
+
+    /// Like [`spawn_node`](Self::spawn_node), into an entity that's already
+    /// been reserved.
+    pub fn insert_node(&mut self, entity: Entity, node: &NodeSpec) -> Entity {
+        let pos = node.position.unwrap_or_else(|| {
+            let angle = self.rng.gen_range(-PI..=PI);
+            // get screen size, max space btwn
+            let distance = self.rng.gen_range(0. ..500.);
+            Vec2::from_angle(angle) * distance
+        });
+        self.insert_node_at(entity, node, pos)
+    }
+
+    fn insert_node_at(&mut self, entity: Entity, node: &NodeSpec, pos: Vec2) -> Entity {
+        let damping = 1. / self.config.cooling_factor;
+        let attributes = Attributes(node.attributes.clone());
+        let radius = attributes.get_f32("size").unwrap_or(5.);
+        let shape = attributes
+            .get("shape")
+            .and_then(NodeShape::from_name)
+            .unwrap_or_default();
+        let label = attributes.get("label").map(|l| NodeLabel(l.to_string()));
+        let charge = attributes.get_f32("charge").map(Charge);
+        let mut entity = self.cmd.entity(entity);
+        entity.insert((
+            GraphNode,
+            NodeId(node.id.clone()),
+            attributes,
+            NodeRadius(radius),
+            shape,
+            Transform::from_translation(Vec3::new(pos.x, pos.y, 0.)),
+            RigidBody::Dynamic,
+            shape.collider(radius),
+            // Only circles could roll; other shapes would spin on contact.
+            LockedAxes::ROTATION_LOCKED,
+            Mass(self.config.node_mass),
+            LinearVelocity::default(),
+            MaxLinearSpeed::default(),
+            ExternalForce::ZERO,
+            LinearDamping(damping),
+        ));
+        if let (Some(meshes), Some(materials), Some(shared)) =
+            (&mut self.meshes, &mut self.materials, &mut self.shared)
+        {
+            entity.insert((
+                Mesh2d(shared.mesh(meshes, shape, radius)),
+                // Colored by `ColorMapping` once spawned.
+                MeshMaterial2d(shared.material(materials, Color::WHITE)),
+            ));
+        }
+        if let Some(label) = label {
+            entity.insert(label);
+        }
+        if let Some(charge) = charge {
+            entity.insert(charge);
+        }
+        entity.id()
+    }
+
+    /// Spawns `edge` between `source` and `target` with its label, attributes
+    /// and, in a directed graph, its direction.
+    pub fn spawn_edge_spec(&mut self, edge: &EdgeSpec, source: Entity, target: Entity) -> Entity {
+        let id = self.spawn_edge(Edge::new(source, target).with_weight(edge.weight));
+        // Mixed Pajek and GEXF files tag their undirected edges.
+        if self.graph.directed
+            && edge.attributes.get("directed").map(String::as_str) != Some("false")
+        {
+            self.cmd.entity(id).insert(EdgeDirection::Forward);
+        }
+        if let Some(label) = edge.attributes.get("label") {
+            self.cmd.entity(id).insert(EdgeLabel(label.clone()));
+        }
+        if !edge.attributes.is_empty() {
+            self.cmd
+                .entity(id)
+                .insert(Attributes(edge.attributes.clone()));
+        }
+        id
+    }
+
+    pub fn spawn_edge(&mut self, edge: Edge) -> Entity {
+        let id = self.cmd.spawn_empty().id();
+        self.insert_edge(id, edge)
+    }
+
+    /// Like [`spawn_edge`](Self::spawn_edge), into an entity that's already
+    /// been reserved. Self-loops get no joint, having nothing to pull
+    /// together.
+    pub fn insert_edge(&mut self, entity: Entity, edge: Edge) -> Entity {
+        let mut entity = self.cmd.entity(entity);
+        entity.insert(edge);
+        if edge.source != edge.target {
+            let (length, compliance) = self.config.joint(edge.weight);
+            entity.insert(
+                DistanceJoint::new(edge.source, edge.target)
+                    .with_rest_length(length)
+                    .with_compliance(compliance),
+            );
+        }
+        entity.id()
+    }
+}
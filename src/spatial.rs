@@ -0,0 +1,83 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    config::Config,
+    graph::{GraphNode, NodeRadius},
+};
+
+/// Uniform hash grid over node positions, rebuilt every frame before the
+/// layout and interaction systems run. Cells are `Config::ideal_length` wide,
+/// so a node's direct neighbours are usually within one cell of it.
+#[derive(Resource, Default, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+    max_radius: f32,
+}
+
+impl SpatialGrid {
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Largest [`NodeRadius`] in the grid, so a search this far around a
+    /// point finds every node covering it.
+    pub fn max_radius(&self) -> f32 {
+        self.max_radius
+    }
+
+    pub fn cell(&self, pos: Vec2) -> IVec2 {
+        (pos / self.cell_size).floor().as_ivec2()
+    }
+
+    /// `(node, position)` pairs stored in `cell`.
+    pub fn in_cell(&self, cell: IVec2) -> &[(Entity, Vec2)] {
+        self.cells.get(&cell).map_or(&[], Vec::as_slice)
+    }
+
+    /// `(node, position)` pairs within `radius` of `pos`.
+    pub fn within(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell(pos - radius);
+        let max = self.cell(pos + radius);
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .flat_map(|cell| self.in_cell(cell).iter().copied())
+            .filter(move |&(_, p)| p.distance_squared(pos) <= radius * radius)
+    }
+
+    /// Closest node to `pos` that is no further than `radius` away.
+    pub fn nearest(&self, pos: Vec2, radius: f32) -> Option<Entity> {
+        self.within(pos, radius)
+            .min_by(|(_, a), (_, b)| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+            .map(|(id, _)| id)
+    }
+
+    fn rebuild(&mut self, cell_size: f32, nodes: impl IntoIterator<Item = (Entity, Vec2)>) {
+        self.cell_size = cell_size.max(f32::EPSILON);
+        self.cells.clear();
+        for (id, pos) in nodes {
+            let cell = self.cell(pos);
+            self.cells.entry(cell).or_default().push((id, pos));
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SpatialGrid>()
+        .add_systems(PreUpdate, update_grid);
+}
+
+pub(crate) fn update_grid(
+    mut grid: ResMut<SpatialGrid>,
+    nodes: Query<(Entity, &Transform, Option<&NodeRadius>), With<GraphNode>>,
+    config: Res<Config>,
+) {
+    grid.rebuild(
+        config.ideal_length,
+        nodes.iter().map(|(id, t, _)| (id, t.translation.xy())),
+    );
+    grid.max_radius = nodes
+        .iter()
+        .filter_map(|(.., r)| r.map(|r| r.0))
+        .fold(0., f32::max);
+}
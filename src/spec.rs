@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+
+/// Declarative description of a graph to lay out. Insert it as a resource
+/// before startup and [`GraphLayoutPlugin`](crate::GraphLayoutPlugin) spawns
+/// one [`GraphNode`](crate::GraphNode) per node and one
+/// [`Edge`](crate::Edge) plus joint per edge.
+#[derive(Resource, Default, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphSpec {
+    pub nodes: Vec<NodeSpec>,
+    pub edges: Vec<EdgeSpec>,
+    /// Whether edges run from `source` to `target` rather than both ways.
+    pub directed: bool,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeSpec {
+    pub id: String,
+    /// Initial position; nodes without one are scattered randomly.
+    pub position: Option<Vec2>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeSpec {
+    pub source: String,
+    pub target: String,
+    pub weight: f32,
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl NodeSpec {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            position: None,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    pub fn at(self, position: Vec2) -> Self {
+        Self {
+            position: Some(position),
+            ..self
+        }
+    }
+
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl EdgeSpec {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            target: target.into(),
+            weight: 1.,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_weight(self, weight: f32) -> Self {
+        Self { weight, ..self }
+    }
+
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl GraphSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn directed(self) -> Self {
+        Self {
+            directed: true,
+            ..self
+        }
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, crate::io::GraphIoError> {
+        crate::io::load(path)
+    }
+
+    pub fn node(mut self, node: impl Into<NodeSpec>) -> Self {
+        self.nodes.push(node.into());
+        self
+    }
+
+    pub fn edge(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        self.edges.push(EdgeSpec::new(source, target));
+        self
+    }
+
+    pub fn weighted_edge(
+        mut self,
+        source: impl Into<String>,
+        target: impl Into<String>,
+        weight: f32,
+    ) -> Self {
+        self.edges
+            .push(EdgeSpec::new(source, target).with_weight(weight));
+        self
+    }
+
+    /// Creates a bare node for every edge endpoint that isn't declared.
+    pub fn add_missing_nodes(&mut self) {
+        let mut known: HashSet<String> = self.nodes.iter().map(|n| n.id.clone()).collect();
+        for edge in &self.edges {
+            for id in [&edge.source, &edge.target] {
+                if known.insert(id.clone()) {
+                    self.nodes.push(NodeSpec::new(id.clone()));
+                }
+            }
+        }
+    }
+}
+
+impl From<&str> for NodeSpec {
+    fn from(id: &str) -> Self {
+        NodeSpec::new(id)
+    }
+}
+
+impl From<String> for NodeSpec {
+    fn from(id: String) -> Self {
+        NodeSpec::new(id)
+    }
+}
@@ -0,0 +1,89 @@
+use avian2d::prelude::*;
+use bevy::{ecs::entity_disabling::Disabled, platform::collections::HashMap, prelude::*};
+
+use crate::graph::{Edge, Graph, GraphNode};
+
+/// Summary figures for the graph, refreshed whenever its topology changes.
+/// `energy` is refreshed every frame.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GraphStats {
+    pub nodes: usize,
+    pub edges: usize,
+    /// Fraction of possible edges present, counting ordered pairs in a
+    /// directed graph and unordered ones otherwise.
+    pub density: f32,
+    pub average_degree: f32,
+    pub components: usize,
+    /// How many nodes have each degree, indexed by degree. Disabled nodes
+    /// and edges are left out, so it follows filters and the timeline.
+    pub degree_histogram: Vec<usize>,
+    /// Modularity of the last [`DetectCommunities`](crate::DetectCommunities)
+    /// run, until the graph changes.
+    pub modularity: Option<f32>,
+    /// Total kinetic energy of the nodes, which settles towards zero as the
+    /// layout converges.
+    pub energy: f32,
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<GraphStats>().add_systems(
+        PostUpdate,
+        (
+            count.run_if(resource_changed::<Graph>),
+            count_degrees.run_if(
+                resource_changed::<Graph>
+                    .or(any_match_filter::<Added<Disabled>>)
+                    .or(any_component_removed::<Disabled>),
+            ),
+            measure_energy,
+        )
+            .after(PhysicsSet::Sync),
+    );
+}
+
+fn count(graph: Res<Graph>, mut stats: ResMut<GraphStats>) {
+    let (n, m) = (graph.node_count(), graph.edge_count());
+    let pairs = (n * n.saturating_sub(1)) as f32;
+    let pairs = if graph.is_directed() {
+        pairs
+    } else {
+        pairs / 2.
+    };
+    stats.nodes = n;
+    stats.edges = m;
+    stats.density = if pairs > 0. { m as f32 / pairs } else { 0. };
+    stats.average_degree = if n > 0 { 2. * m as f32 / n as f32 } else { 0. };
+    stats.components = graph.components().len();
+    stats.modularity = None;
+}
+
+fn count_degrees(
+    nodes: Query<Entity, With<GraphNode>>,
+    edges: Query<&Edge>,
+    mut stats: ResMut<GraphStats>,
+) {
+    let mut degrees: HashMap<Entity, usize> = nodes.iter().map(|id| (id, 0)).collect();
+    for edge in &edges {
+        if !degrees.contains_key(&edge.source) || !degrees.contains_key(&edge.target) {
+            continue;
+        }
+        for end in [edge.source, edge.target] {
+            *degrees.entry(end).or_default() += 1;
+        }
+    }
+    let mut histogram = vec![0; degrees.values().max().map_or(0, |&d| d + 1)];
+    for degree in degrees.into_values() {
+        histogram[degree] += 1;
+    }
+    stats.degree_histogram = histogram;
+}
+
+fn measure_energy(
+    nodes: Query<(&Mass, &LinearVelocity), With<GraphNode>>,
+    mut stats: ResMut<GraphStats>,
+) {
+    stats.energy = nodes
+        .iter()
+        .map(|(mass, velocity)| 0.5 * mass.0 * velocity.length_squared())
+        .sum();
+}
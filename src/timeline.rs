@@ -0,0 +1,260 @@
+use bevy::{
+    ecs::{entity_disabling::Disabled, system::SystemParam},
+    platform::collections::HashSet,
+    prelude::*,
+};
+
+use crate::{
+    collapse::CollapsedInto,
+    filter::{FilteredOut, NodeFilter},
+    graph::{Attributes, Edge, GraphNode, IncludeDisabled},
+    layout::registry::LayoutRegistry,
+};
+
+/// When a node or edge exists, from its `start` and `end` attributes, either
+/// of which may be left out, or from `spells`, a space-separated list of
+/// `start:end` spans for elements that come and go. Times are numbers or
+/// ISO dates, which count in days. Kept in step with the attributes.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Lifetime(pub Vec<(f64, f64)>);
+
+impl Lifetime {
+    pub fn from_attributes(attributes: &Attributes) -> Option<Self> {
+        if let Some(spells) = attributes.get("spells") {
+            let spans = spells
+                .split_whitespace()
+                .filter_map(|span| {
+                    let (start, end) = span.split_once(':')?;
+                    Some((parse_time(start)?, parse_time(end)?))
+                })
+                .collect();
+            return Some(Self(spans));
+        }
+        let start = attributes.get("start").and_then(parse_time);
+        let end = attributes.get("end").and_then(parse_time);
+        (start.is_some() || end.is_some()).then(|| {
+            Self(vec![(
+                start.unwrap_or(f64::NEG_INFINITY),
+                end.unwrap_or(f64::INFINITY),
+            )])
+        })
+    }
+
+    pub fn contains(&self, time: f64) -> bool {
+        self.0
+            .iter()
+            .any(|&(start, end)| start <= time && time <= end)
+    }
+}
+
+/// A number, or an ISO date with an optional time of day as days since 1970.
+pub(crate) fn parse_time(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Ok(time) = value.parse() {
+        return Some(time);
+    }
+    let (date, clock) = value.split_once('T').unwrap_or((value, ""));
+    let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    // Howard Hinnant's days_from_civil.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era - 719_468) as f64;
+    let seconds: f64 = clock
+        .trim_end_matches('Z')
+        .split(':')
+        .zip([3600., 60., 1.])
+        .filter_map(|(part, scale)| Some(part.parse::<f64>().ok()? * scale))
+        .sum();
+    Some(days + seconds / 86_400.)
+}
+
+/// Playback over the [`Lifetime`]s in the graph: only nodes and edges alive
+/// at `time` are shown and laid out, the others are disabled along with
+/// their edges until their time comes, keeping their positions. Changing
+/// which elements are alive restarts the layout so it follows the
+/// structure.
+#[derive(Resource, Clone, Debug)]
+pub struct Timeline {
+    pub time: f64,
+    pub playing: bool,
+    /// Seconds a play-through from start to end takes.
+    pub duration: f32,
+    /// Whether playback starts over once it reaches the end.
+    pub looping: bool,
+    range: Option<(f64, f64)>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            time: 0.,
+            playing: false,
+            duration: 20.,
+            looping: false,
+            range: None,
+        }
+    }
+}
+
+impl Timeline {
+    /// Earliest and latest finite time in any [`Lifetime`], or `None` if
+    /// the graph isn't dynamic.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<Timeline>()
+        .add_systems(Update, (read_lifetimes, play, apply_timeline).chain());
+}
+
+fn read_lifetimes(
+    mut cmd: Commands,
+    elements: Query<(Entity, &Attributes, Option<&Lifetime>), Changed<Attributes>>,
+) {
+    for (id, attributes, current) in &elements {
+        match Lifetime::from_attributes(attributes) {
+            Some(lifetime) if current != Some(&lifetime) => {
+                cmd.entity(id).insert(lifetime);
+            }
+            None if current.is_some() => {
+                cmd.entity(id).remove::<Lifetime>();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn play(mut timeline: ResMut<Timeline>, time: Res<Time>) {
+    let Some((start, end)) = timeline.range.filter(|_| timeline.playing) else {
+        return;
+    };
+    let step = (end - start) * time.delta_secs_f64() / timeline.duration.max(0.01) as f64;
+    timeline.time += step;
+    if timeline.time > end {
+        if timeline.looping {
+            timeline.time = start;
+        } else {
+            timeline.time = end;
+            timeline.playing = false;
+        }
+    }
+}
+
+/// A node or edge's lifetime and whether it's disabled at the moment.
+type Timed = (Option<Ref<'static, Lifetime>>, Has<Disabled>);
+
+/// A node's lifetime, whether it's disabled and whether it's filtered out or
+/// collapsed.
+type TimedNode = (Entity, Timed, Has<FilteredOut>, Has<CollapsedInto>);
+
+/// Nodes that were just filtered out or collapsed.
+type NewlyHidden = Or<(Added<FilteredOut>, Added<CollapsedInto>)>;
+
+/// Why live nodes are disabled anyway: being [`FilteredOut`], unless the
+/// filter keeps ghosts, or collapsed into a meta-node.
+#[derive(SystemParam)]
+pub(crate) struct Hiding<'w, 's> {
+    filter: Res<'w, NodeFilter>,
+    added: Query<'w, 's, (), NewlyHidden>,
+    removed: (
+        RemovedComponents<'w, 's, FilteredOut>,
+        RemovedComponents<'w, 's, CollapsedInto>,
+    ),
+}
+
+impl Hiding<'_, '_> {
+    fn is_changed(&mut self) -> bool {
+        let removed = self.removed.0.read().count() + self.removed.1.read().count() > 0;
+        self.filter.is_changed() || !self.added.is_empty() || removed
+    }
+
+    fn hides(&self, filtered: bool, collapsed: bool) -> bool {
+        collapsed || (filtered && !self.filter.ghosts)
+    }
+}
+
+/// Refits the range when lifetimes change and disables whatever isn't
+/// alive at the current time or is [`Hiding`].
+pub(crate) fn apply_timeline(
+    mut cmd: Commands,
+    mut timeline: ResMut<Timeline>,
+    mut registry: ResMut<LayoutRegistry>,
+    nodes: Query<TimedNode, With<GraphNode>>,
+    edges: Query<(Entity, &Edge, Timed)>,
+    (mut removed, mut hiding): (RemovedComponents<Lifetime>, Hiding),
+    children: Query<&Children, IncludeDisabled>,
+) {
+    let lifetimes_changed = removed.read().count() > 0
+        || nodes
+            .iter()
+            .any(|(_, (l, _), ..)| l.is_some_and(|l| l.is_changed()))
+        || edges
+            .iter()
+            .any(|(_, _, (l, _))| l.is_some_and(|l| l.is_changed()));
+    if lifetimes_changed {
+        let range = nodes
+            .iter()
+            .filter_map(|(_, (l, _), ..)| l)
+            .chain(edges.iter().filter_map(|(_, _, (l, _))| l))
+            .map(Ref::into_inner)
+            .flat_map(|l| l.0.iter().flat_map(|&(start, end)| [start, end]))
+            .filter(|t| t.is_finite())
+            .fold(None, |range, t| {
+                Some(range.map_or((t, t), |(lo, hi): (f64, f64)| (lo.min(t), hi.max(t))))
+            });
+        if range != timeline.range {
+            timeline.range = range;
+            if let Some((start, end)) = range
+                && !(start..=end).contains(&timeline.time)
+            {
+                timeline.time = start;
+            }
+        }
+    } else if !timeline.is_changed() && !hiding.is_changed() {
+        return;
+    }
+
+    let time = timeline.time;
+    let alive = |lifetime: Option<Ref<Lifetime>>| lifetime.is_none_or(|l| l.contains(time));
+    let mut dead = HashSet::new();
+    let mut toggled = Vec::new();
+    for (id, (lifetime, disabled), filtered, collapsed) in &nodes {
+        let alive = alive(lifetime) && !hiding.hides(filtered, collapsed);
+        if !alive {
+            dead.insert(id);
+        }
+        if alive == disabled {
+            toggled.push((id, alive));
+        }
+    }
+    for (id, edge, (lifetime, disabled)) in &edges {
+        let alive = alive(lifetime) && !dead.contains(&edge.source) && !dead.contains(&edge.target);
+        if alive == disabled {
+            toggled.push((id, alive));
+        }
+    }
+    if toggled.is_empty() {
+        return;
+    }
+    for (id, alive) in toggled {
+        // Labels and edge strokes are children, which have to go along.
+        for id in [id].into_iter().chain(children.iter_descendants(id)) {
+            if alive {
+                cmd.entity(id).remove::<Disabled>();
+            } else {
+                cmd.entity(id).insert(Disabled);
+            }
+        }
+    }
+    registry.restart();
+}
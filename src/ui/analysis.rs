@@ -0,0 +1,129 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    analysis::{
+        centrality::{BetweennessProgress, ComputeBetweenness, ComputePageRank, PageRank},
+        community::DetectCommunities,
+        components::ComponentSettings,
+        cycles::Cycles,
+        spanning::{SpanningTree, SpanningTreeMode},
+        traversal::Traversal,
+    },
+    render::{
+        hulls::CommunityHulls,
+        shapes::ShapeMapping,
+        sizes::{NodeSizing, SizeBy},
+    },
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, analysis_panel);
+}
+
+/// Settings of the graph algorithms in the analysis panel.
+#[derive(SystemParam)]
+struct AnalysisSettings<'w> {
+    components: ResMut<'w, ComponentSettings>,
+    pagerank: ResMut<'w, PageRank>,
+    traversal: ResMut<'w, Traversal>,
+    tree: ResMut<'w, SpanningTree>,
+    cycles: ResMut<'w, Cycles>,
+    hulls: ResMut<'w, CommunityHulls>,
+}
+
+/// Buttons for the graph algorithms, which write their results to node
+/// attributes and map them to color or size, or single out parts of the
+/// graph.
+fn analysis_panel(
+    mut cmd: Commands,
+    mut contexts: EguiContexts,
+    settings: AnalysisSettings,
+    betweenness: Option<Res<BetweennessProgress>>,
+    mut sizing: ResMut<NodeSizing>,
+    mut shapes: ResMut<ShapeMapping>,
+) -> Result {
+    let AnalysisSettings {
+        mut components,
+        mut pagerank,
+        mut traversal,
+        mut tree,
+        mut cycles,
+        mut hulls,
+    } = settings;
+    egui::Window::new("Analysis").show(contexts.ctx_mut()?, |ui| {
+        let c = components.bypass_change_detection();
+        let changed = ui.checkbox(&mut c.color, "Color by component").changed()
+            | ui.checkbox(&mut c.separate, "Separate components")
+                .changed();
+        if changed {
+            components.set_changed();
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Detect communities").clicked() {
+                cmd.send_event(DetectCommunities);
+            }
+            let h = hulls.bypass_change_detection();
+            if ui.checkbox(&mut h.enabled, "Hulls").changed() {
+                hulls.set_changed();
+            }
+            let mut by_community = shapes.attribute.as_deref() == Some("community");
+            let toggled = ui
+                .checkbox(&mut by_community, "Shapes")
+                .on_hover_text("Give each community its own node shape")
+                .changed();
+            if toggled {
+                shapes.attribute = by_community.then(|| "community".into());
+            }
+        });
+        if let Some(progress) = betweenness {
+            ui.add(egui::ProgressBar::new(progress.0).text("Betweenness"));
+        } else if ui.button("Size by betweenness").clicked() {
+            cmd.send_event(ComputeBetweenness);
+            sizing.by = Some(SizeBy::Attribute("betweenness".into()));
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Size by PageRank").clicked() {
+                cmd.send_event(ComputePageRank);
+            }
+            let damping =
+                egui::Slider::new(&mut pagerank.bypass_change_detection().damping, 0. ..=1.)
+                    .text("Damping");
+            if ui.add(damping).changed() {
+                pagerank.set_changed();
+            }
+        });
+        ui.horizontal(|ui| {
+            let delay = egui::Slider::new(
+                &mut traversal.bypass_change_detection().step_delay,
+                0.01..=2.,
+            )
+            .logarithmic(true)
+            .text("Traversal step (s)");
+            if ui.add(delay).changed() {
+                traversal.set_changed();
+            }
+            if traversal.is_active() && ui.button("Stop").clicked() {
+                traversal.stop();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Spanning tree");
+            for (name, mode) in [
+                ("Off", SpanningTreeMode::Off),
+                ("Highlight", SpanningTreeMode::Highlight),
+                ("Only", SpanningTreeMode::Only),
+            ] {
+                let selected = tree.mode == mode;
+                if ui.selectable_label(selected, name).clicked() && !selected {
+                    tree.mode = mode;
+                }
+            }
+        });
+        let c = cycles.bypass_change_detection();
+        if ui.checkbox(&mut c.highlight, "Highlight cycles").changed() {
+            cycles.set_changed();
+        }
+    });
+    Ok(())
+}
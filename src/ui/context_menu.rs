@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+use bevy_cursor::CursorLocation;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use super::{MainCamera, set_hidden, with_edges};
+use crate::{
+    analysis::{
+        community::Community,
+        path::ShortestPath,
+        traversal::{StartTraversal, TraversalOrder},
+    },
+    camera::FocusNode,
+    collapse::{CollapseNodes, ExpandNode, MetaNode},
+    edit::{AddNode, DeleteElements, SetPinned},
+    graph::{Edge, Graph, GraphNode, Pinned},
+    selection::Selected,
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, context_menu)
+        .add_observer(open_context_menu);
+}
+
+/// What a right-click landed on.
+#[derive(Clone, Copy)]
+enum MenuTarget {
+    Node(Entity),
+    Edge(Entity),
+    Canvas,
+}
+
+/// Open context menu, with where it was opened on screen and in the world.
+#[derive(Resource)]
+struct ContextMenu {
+    target: MenuTarget,
+    screen: Vec2,
+    world: Vec2,
+}
+
+enum MenuAction {
+    Delete,
+    TogglePin,
+    Hide,
+    ExpandNeighbors,
+    PathFrom,
+    PathTo,
+    Traverse(TraversalOrder),
+    CollapseSelection,
+    CollapseCommunity,
+    Expand,
+    ClearPath,
+    AddNode,
+    Center,
+}
+
+/// What the context menu of a node offers depends on.
+type MenuNode = (
+    Entity,
+    Has<Pinned>,
+    Has<Selected>,
+    Option<&'static Community>,
+    Has<MetaNode>,
+);
+
+/// Right-clicks on edge parts bubble up to the edge, and clicks on empty
+/// space land on the window.
+fn open_context_menu(
+    trigger: Trigger<Pointer<Click>>,
+    mut cmd: Commands,
+    nodes: Query<(), With<GraphNode>>,
+    edges: Query<(), With<Edge>>,
+    windows: Query<(), With<Window>>,
+    cursor: Res<CursorLocation>,
+) {
+    if trigger.button != PointerButton::Secondary {
+        return;
+    }
+    let id = trigger.target();
+    let target = if nodes.contains(id) {
+        MenuTarget::Node(id)
+    } else if edges.contains(id) {
+        MenuTarget::Edge(id)
+    } else if windows.contains(id) {
+        MenuTarget::Canvas
+    } else {
+        return;
+    };
+    let Some(world) = cursor.world_position() else {
+        return;
+    };
+    cmd.insert_resource(ContextMenu {
+        target,
+        screen: trigger.pointer_location.position,
+        world,
+    });
+}
+
+fn context_menu(
+    mut cmd: Commands,
+    mut contexts: EguiContexts,
+    menu: Option<Res<ContextMenu>>,
+    graph: Res<Graph>,
+    nodes: Query<MenuNode, With<GraphNode>>,
+    mut path: ResMut<ShortestPath>,
+    mut cameras: MainCamera,
+) -> Result {
+    let Some(menu) = menu else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+    let mut action = None;
+    let area = egui::Area::new("context_menu".into())
+        .fixed_pos([menu.screen.x, menu.screen.y])
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::menu(ui.style()).show(ui, |ui| {
+                let mut item = |label: &str, choice: MenuAction| {
+                    if ui.button(label).clicked() {
+                        action = Some(choice);
+                    }
+                };
+                match menu.target {
+                    MenuTarget::Node(id) => {
+                        let Ok((_, pinned, selected, community, meta)) = nodes.get(id) else {
+                            return;
+                        };
+                        item(if pinned { "Unpin" } else { "Pin" }, MenuAction::TogglePin);
+                        item("Hide", MenuAction::Hide);
+                        item("Expand neighbours", MenuAction::ExpandNeighbors);
+                        item("Path from here", MenuAction::PathFrom);
+                        item("Path to here", MenuAction::PathTo);
+                        item(
+                            "Breadth-first from here",
+                            MenuAction::Traverse(TraversalOrder::BreadthFirst),
+                        );
+                        item(
+                            "Depth-first from here",
+                            MenuAction::Traverse(TraversalOrder::DepthFirst),
+                        );
+                        let many = nodes.iter().filter(|(_, _, s, ..)| *s).nth(1).is_some();
+                        if selected && many {
+                            item("Collapse selection", MenuAction::CollapseSelection);
+                        }
+                        if community.is_some() {
+                            item("Collapse community", MenuAction::CollapseCommunity);
+                        }
+                        if meta {
+                            item("Expand group", MenuAction::Expand);
+                        }
+                        item("Center camera", MenuAction::Center);
+                        item("Delete", MenuAction::Delete);
+                    }
+                    MenuTarget::Edge(_) => {
+                        item("Hide", MenuAction::Hide);
+                        item("Center camera", MenuAction::Center);
+                        item("Delete", MenuAction::Delete);
+                    }
+                    MenuTarget::Canvas => {
+                        item("Add node here", MenuAction::AddNode);
+                        item("Center camera here", MenuAction::Center);
+                        if path.source.is_some() || path.target.is_some() {
+                            item("Clear path", MenuAction::ClearPath);
+                        }
+                    }
+                }
+            });
+        });
+    let dismissed = ctx.input(|i| {
+        i.key_pressed(egui::Key::Escape)
+            || (i.pointer.primary_clicked() && !area.response.contains_pointer())
+    });
+    if action.is_some() || dismissed {
+        cmd.remove_resource::<ContextMenu>();
+    }
+
+    match (action, menu.target) {
+        (None, _) => {}
+        (Some(MenuAction::Delete), MenuTarget::Node(id) | MenuTarget::Edge(id)) => {
+            cmd.send_event(DeleteElements(vec![id]));
+        }
+        (Some(MenuAction::TogglePin), MenuTarget::Node(id)) => {
+            cmd.send_event(SetPinned {
+                node: id,
+                pinned: !nodes.get(id).is_ok_and(|(_, pinned, ..)| pinned),
+            });
+        }
+        (Some(MenuAction::Hide), MenuTarget::Node(id)) => {
+            set_hidden(&mut cmd, with_edges(&graph, id), true);
+        }
+        (Some(MenuAction::Hide), MenuTarget::Edge(id)) => set_hidden(&mut cmd, [id], true),
+        // Shows what was hidden by hand; the spanning tree and level of
+        // detail still have their say.
+        (Some(MenuAction::ExpandNeighbors), MenuTarget::Node(id)) => {
+            for (neighbor, edge) in graph.neighbors(id) {
+                set_hidden(&mut cmd, [edge, neighbor], false);
+                cmd.entity(neighbor).insert(Selected);
+            }
+        }
+        (Some(MenuAction::PathFrom), MenuTarget::Node(id)) => path.source = Some(id),
+        (Some(MenuAction::PathTo), MenuTarget::Node(id)) => path.target = Some(id),
+        (Some(MenuAction::ClearPath), _) => path.clear(),
+        (Some(MenuAction::Traverse(order)), MenuTarget::Node(root)) => {
+            cmd.send_event(StartTraversal { root, order });
+        }
+        (Some(MenuAction::CollapseSelection), _) => {
+            let selected = nodes.iter().filter(|(_, _, s, ..)| *s);
+            cmd.send_event(CollapseNodes(selected.map(|(id, ..)| id).collect()));
+        }
+        (Some(MenuAction::CollapseCommunity), MenuTarget::Node(id)) => {
+            let community = nodes.get(id).ok().and_then(|(.., c, _)| c);
+            let members = nodes
+                .iter()
+                .filter(|(.., c, _)| c.is_some() && *c == community);
+            cmd.send_event(CollapseNodes(members.map(|(id, ..)| id).collect()));
+        }
+        (Some(MenuAction::Expand), MenuTarget::Node(id)) => {
+            cmd.send_event(ExpandNode(id));
+        }
+        (Some(MenuAction::AddNode), _) => {
+            cmd.send_event(AddNode::at(menu.world));
+        }
+        (Some(MenuAction::Center), MenuTarget::Node(id)) => {
+            cmd.send_event(FocusNode(id));
+        }
+        (Some(MenuAction::Center), _) => {
+            if let Ok((_, mut transform, _)) = cameras.single_mut() {
+                transform.translation = menu.world.extend(transform.translation.z);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
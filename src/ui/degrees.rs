@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::stats::GraphStats;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, degree_panel);
+}
+
+/// Degree distribution as bars, or as points on log-log axes where power
+/// laws show up as straight lines.
+fn degree_panel(
+    mut contexts: EguiContexts,
+    stats: Res<GraphStats>,
+    mut log_log: Local<bool>,
+) -> Result {
+    egui::Window::new("Degrees")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.checkbox(&mut log_log, "Log-log");
+            let (response, painter) =
+                ui.allocate_painter(egui::vec2(240., 120.), egui::Sense::hover());
+            let rect = response.rect;
+            painter.rect_stroke(
+                rect,
+                0.,
+                ui.visuals().widgets.noninteractive.bg_stroke,
+                egui::StrokeKind::Inside,
+            );
+            let color = ui.visuals().selection.bg_fill;
+            let histogram = &stats.degree_histogram;
+            // Bars for linear axes, points at (log degree, log count) otherwise.
+            let points: Vec<(f32, f32)> = histogram
+                .iter()
+                .enumerate()
+                .filter(|&(degree, &count)| count > 0 && (!*log_log || degree > 0))
+                .map(|(degree, &count)| {
+                    if *log_log {
+                        ((degree as f32).ln(), (count as f32).ln())
+                    } else {
+                        (degree as f32, count as f32)
+                    }
+                })
+                .collect();
+            let max_x = points.iter().map(|p| p.0).fold(0., f32::max);
+            let max_y = points.iter().map(|p| p.1).fold(0., f32::max);
+            let to_screen = |x: f32, y: f32| {
+                let slots = if *log_log { max_x } else { max_x + 1. };
+                egui::pos2(
+                    rect.left() + x / slots.max(f32::EPSILON) * rect.width(),
+                    rect.bottom() - y / max_y.max(f32::EPSILON) * rect.height(),
+                )
+            };
+            for &(x, y) in &points {
+                if *log_log {
+                    painter.circle_filled(to_screen(x, y), 2.5, color);
+                } else {
+                    let top_left = to_screen(x, y);
+                    let bottom_right = to_screen(x + 1., 0.);
+                    let bar = egui::Rect::from_min_max(top_left, bottom_right)
+                        .shrink2(egui::vec2((bottom_right.x - top_left.x) * 0.1, 0.));
+                    painter.rect_filled(bar, 0., color);
+                }
+            }
+            let max_degree = histogram.len().saturating_sub(1);
+            let most = histogram.iter().max().copied().unwrap_or(0);
+            ui.label(format!(
+                "Degree 0 to {max_degree}, at most {most} nodes each"
+            ));
+        });
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    config::Config,
+    graph::{Edge, EdgeCompliance, EdgeLength, NodeId},
+    selection::Selection,
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, edge_inspector);
+}
+
+/// Weight and joint overrides of the most recently selected edge.
+fn edge_inspector(
+    mut cmd: Commands,
+    mut contexts: EguiContexts,
+    mut edges: Query<(&mut Edge, Option<&EdgeLength>, Option<&EdgeCompliance>)>,
+    ids: Query<&NodeId>,
+    selection: Res<Selection>,
+    config: Res<Config>,
+) -> Result {
+    let Some((id, Ok((mut edge, length, compliance)))) =
+        selection.last().map(|id| (id, edges.get_mut(id)))
+    else {
+        return Ok(());
+    };
+    let name = |node| ids.get(node).map_or("?", |id| id.0.as_str());
+    let (default_length, default_compliance) = config.joint(edge.weight);
+    egui::SidePanel::right("edge_inspector").show(contexts.ctx_mut()?, |ui| {
+        ui.heading(format!("{} → {}", name(edge.source), name(edge.target)));
+        let mut weight = edge.weight;
+        let slider = egui::Slider::new(&mut weight, 0.1..=10.)
+            .logarithmic(true)
+            .text("Weight");
+        if ui.add(slider).changed() {
+            edge.weight = weight;
+        }
+        ui.separator();
+        ui.label("Overrides");
+        if let Some(value) = override_row(
+            ui,
+            "Rest length",
+            length.map(|l| l.0),
+            default_length,
+            1. ..=500.,
+        ) {
+            match value {
+                Some(v) => cmd.entity(id).insert(EdgeLength(v)),
+                None => cmd.entity(id).remove::<EdgeLength>(),
+            };
+        }
+        if let Some(value) = override_row(
+            ui,
+            "Compliance",
+            compliance.map(|c| c.0),
+            default_compliance,
+            1e-5..=0.1,
+        ) {
+            match value {
+                Some(v) => cmd.entity(id).insert(EdgeCompliance(v)),
+                None => cmd.entity(id).remove::<EdgeCompliance>(),
+            };
+        }
+    });
+    Ok(())
+}
+
+/// Checkbox enabling an override plus a logarithmic slider for its value,
+/// which shows `default` while the override is off. Returns the new override
+/// if the user changed it.
+fn override_row(
+    ui: &mut egui::Ui,
+    name: &str,
+    current: Option<f32>,
+    default: f32,
+    range: std::ops::RangeInclusive<f32>,
+) -> Option<Option<f32>> {
+    let mut enabled = current.is_some();
+    let mut value = current.unwrap_or(default);
+    let (toggled, moved) = ui
+        .horizontal(|ui| {
+            let toggled = ui.checkbox(&mut enabled, name).changed();
+            let slider = egui::Slider::new(&mut value, range).logarithmic(true);
+            (toggled, ui.add_enabled(enabled, slider).changed())
+        })
+        .inner;
+    (toggled || moved).then_some(enabled.then_some(value))
+}
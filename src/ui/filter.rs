@@ -0,0 +1,184 @@
+use bevy::{ecs::entity_disabling::Disabled, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    analysis::community::Community,
+    ego::EgoFocus,
+    filter::{AttributeFilter, Comparison, FilteredOut, NodeFilter},
+    sample::{GraphSample, SampleMethod},
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, filter_panel);
+}
+
+/// Controls for hiding nodes by degree, attribute or community, for the
+/// ego-network focus and for sampling.
+fn filter_panel(
+    mut contexts: EguiContexts,
+    mut filter: ResMut<NodeFilter>,
+    mut ego: ResMut<EgoFocus>,
+    mut sample: ResMut<GraphSample>,
+    communities: Query<(&Community, Has<Disabled>)>,
+    filtered: Query<Has<Disabled>, With<FilteredOut>>,
+) -> Result {
+    let mut changed = false;
+    let f = filter.bypass_change_detection();
+    egui::Window::new("Filter")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Degree");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut f.min_degree).prefix("min "))
+                    .changed();
+                let mut capped = f.max_degree.is_some();
+                if ui.checkbox(&mut capped, "max").changed() {
+                    f.max_degree = capped.then_some(f.min_degree.max(1));
+                    changed = true;
+                }
+                if let Some(max) = &mut f.max_degree {
+                    changed |= ui.add(egui::DragValue::new(max)).changed();
+                }
+            });
+            let mut by_attribute = f.attribute.is_some();
+            if ui.checkbox(&mut by_attribute, "By attribute").changed() {
+                f.attribute = by_attribute.then(AttributeFilter::default);
+                changed = true;
+            }
+            if let Some(attribute) = &mut f.attribute {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut attribute.key).desired_width(80.))
+                        .changed();
+                    egui::ComboBox::from_id_salt("comparison")
+                        .width(40.)
+                        .selected_text(comparison_symbol(attribute.comparison))
+                        .show_ui(ui, |ui| {
+                            for comparison in [
+                                Comparison::Equal,
+                                Comparison::NotEqual,
+                                Comparison::Less,
+                                Comparison::Greater,
+                                Comparison::Contains,
+                            ] {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut attribute.comparison,
+                                        comparison,
+                                        comparison_symbol(comparison),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut attribute.value).desired_width(80.))
+                        .changed();
+                });
+            }
+            // Hidden communities stay listed so they can be shown again.
+            let mut all: Vec<usize> = communities.iter().map(|(c, _)| c.0).collect();
+            all.extend(f.hidden_communities.iter().copied());
+            all.sort_unstable();
+            all.dedup();
+            if !all.is_empty() {
+                ui.label("Communities");
+                egui::ScrollArea::vertical()
+                    .max_height(120.)
+                    .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for community in all {
+                                let mut shown = !f.hidden_communities.contains(&community);
+                                if ui.checkbox(&mut shown, community.to_string()).changed() {
+                                    if shown {
+                                        f.hidden_communities.remove(&community);
+                                    } else {
+                                        f.hidden_communities.insert(community);
+                                    }
+                                    changed = true;
+                                }
+                            }
+                        });
+                    });
+            }
+            ui.horizontal(|ui| {
+                let radius = egui::Slider::new(&mut ego.bypass_change_detection().radius, 1..=3)
+                    .text("Focus hops")
+                    .clamping(egui::SliderClamping::Always);
+                let radius = ui
+                    .add(radius)
+                    .on_hover_text("Double-click a node to focus on its neighbourhood");
+                if radius.changed() {
+                    ego.set_changed();
+                }
+                if ego.is_active() && ui.button("Leave focus").clicked() {
+                    ego.center = None;
+                }
+            });
+            ui.horizontal(|ui| {
+                let s = sample.bypass_change_detection();
+                let mut resampled = false;
+                egui::ComboBox::from_label("Sample")
+                    .selected_text(sample_method_name(s.method))
+                    .show_ui(ui, |ui| {
+                        for method in [
+                            None,
+                            Some(SampleMethod::RandomEdge),
+                            Some(SampleMethod::ForestFire),
+                            Some(SampleMethod::TopDegree),
+                        ] {
+                            resampled |= ui
+                                .selectable_value(&mut s.method, method, sample_method_name(method))
+                                .changed();
+                        }
+                    });
+                if s.is_active() {
+                    resampled |= ui
+                        .add(
+                            egui::DragValue::new(&mut s.size)
+                                .range(1..=usize::MAX)
+                                .speed(10.)
+                                .suffix(" nodes"),
+                        )
+                        .changed();
+                }
+                if resampled {
+                    sample.set_changed();
+                }
+            });
+            changed |= ui
+                .checkbox(&mut f.ghosts, "Keep as ghosts")
+                .on_hover_text("Fade filtered nodes out instead of removing them from the layout")
+                .changed();
+            ui.horizontal(|ui| {
+                ui.label(format!("{} filtered out", filtered.iter().count()));
+                if f.is_active() && ui.button("Show all").clicked() {
+                    f.clear();
+                    changed = true;
+                }
+            });
+        });
+    if changed {
+        filter.set_changed();
+    }
+    Ok(())
+}
+
+fn sample_method_name(method: Option<SampleMethod>) -> &'static str {
+    match method {
+        None => "Off",
+        Some(SampleMethod::RandomEdge) => "Random edges",
+        Some(SampleMethod::ForestFire) => "Forest fire",
+        Some(SampleMethod::TopDegree) => "Top degree",
+    }
+}
+
+fn comparison_symbol(comparison: Comparison) -> &'static str {
+    match comparison {
+        Comparison::Equal => "=",
+        Comparison::NotEqual => "≠",
+        Comparison::Less => "<",
+        Comparison::Greater => ">",
+        Comparison::Contains => "contains",
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    graph::GraphNode,
+    render::{
+        colors::ColorMapping,
+        hulls::{CommunityHull, NodeGroup},
+    },
+    selection::Selected,
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, groups_panel);
+}
+
+/// Named groups of nodes: the selection can be grouped, and each group
+/// recolored or ungrouped.
+fn groups_panel(
+    mut cmd: Commands,
+    mut contexts: EguiContexts,
+    mut groups: Query<(Entity, &mut NodeGroup), Without<CommunityHull>>,
+    selected: Query<Entity, (With<Selected>, With<GraphNode>)>,
+    mapping: Res<ColorMapping>,
+    mut name: Local<String>,
+) -> Result {
+    egui::Window::new("Groups")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut *name)
+                        .hint_text("Name")
+                        .desired_width(120.),
+                );
+                let members: Vec<Entity> = selected.iter().collect();
+                let group =
+                    ui.add_enabled(!members.is_empty(), egui::Button::new("Group selection"));
+                if group.clicked() {
+                    let n = groups.iter().count();
+                    let color = mapping
+                        .palette
+                        .get(n % mapping.palette.len().max(1))
+                        .copied()
+                        .unwrap_or(mapping.default_color);
+                    let name = match name.trim() {
+                        "" => format!("Group {}", n + 1),
+                        _ => std::mem::take(&mut *name),
+                    };
+                    cmd.spawn(NodeGroup {
+                        name,
+                        members,
+                        color,
+                    });
+                }
+            });
+            for (id, mut group) in &mut groups {
+                ui.horizontal(|ui| {
+                    let [r, g, b, a] = group.color.to_srgba().to_u8_array();
+                    let mut color = egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+                    if ui.color_edit_button_srgba(&mut color).changed() {
+                        let [r, g, b, a] = color.to_srgba_unmultiplied();
+                        group.color = Color::srgba_u8(r, g, b, a);
+                    }
+                    ui.label(format!("{} ({})", group.name, group.members.len()));
+                    if ui.small_button("Ungroup").clicked() {
+                        cmd.entity(id).despawn();
+                    }
+                });
+            }
+        });
+    Ok(())
+}
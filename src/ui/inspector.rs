@@ -0,0 +1,112 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use super::{set_hidden, with_edges};
+use crate::{
+    edit::{DeleteElements, SetAttribute, SetPinned},
+    graph::{Attributes, Graph, GraphNode, NodeId, NodeLabel, Pinned},
+    hidden::Hidden,
+    selection::Selection,
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, inspector);
+}
+
+type Inspected<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static NodeId,
+        Option<&'static NodeLabel>,
+        Option<&'static Attributes>,
+        &'static Transform,
+        &'static LinearVelocity,
+        Has<Pinned>,
+        Has<Hidden>,
+    ),
+    With<GraphNode>,
+>;
+
+/// Details of the most recently selected node, with editable attributes and
+/// buttons to pin, hide or delete it.
+fn inspector(
+    mut cmd: Commands,
+    mut contexts: EguiContexts,
+    nodes: Inspected,
+    selection: Res<Selection>,
+    graph: Res<Graph>,
+) -> Result {
+    let Some(Ok((id, node_id, label, attributes, transform, velocity, pinned, hidden))) =
+        selection.last().map(|id| nodes.get(id))
+    else {
+        return Ok(());
+    };
+    let degree = graph.degree(id);
+    egui::SidePanel::right("inspector").show(contexts.ctx_mut()?, |ui| {
+        ui.heading(&node_id.0);
+        egui::Grid::new("node").show(ui, |ui| {
+            let pos = transform.translation;
+            let rows = [
+                ("Label", label.map_or("", |l| &l.0).to_string()),
+                ("Degree", degree.to_string()),
+                ("Position", format!("({:.1}, {:.1})", pos.x, pos.y)),
+                (
+                    "Velocity",
+                    format!("({:.1}, {:.1})", velocity.x, velocity.y),
+                ),
+            ];
+            for (name, value) in rows {
+                ui.label(name);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+        if let Some(attributes) = attributes {
+            ui.separator();
+            egui::Grid::new("attributes").show(ui, |ui| {
+                for (key, value) in &attributes.0 {
+                    ui.label(key);
+                    // The edit lives in egui's memory until it's committed.
+                    let draft = ui.make_persistent_id(("attribute", id, key));
+                    let mut text = ui
+                        .data(|d| d.get_temp::<String>(draft))
+                        .unwrap_or_else(|| value.clone());
+                    let response = ui.text_edit_singleline(&mut text);
+                    if response.changed() {
+                        ui.data_mut(|d| d.insert_temp(draft, text.clone()));
+                    }
+                    if response.lost_focus() {
+                        ui.data_mut(|d| d.remove::<String>(draft));
+                        if text != *value {
+                            cmd.send_event(SetAttribute {
+                                node: id,
+                                key: key.clone(),
+                                value: Some(text),
+                            });
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button(if pinned { "Unpin" } else { "Pin" }).clicked() {
+                cmd.send_event(SetPinned {
+                    node: id,
+                    pinned: !pinned,
+                });
+            }
+            if ui.button(if hidden { "Show" } else { "Hide" }).clicked() {
+                set_hidden(&mut cmd, with_edges(&graph, id), !hidden);
+            }
+            if ui.button("Delete").clicked() {
+                cmd.send_event(DeleteElements(vec![id]));
+            }
+        });
+    });
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    camera::FitView,
+    drag::DragMode,
+    layout::{control::LayoutControl, registry::LayoutRegistry},
+    render::{bundling::EdgeBundling, theme::Theme},
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, layout_picker);
+}
+
+/// Switches between the registered layout algorithms, drag modes and themes,
+/// sets how strongly edges are bundled and frames the view.
+fn layout_picker(
+    mut contexts: EguiContexts,
+    mut registry: ResMut<LayoutRegistry>,
+    mut control: ResMut<LayoutControl>,
+    mut bundling: ResMut<EdgeBundling>,
+    mut theme: ResMut<Theme>,
+    mut mode: ResMut<DragMode>,
+    mut fit: EventWriter<FitView>,
+) -> Result {
+    let names: Vec<String> = registry.names().map(String::from).collect();
+    let mut selected = registry.active_name().unwrap_or_default().to_string();
+    egui::Window::new("Layout").show(contexts.ctx_mut()?, |ui| {
+        egui::ComboBox::from_id_salt("layout")
+            .selected_text(&selected)
+            .show_ui(ui, |ui| {
+                for name in names {
+                    ui.selectable_value(&mut selected, name.clone(), name);
+                }
+            });
+        ui.horizontal(|ui| {
+            let paused = control.is_paused();
+            if ui
+                .button(if paused { "Resume" } else { "Pause" })
+                .on_hover_text("Space")
+                .clicked()
+            {
+                control.toggle();
+            }
+            if ui.button("Step").on_hover_text(".").clicked() {
+                control.step();
+            }
+            if ui.button("Restart").on_hover_text("R").clicked() {
+                control.restart();
+            }
+            if registry.is_converged() {
+                ui.label("Converged");
+            }
+        });
+        ui.add(egui::Slider::new(&mut bundling.strength, 0. ..=1.).text("Edge bundling"));
+        ui.horizontal(|ui| {
+            if ui.button("Fit all").on_hover_text("Home").clicked() {
+                fit.write(FitView::All);
+            }
+            if ui.button("Fit selection").on_hover_text("F").clicked() {
+                fit.write(FitView::Selection);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Drag").on_hover_text("E toggles, Shift connects");
+            for (name, choice) in [("Move", DragMode::Move), ("Connect", DragMode::Connect)] {
+                if ui.selectable_label(*mode == choice, name).clicked() && *mode != choice {
+                    *mode = choice;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Theme");
+            for (name, preset) in [("Dark", Theme::dark()), ("Light", Theme::light())] {
+                let selected = *theme == preset;
+                if ui.selectable_label(selected, name).clicked() && !selected {
+                    *theme = preset;
+                }
+            }
+        });
+    });
+    if registry.active_name() != Some(selected.as_str()) {
+        registry.select(&selected);
+    }
+    Ok(())
+}
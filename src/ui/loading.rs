@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::io::{LoadProgress, LoadStage};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, loading_panel);
+}
+
+/// Progress of a graph file being loaded.
+fn loading_panel(mut contexts: EguiContexts, progress: Option<Res<LoadProgress>>) -> Result {
+    let Some(progress) = progress else {
+        return Ok(());
+    };
+    let text = match progress.stage {
+        LoadStage::Reading => "Reading",
+        LoadStage::Parsing => "Parsing",
+        LoadStage::Spawning => "Adding nodes and edges",
+    };
+    egui::Window::new("Loading")
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0., -48.))
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.add(
+                egui::ProgressBar::new(progress.done)
+                    .text(text)
+                    .animate(progress.stage == LoadStage::Parsing),
+            );
+        });
+    Ok(())
+}
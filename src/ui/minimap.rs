@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use super::MainCamera;
+use crate::{
+    graph::{Edge, GraphNode},
+    render::theme::Theme,
+};
+
+/// Side of the minimap in screen points.
+const MINIMAP_SIZE: f32 = 200.;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, minimap);
+}
+
+/// The whole graph in the bottom-right corner with the visible area outlined.
+/// Clicking or dragging on it moves the camera there.
+fn minimap(
+    mut contexts: EguiContexts,
+    nodes: Query<&Transform, With<GraphNode>>,
+    edges: Query<&Edge>,
+    mut cameras: MainCamera,
+    theme: Res<Theme>,
+) -> Result {
+    let Ok((camera, mut camera_transform, projection)) = cameras.single_mut() else {
+        return Ok(());
+    };
+    let Some(bounds) = nodes.iter().fold(None, |acc: Option<Rect>, t| {
+        let p = Rect::from_center_size(t.translation.xy(), Vec2::ZERO);
+        Some(acc.map_or(p, |a| a.union(p)))
+    }) else {
+        return Ok(());
+    };
+    let scale = match projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.,
+    };
+    let view = Rect::from_center_size(
+        camera_transform.translation.xy(),
+        camera.logical_viewport_size().unwrap_or_default() * scale,
+    );
+    let world = bounds.inflate(bounds.size().max_element() * 0.05 + 1.);
+    let fit = MINIMAP_SIZE / world.size().max_element();
+    let color = |c: Color| {
+        let [r, g, b, a] = c.to_srgba().to_u8_array();
+        egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+    };
+
+    egui::Area::new("minimap".into())
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10., -10.])
+        .show(contexts.ctx_mut()?, |ui| {
+            let (response, painter) = ui.allocate_painter(
+                egui::Vec2::splat(MINIMAP_SIZE),
+                egui::Sense::click_and_drag(),
+            );
+            let origin = response.rect.center();
+            let to_map = |p: Vec2| {
+                let p = (p - world.center()) * fit;
+                origin + egui::vec2(p.x, -p.y)
+            };
+            painter.rect_filled(response.rect, 4., egui::Color32::from_black_alpha(160));
+            for edge in &edges {
+                if let Ok([s, t]) = nodes.get_many([edge.source, edge.target]) {
+                    painter.line_segment(
+                        [to_map(s.translation.xy()), to_map(t.translation.xy())],
+                        (0.5, color(theme.edge)),
+                    );
+                }
+            }
+            for t in &nodes {
+                painter.circle_filled(to_map(t.translation.xy()), 1.5, color(theme.node));
+            }
+            painter.rect_stroke(
+                egui::Rect::from_two_pos(to_map(view.min), to_map(view.max)),
+                0.,
+                (1., color(theme.selection)),
+                egui::StrokeKind::Middle,
+            );
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let p = (pointer - origin) / fit;
+                let target = world.center() + Vec2::new(p.x, -p.y);
+                camera_transform.translation = target.extend(camera_transform.translation.z);
+            }
+        });
+    Ok(())
+}
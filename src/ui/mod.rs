@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiGlobalSettings, EguiPlugin};
+
+use crate::{
+    graph::{Graph, GraphNode},
+    hidden::Hidden,
+};
+
+mod analysis;
+mod context_menu;
+mod degrees;
+mod edge_inspector;
+mod filter;
+mod groups;
+mod inspector;
+mod layout;
+mod loading;
+mod minimap;
+mod search;
+mod settings;
+mod stats;
+mod timeline;
+mod tooltip;
+
+/// Egui windows for driving the graph: layout and physics settings,
+/// statistics, analysis, filters, node and edge inspectors, groups, search,
+/// the timeline, a minimap, a right-click menu and hover tooltips.
+///
+/// Adds [`EguiPlugin`] unless the app already has it, and has egui absorb
+/// input it uses, so typing in text boxes doesn't trigger the graph hotkeys.
+/// Add it alongside [`GraphLayoutPlugin`](crate::GraphLayoutPlugin).
+pub struct GraphUiPlugin;
+
+impl Plugin for GraphUiPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin::default());
+        }
+        app.world_mut()
+            .get_resource_or_init::<EguiGlobalSettings>()
+            .enable_absorb_bevy_input_system = true;
+        app.add_plugins((
+            layout::plugin,
+            settings::plugin,
+            stats::plugin,
+            analysis::plugin,
+            degrees::plugin,
+            filter::plugin,
+            groups::plugin,
+            inspector::plugin,
+            edge_inspector::plugin,
+            search::plugin,
+            timeline::plugin,
+            minimap::plugin,
+            context_menu::plugin,
+            tooltip::plugin,
+            loading::plugin,
+        ));
+    }
+}
+
+type MainCamera<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Camera, &'static mut Transform, &'static Projection),
+    (With<Camera2d>, Without<GraphNode>),
+>;
+
+/// Hides or shows nodes and edges on the user's behalf. Only [`Hidden`] is
+/// touched, so elements hidden for other reasons stay hidden.
+fn set_hidden(cmd: &mut Commands, elements: impl IntoIterator<Item = Entity>, hidden: bool) {
+    for id in elements {
+        if hidden {
+            cmd.entity(id).insert(Hidden);
+        } else {
+            cmd.entity(id).remove::<Hidden>();
+        }
+    }
+}
+
+/// A node and its edges, which are hidden and shown along with it.
+fn with_edges(graph: &Graph, node: Entity) -> impl Iterator<Item = Entity> + '_ {
+    graph.neighbors(node).map(|(_, edge)| edge).chain([node])
+}
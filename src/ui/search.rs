@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{camera::FocusNode, search::NodeSearch};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, search_box);
+}
+
+/// Fuzzy node search; Enter focuses the camera on the best match.
+fn search_box(
+    mut contexts: EguiContexts,
+    mut search: ResMut<NodeSearch>,
+    mut focus: EventWriter<FocusNode>,
+) -> Result {
+    egui::Window::new("Search").show(contexts.ctx_mut()?, |ui| {
+        let response = ui.text_edit_singleline(&mut search.bypass_change_detection().query);
+        if response.changed() {
+            search.set_changed();
+        }
+        if response.lost_focus()
+            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+            && let Some(best) = search.best()
+        {
+            focus.write(FocusNode(best));
+        }
+        if !search.query.is_empty() {
+            ui.label(format!("{} matches", search.matches().len()));
+        }
+    });
+    Ok(())
+}
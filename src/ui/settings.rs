@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    config::Config,
+    forces::{Cooling, CoolingSchedule},
+    render::{lod::LevelOfDetail, transitions::Transitions},
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, settings_panel);
+}
+
+/// Live-edits the physics settings. `Config` is only marked changed when a
+/// value actually moves, since every change refits all joints and bodies.
+fn settings_panel(
+    mut contexts: EguiContexts,
+    mut config: ResMut<Config>,
+    mut cooling: ResMut<Cooling>,
+    mut transitions: ResMut<Transitions>,
+    mut detail: ResMut<LevelOfDetail>,
+) -> Result {
+    let mut changed = false;
+    let c = config.bypass_change_detection();
+    egui::Window::new("Settings").show(contexts.ctx_mut()?, |ui| {
+        let sliders = [
+            egui::Slider::new(&mut c.ideal_length, 5. ..=200.).text("Ideal length"),
+            egui::Slider::new(&mut c.compliance, 1e-5..=0.1)
+                .logarithmic(true)
+                .text("Compliance"),
+            egui::Slider::new(&mut c.node_mass, 0.1..=50.)
+                .logarithmic(true)
+                .text("Node mass"),
+            egui::Slider::new(&mut c.cooling_factor, 0.01..=2.)
+                .logarithmic(true)
+                .text("Cooling (1 / damping)"),
+            egui::Slider::new(&mut c.repulsion_strength, 0. ..=500.).text("Repulsion"),
+            egui::Slider::new(&mut c.center_gravity, 0. ..=5.).text("Center gravity"),
+        ];
+        for slider in sliders {
+            changed |= ui.add(slider).changed();
+        }
+        ui.horizontal(|ui| {
+            changed |= ui.add(egui::DragValue::new(&mut c.seed)).changed();
+            ui.label("Seed");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Annealing");
+            for (name, schedule) in [
+                ("Linear", CoolingSchedule::Linear { iterations: 500 }),
+                ("Exponential", CoolingSchedule::default()),
+                ("Adaptive", CoolingSchedule::Adaptive { decay: 0.9 }),
+            ] {
+                let selected = cooling.schedule == schedule;
+                if ui.selectable_label(selected, name).clicked() && !selected {
+                    cooling.schedule = schedule;
+                }
+            }
+        });
+        ui.label(format!("Temperature {:.3}", cooling.temperature()));
+        ui.add(egui::Slider::new(&mut transitions.duration, 0. ..=3.).text("Transition (s)"));
+        ui.separator();
+        let simplified = detail.is_simplified();
+        let d = detail.bypass_change_detection();
+        let mut retuned = ui
+            .checkbox(&mut d.enabled, "Simplify when zoomed out or large")
+            .changed();
+        retuned |= ui
+            .add(
+                egui::Slider::new(&mut d.zoom_threshold, 1. ..=50.)
+                    .logarithmic(true)
+                    .text("Zoom threshold"),
+            )
+            .changed();
+        ui.horizontal(|ui| {
+            retuned |= ui
+                .add(egui::DragValue::new(&mut d.node_threshold))
+                .changed();
+            ui.label("Node threshold");
+        });
+        ui.horizontal(|ui| {
+            retuned |= ui
+                .add(egui::DragValue::new(&mut d.max_edges).range(1..=usize::MAX))
+                .changed();
+            ui.label("Edges drawn when simplified");
+        });
+        if simplified {
+            ui.label("Simplified");
+        }
+        if retuned {
+            detail.set_changed();
+        }
+    });
+    if changed {
+        config.set_changed();
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    analysis::{cycles::Cycles, path::ShortestPath},
+    stats::GraphStats,
+};
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, stats_panel);
+}
+
+fn stats_panel(
+    mut contexts: EguiContexts,
+    stats: Res<GraphStats>,
+    path: Res<ShortestPath>,
+    cycles: Res<Cycles>,
+) -> Result {
+    egui::Window::new("Statistics").show(contexts.ctx_mut()?, |ui| {
+        egui::Grid::new("stats").show(ui, |ui| {
+            let mut rows = vec![
+                ("Nodes", stats.nodes.to_string()),
+                ("Edges", stats.edges.to_string()),
+                ("Density", format!("{:.4}", stats.density)),
+                ("Average degree", format!("{:.2}", stats.average_degree)),
+                ("Components", stats.components.to_string()),
+                ("Energy", format!("{:.1}", stats.energy)),
+            ];
+            if let Some(modularity) = stats.modularity {
+                rows.push(("Modularity", format!("{modularity:.3}")));
+            }
+            if path.is_found() {
+                rows.push(("Path hops", path.hops().to_string()));
+                rows.push(("Path weight", format!("{:.2}", path.length())));
+            } else if path.source.is_some() && path.target.is_some() {
+                rows.push(("Path", "none".into()));
+            }
+            if cycles.highlight {
+                rows.push(("Cyclic groups", cycles.groups().len().to_string()));
+            }
+            for (name, value) in rows {
+                ui.label(name);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    });
+    Ok(())
+}
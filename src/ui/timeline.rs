@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::timeline::Timeline;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, timeline_panel);
+}
+
+/// Play controls and a slider over time, for graphs whose elements come and
+/// go.
+fn timeline_panel(mut contexts: EguiContexts, mut timeline: ResMut<Timeline>) -> Result {
+    let Some((start, end)) = timeline.range() else {
+        return Ok(());
+    };
+    let mut changed = false;
+    let t = timeline.bypass_change_detection();
+    egui::TopBottomPanel::bottom("timeline").show(contexts.ctx_mut()?, |ui| {
+        ui.horizontal(|ui| {
+            if ui
+                .button(if t.playing { "Pause" } else { "Play" })
+                .clicked()
+            {
+                // Playing from the end starts over.
+                if !t.playing && t.time >= end {
+                    t.time = start;
+                }
+                t.playing = !t.playing;
+                changed = true;
+            }
+            changed |= ui.checkbox(&mut t.looping, "Loop").changed();
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut t.duration)
+                        .range(1. ..=600.)
+                        .suffix(" s"),
+                )
+                .on_hover_text("Length of a play-through")
+                .changed();
+            ui.spacing_mut().slider_width = (ui.available_width() - 80.).max(100.);
+            changed |= ui
+                .add(egui::Slider::new(&mut t.time, start..=end))
+                .changed();
+        });
+    });
+    if changed {
+        timeline.set_changed();
+    }
+    Ok(())
+}
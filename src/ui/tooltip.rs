@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::graph::{Attributes, Graph, GraphNode, NodeId, NodeLabel};
+
+/// Seconds the cursor has to rest on a node before its tooltip shows.
+const TOOLTIP_DELAY: f32 = 0.4;
+
+/// Attributes listed in a tooltip; the inspector shows them all.
+const TOOLTIP_ATTRIBUTES: usize = 5;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(EguiPrimaryContextPass, tooltip)
+        .add_observer(start_hover)
+        .add_observer(end_hover);
+}
+
+/// Node under the cursor and when it got there.
+#[derive(Resource)]
+struct Hovered {
+    node: Entity,
+    since: f32,
+}
+
+fn start_hover(
+    trigger: Trigger<Pointer<Over>>,
+    mut cmd: Commands,
+    nodes: Query<(), With<GraphNode>>,
+    time: Res<Time>,
+) {
+    if nodes.contains(trigger.target()) {
+        cmd.insert_resource(Hovered {
+            node: trigger.target(),
+            since: time.elapsed_secs(),
+        });
+    }
+}
+
+fn end_hover(trigger: Trigger<Pointer<Out>>, mut cmd: Commands, hovered: Option<Res<Hovered>>) {
+    if hovered.is_some_and(|h| h.node == trigger.target()) {
+        cmd.remove_resource::<Hovered>();
+    }
+}
+
+/// Label, degree and the first few attributes of the hovered node, next to
+/// the cursor. Drawn by egui, so it keeps its size whatever the zoom.
+fn tooltip(
+    mut contexts: EguiContexts,
+    hovered: Option<Res<Hovered>>,
+    nodes: Query<(&NodeId, Option<&NodeLabel>, Option<&Attributes>)>,
+    graph: Res<Graph>,
+    time: Res<Time>,
+) -> Result {
+    let Some(hovered) = hovered.filter(|h| time.elapsed_secs() - h.since >= TOOLTIP_DELAY) else {
+        return Ok(());
+    };
+    let Ok((id, label, attributes)) = nodes.get(hovered.node) else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return Ok(());
+    };
+    egui::Area::new("tooltip".into())
+        .fixed_pos(pointer + egui::vec2(16., 16.))
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.strong(label.map_or(&id.0, |l| &l.0));
+                ui.label(format!("Degree {}", graph.degree(hovered.node)));
+                for (key, value) in attributes
+                    .into_iter()
+                    .flat_map(|a| &a.0)
+                    .filter(|(key, _)| key.as_str() != "label")
+                    .take(TOOLTIP_ATTRIBUTES)
+                {
+                    ui.label(format!("{key}: {value}"));
+                }
+            });
+        });
+    Ok(())
+}